@@ -28,6 +28,24 @@ impl Registry {
         Ok(())
     }
 
+    /// Registers a descriptor built at runtime, such as one compiled from a contract.
+    ///
+    /// Registered descriptors live for the rest of the process, so the box is leaked once
+    /// it is accepted; a rejected duplicate is dropped normally.
+    pub fn register_owned(
+        &self,
+        descriptor: Box<dyn CircuitDescriptor>,
+    ) -> Result<&'static dyn CircuitDescriptor> {
+        let mut entries = self.entries.write().expect("registry lock poisoned");
+        let name = descriptor.name();
+        if entries.contains_key(name) {
+            return Err(CircuitError::AlreadyRegistered(name.to_string()));
+        }
+        let descriptor: &'static dyn CircuitDescriptor = Box::leak(descriptor);
+        entries.insert(name, descriptor);
+        Ok(descriptor)
+    }
+
     pub fn get(&self, name: &str) -> Result<&'static dyn CircuitDescriptor> {
         self.entries
             .read()
@@ -153,6 +171,19 @@ mod tests {
         assert!(matches!(err, CircuitError::AlreadyRegistered(name) if name == "dummy_a"));
     }
 
+    #[test]
+    fn register_owned_is_listed_and_rejects_duplicates() {
+        let reg = Registry::new();
+        let d = reg.register_owned(Box::new(DummyA)).unwrap();
+        assert_eq!(d.name(), "dummy_a");
+        assert_eq!(reg.get("dummy_a").unwrap().default_k(), 4);
+
+        let err = reg.register(&DUMMY_A).unwrap_err();
+        assert!(matches!(err, CircuitError::AlreadyRegistered(_)));
+        let err = reg.register_owned(Box::new(DummyA)).err().unwrap();
+        assert!(matches!(err, CircuitError::AlreadyRegistered(_)));
+    }
+
     #[test]
     fn get_missing_returns_circuit_not_found() {
         let reg = Registry::new();
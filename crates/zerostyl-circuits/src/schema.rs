@@ -1,4 +1,10 @@
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use zerostyl_runtime::encoding::{
+    decode_address, decode_bytes32, encode_address, encode_bytes32, parse_hex, to_hex, FieldRepr,
+};
+
+use crate::error::{CircuitError, Result};
 
 /// Schema describing the witness inputs of a circuit.
 ///
@@ -23,11 +29,140 @@ pub struct WitnessField {
 pub enum FieldType {
     U64,
     U128,
+    /// Signed integer, offset-encoded in the circuit as `v + 2^63`.
+    I64,
     Bool,
     Bytes32,
     Address,
     Fp,
-    Array { kind: Box<FieldType>, len: usize },
+    Array {
+        kind: Box<FieldType>,
+        len: usize,
+    },
+}
+
+impl FieldType {
+    /// Number of field elements a witness of this type occupies in a circuit.
+    ///
+    /// `Bytes32` does not fit in one Pasta `Fp` and is split into two 128-bit limbs
+    /// (see [`zerostyl_runtime::encoding`]); every other scalar is a single element.
+    pub fn num_limbs(&self) -> usize {
+        match self {
+            FieldType::Bytes32 => 2,
+            FieldType::Array { kind, len } => len * kind.num_limbs(),
+            _ => 1,
+        }
+    }
+
+    /// Encodes a witness JSON value of this type into field limbs (little-endian reprs).
+    ///
+    /// Integers and booleans are decimal strings (`i64` may be negative), `bytes32` and
+    /// `address` are `0x`-prefixed big-endian hex as in EVM calldata, and `fp` is either form.
+    /// `fp` values are not reduced: a hex value at or above the field modulus is rejected by
+    /// the prover.
+    pub fn encode_witness(&self, value: &Value) -> Result<Vec<FieldRepr>> {
+        if let FieldType::Array { kind, len } = self {
+            let items = value.as_array().filter(|items| items.len() == *len).ok_or_else(|| {
+                CircuitError::InvalidWitness(format!("expected an array of {len} values"))
+            })?;
+            let mut limbs = Vec::with_capacity(self.num_limbs());
+            for item in items {
+                limbs.extend(kind.encode_witness(item)?);
+            }
+            return Ok(limbs);
+        }
+
+        let s = value.as_str().ok_or_else(|| {
+            CircuitError::InvalidWitness(format!("expected a string, got {value}"))
+        })?;
+        let invalid =
+            |e: &dyn std::fmt::Display| CircuitError::InvalidWitness(format!("'{s}': {e}"));
+        Ok(match self {
+            FieldType::U64 => vec![u128_repr(s.parse::<u64>().map_err(|e| invalid(&e))?.into())],
+            FieldType::U128 => vec![u128_repr(s.parse::<u128>().map_err(|e| invalid(&e))?)],
+            FieldType::I64 => {
+                let value = s.parse::<i64>().map_err(|e| invalid(&e))?;
+                vec![u128_repr(((value as u64) ^ (1 << 63)).into())]
+            }
+            FieldType::Bool => match s {
+                "0" | "false" => vec![u128_repr(0)],
+                "1" | "true" => vec![u128_repr(1)],
+                _ => return Err(invalid(&"expected 0, 1, true or false")),
+            },
+            FieldType::Bytes32 => encode_bytes32(&parse_hex(s).map_err(|e| invalid(&e))?).to_vec(),
+            FieldType::Address => vec![encode_address(&parse_hex(s).map_err(|e| invalid(&e))?)],
+            FieldType::Fp if s.starts_with("0x") => {
+                let mut repr: FieldRepr = parse_hex(s).map_err(|e| invalid(&e))?;
+                repr.reverse();
+                vec![repr]
+            }
+            FieldType::Fp => vec![u128_repr(s.parse::<u128>().map_err(|e| invalid(&e))?)],
+            FieldType::Array { .. } => unreachable!("arrays are handled above"),
+        })
+    }
+
+    /// Decodes field limbs produced by [`FieldType::encode_witness`] back into JSON.
+    ///
+    /// `bytes32` and `address` round-trip exactly (as lowercase hex); `fp` always decodes
+    /// to big-endian hex.
+    pub fn decode_witness(&self, limbs: &[FieldRepr]) -> Result<Value> {
+        if limbs.len() != self.num_limbs() {
+            return Err(CircuitError::InvalidWitness(format!(
+                "expected {} limb(s), got {}",
+                self.num_limbs(),
+                limbs.len()
+            )));
+        }
+        let invalid = |e: &dyn std::fmt::Display| CircuitError::InvalidWitness(e.to_string());
+        Ok(match self {
+            FieldType::U64 => Value::String(
+                u64::try_from(repr_u128(&limbs[0])?).map_err(|e| invalid(&e))?.to_string(),
+            ),
+            FieldType::U128 => Value::String(repr_u128(&limbs[0])?.to_string()),
+            FieldType::I64 => {
+                let encoded = u64::try_from(repr_u128(&limbs[0])?).map_err(|e| invalid(&e))?;
+                Value::String(((encoded ^ (1 << 63)) as i64).to_string())
+            }
+            FieldType::Bool => match repr_u128(&limbs[0])? {
+                0 => Value::String("0".into()),
+                1 => Value::String("1".into()),
+                other => return Err(invalid(&format!("{other} is not a boolean"))),
+            },
+            FieldType::Bytes32 => {
+                let word = decode_bytes32(&[limbs[0], limbs[1]]).map_err(|e| invalid(&e))?;
+                Value::String(to_hex(&word))
+            }
+            FieldType::Address => {
+                Value::String(to_hex(&decode_address(&limbs[0]).map_err(|e| invalid(&e))?))
+            }
+            FieldType::Fp => {
+                let mut be = limbs[0];
+                be.reverse();
+                Value::String(to_hex(&be))
+            }
+            FieldType::Array { kind, .. } => Value::Array(
+                limbs
+                    .chunks(kind.num_limbs())
+                    .map(|chunk| kind.decode_witness(chunk))
+                    .collect::<Result<_>>()?,
+            ),
+        })
+    }
+}
+
+fn u128_repr(value: u128) -> FieldRepr {
+    let mut repr = [0u8; 32];
+    repr[..16].copy_from_slice(&value.to_le_bytes());
+    repr
+}
+
+fn repr_u128(repr: &FieldRepr) -> Result<u128> {
+    if repr[16..].iter().any(|b| *b != 0) {
+        return Err(CircuitError::InvalidWitness("limb exceeds 128 bits".into()));
+    }
+    let mut bytes = [0u8; 16];
+    bytes.copy_from_slice(&repr[..16]);
+    Ok(u128::from_le_bytes(bytes))
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
@@ -96,4 +231,72 @@ mod tests {
         let json = serde_json::to_string(&f).unwrap();
         assert!(!json.contains("description"));
     }
+
+    #[test]
+    fn bytes32_witness_roundtrips_exactly() {
+        // Above the Pasta modulus, so it cannot be a single field element
+        let word = format!("0x{}", "ff".repeat(32));
+        let limbs = FieldType::Bytes32.encode_witness(&Value::String(word.clone())).unwrap();
+        assert_eq!(limbs.len(), 2);
+        assert_eq!(limbs[0][..16], [0xff; 16]);
+        assert_eq!(limbs[0][16..], [0; 16]);
+        assert_eq!(FieldType::Bytes32.decode_witness(&limbs).unwrap(), Value::String(word));
+
+        let word = "0x00112233445566778899aabbccddeeff0123456789abcdeffedcba9876543210";
+        let limbs = FieldType::Bytes32.encode_witness(&Value::String(word.into())).unwrap();
+        assert_eq!(FieldType::Bytes32.decode_witness(&limbs).unwrap(), Value::String(word.into()));
+    }
+
+    #[test]
+    fn address_and_scalar_witnesses_roundtrip() {
+        let cases = [
+            (FieldType::Address, "0x00000000000000000000000000000000deadbeef"),
+            (FieldType::U64, "18446744073709551615"),
+            (FieldType::U128, "340282366920938463463374607431768211455"),
+            (FieldType::I64, "-9223372036854775808"),
+            (FieldType::I64, "42"),
+            (FieldType::Bool, "1"),
+        ];
+        for (kind, value) in cases {
+            let limbs = kind.encode_witness(&Value::String(value.into())).unwrap();
+            assert_eq!(kind.decode_witness(&limbs).unwrap(), Value::String(value.into()));
+        }
+    }
+
+    #[test]
+    fn i64_witness_is_offset_encoded() {
+        let limbs = FieldType::I64.encode_witness(&Value::String("-1".into())).unwrap();
+        assert_eq!(limbs[0][..8], (i64::MAX as u64).to_le_bytes());
+        assert_eq!(limbs[0][8..], [0; 24]);
+    }
+
+    #[test]
+    fn array_of_bytes32_flattens_limbs() {
+        let kind = FieldType::Array { kind: Box::new(FieldType::Bytes32), len: 2 };
+        assert_eq!(kind.num_limbs(), 4);
+        let value =
+            serde_json::json!([format!("0x{}", "01".repeat(32)), format!("0x{}", "02".repeat(32))]);
+        let limbs = kind.encode_witness(&value).unwrap();
+        assert_eq!(limbs.len(), 4);
+        assert_eq!(kind.decode_witness(&limbs).unwrap(), value);
+    }
+
+    #[test]
+    fn malformed_witnesses_are_rejected() {
+        let bad = [
+            (FieldType::Bytes32, serde_json::json!("0x1234")),
+            (FieldType::Address, serde_json::json!(format!("0x{}", "00".repeat(32)))),
+            (FieldType::U64, serde_json::json!("-1")),
+            (FieldType::I64, serde_json::json!("9223372036854775808")),
+            (FieldType::Bool, serde_json::json!("2")),
+            (FieldType::U64, serde_json::json!(5)),
+        ];
+        for (kind, value) in bad {
+            assert!(kind.encode_witness(&value).is_err(), "{kind:?} accepted {value}");
+        }
+        let mut limbs = vec![[0u8; 32]; 2];
+        limbs[1][16] = 1;
+        assert!(FieldType::Bytes32.decode_witness(&limbs).is_err());
+        assert!(FieldType::Bytes32.decode_witness(&limbs[..1]).is_err());
+    }
 }
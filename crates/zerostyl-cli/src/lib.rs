@@ -186,6 +186,7 @@ fn render_field_type(t: &FieldType) -> String {
     match t {
         FieldType::U64 => "u64".into(),
         FieldType::U128 => "u128".into(),
+        FieldType::I64 => "i64".into(),
         FieldType::Bool => "bool".into(),
         FieldType::Bytes32 => "bytes32".into(),
         FieldType::Address => "address".into(),
@@ -204,7 +205,9 @@ fn render_witness_template(schema: &WitnessSchema) -> Value {
 
 fn example_value(t: &FieldType) -> Value {
     match t {
-        FieldType::U64 | FieldType::U128 | FieldType::Bool | FieldType::Fp => json!("0"),
+        FieldType::U64 | FieldType::U128 | FieldType::I64 | FieldType::Bool | FieldType::Fp => {
+            json!("0")
+        }
         FieldType::Bytes32 => json!(format!("0x{}", "00".repeat(32))),
         FieldType::Address => json!(format!("0x{}", "00".repeat(20))),
         FieldType::Array { kind, len } => {
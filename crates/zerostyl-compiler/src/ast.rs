@@ -4,18 +4,23 @@
 //! suitable for generating halo2 zk-SNARK circuits. It validates type compatibility
 //! and extracts metadata needed for circuit construction.
 
+use crate::circuit::expression_check_bits;
 use crate::error::{CompilerError, Result};
 use crate::parser::{ParsedContract, PrivateField};
+use quote::ToTokens;
+use serde::{Deserialize, Serialize};
+use zerostyl_runtime::encoding::{ADDRESS_BITS, BYTES32_LIMB_BITS};
 use zerostyl_runtime::CircuitConfig;
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct ZkField {
     pub name: String,
     pub field_type: ZkType,
     pub constraints: Vec<Constraint>,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum ZkType {
     U8,
     U16,
@@ -30,7 +35,8 @@ pub enum ZkType {
     Address,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum Constraint {
     /// Bit-decomposition range proof: value ∈ [0, 2^num_bits). Uses RangeProofChip.
     Range {
@@ -38,18 +44,26 @@ pub enum Constraint {
     },
     /// Boolean constraint: value ∈ {0, 1}. Uses RangeProofChip with 1 bit.
     Boolean,
-    /// Legacy range proof with explicit min/max bounds (for types without a gadget, e.g. u128).
+    /// Bounded range proof: value ∈ [min, max]. Uses RangeProofChip's bounded check, one
+    /// 64-bit limb at a time for bounds wider than 64 bits (e.g. u128).
     RangeProof {
         min: u128,
         max: u128,
     },
+    /// Comparison against a constant: `value <op> value`. Signed fields store the constant
+    /// offset-encoded, like their witnesses.
     Comparison {
         operator: ComparisonOp,
-        value: u64,
+        value: u128,
     },
     Commitment {
         hash_type: HashType,
     },
+    /// `lhs_field = rhs_fields[0] ∘ rhs_fields[1] ∘ …`, evaluated left to right.
+    ///
+    /// Each operand after the first is folded into the running value with the op paired
+    /// with it; the op paired with the first operand is ignored. `operator` names the
+    /// relation's primary operation and does not affect synthesis.
     ArithmeticRelation {
         lhs_field: String,
         operator: ArithOp,
@@ -59,11 +73,85 @@ pub enum Constraint {
         leaf_field: String,
         path_field: String,
         root_field: String,
+        /// Field holding the left/right direction bit for each level.
+        indices_field: String,
         tree_depth: usize,
     },
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// Maximum number of scalar cells a single array field may flatten to.
+pub const MAX_ARRAY_ELEMENTS: usize = 1024;
+
+impl ZkType {
+    /// Number of scalar cells this type occupies once flattened into the circuit.
+    ///
+    /// Scalars occupy one cell, except `Bytes32`, which is split into two 128-bit limbs
+    /// (see [`zerostyl_runtime::encoding`]); arrays occupy `size` times the cells of their
+    /// element type.
+    pub fn num_elements(&self) -> usize {
+        match self {
+            ZkType::Array { element_type, size } => size * element_type.num_elements(),
+            ZkType::Bytes32 => 2,
+            _ => 1,
+        }
+    }
+
+    /// Innermost scalar type of a (possibly nested) array, or the type itself.
+    pub fn scalar_type(&self) -> &ZkType {
+        match self {
+            ZkType::Array { element_type, .. } => element_type.scalar_type(),
+            other => other,
+        }
+    }
+
+    /// Bit width that the type's default constraints range-check a scalar to, if any.
+    ///
+    /// Ordering comparisons are only sound on operands with a known width.
+    pub fn range_bits(&self) -> Option<usize> {
+        match self {
+            ZkType::U8 => Some(8),
+            ZkType::U16 => Some(16),
+            ZkType::U32 => Some(32),
+            ZkType::U64 => Some(64),
+            ZkType::U128 => Some(128),
+            ZkType::I64 => Some(64),
+            ZkType::Bool => Some(1),
+            _ => None,
+        }
+    }
+
+    /// Whether the scalar is a signed integer, stored offset-encoded (see [`encode_i64`]).
+    pub fn is_signed(&self) -> bool {
+        matches!(self.scalar_type(), ZkType::I64)
+    }
+}
+
+/// Offset encoding of a signed value as stored in an `I64` witness cell: `value + 2^63`.
+///
+/// The encoding is monotonic and maps `[i64::MIN, i64::MAX]` onto `[0, 2^64)`, so signed
+/// fields reuse the unsigned 64-bit range and comparison checks.
+pub fn encode_i64(value: i64) -> u64 {
+    (value as u64) ^ (1 << 63)
+}
+
+impl Constraint {
+    /// Whether the constraint applies independently to every element of an array field.
+    ///
+    /// Element-wise constraints are synthesized once per flattened cell; the others
+    /// (commitments, Merkle proofs, arithmetic relations) bind the field as a whole.
+    pub fn is_element_wise(&self) -> bool {
+        matches!(
+            self,
+            Constraint::Range { .. }
+                | Constraint::Boolean
+                | Constraint::RangeProof { .. }
+                | Constraint::Comparison { .. }
+        )
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum ComparisonOp {
     GreaterThan,
     GreaterThanOrEqual,
@@ -73,7 +161,8 @@ pub enum ComparisonOp {
     NotEqual,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum ArithOp {
     Add,
     Sub,
@@ -81,13 +170,14 @@ pub enum ArithOp {
     Div,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum HashType {
     Pedersen,
     Poseidon,
 }
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct CircuitIR {
     pub name: String,
     pub public_inputs: Vec<ZkField>,
@@ -96,18 +186,24 @@ pub struct CircuitIR {
     pub circuit_config: CircuitConfig,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum InterFieldConstraint {
+    /// `result_field = operands[0] ∘ operands[1] ∘ …` with `∘ = operation`, left to right.
     ArithmeticRelation {
         result_field: String,
         operation: ArithOp,
         operands: Vec<String>,
     },
+    /// Hashing `leaf` up the sibling array `path`, steered by the direction bits in
+    /// `indices`, yields `root`. The depth is the length of `path`.
     MerkleVerification {
         leaf: String,
         path: String,
         root: String,
+        indices: String,
     },
+    /// Public input `commitment` equals `Hash(value, randomness)` of two private fields.
     CommitmentVerification {
         commitment: String,
         value: String,
@@ -119,24 +215,146 @@ pub enum InterFieldConstraint {
         right_field: String,
         op: ComparisonOp,
     },
+    /// Public input `public_input` equals the value of `source`.
+    PublicInputBinding {
+        public_input: String,
+        source: PublicInputSource,
+    },
+    /// `lhs op rhs` over the integers, e.g. `collateral * 100 >= debt * 150`.
+    ///
+    /// Ordering checks are sized from the operands' bit widths; see
+    /// [`ArithExpr::magnitude_bits`].
+    ExpressionCheck {
+        lhs: ArithExpr,
+        op: ComparisonOp,
+        rhs: ArithExpr,
+    },
+}
+
+/// Integer expression over scalar private fields, as used by
+/// [`InterFieldConstraint::ExpressionCheck`].
+///
+/// Signed fields enter with their decoded value, so `a - b` on `i64` fields is the true
+/// difference. Division is not supported: field division does not truncate.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ArithExpr {
+    Field(String),
+    Constant(u128),
+    Binary { op: ArithOp, lhs: Box<ArithExpr>, rhs: Box<ArithExpr> },
+}
+
+impl ArithExpr {
+    pub fn field(name: impl Into<String>) -> ArithExpr {
+        ArithExpr::Field(name.into())
+    }
+
+    pub fn constant(value: u128) -> ArithExpr {
+        ArithExpr::Constant(value)
+    }
+
+    /// Names of the fields the expression reads, in order of appearance.
+    pub fn fields(&self) -> Vec<&str> {
+        match self {
+            ArithExpr::Field(name) => vec![name],
+            ArithExpr::Constant(_) => vec![],
+            ArithExpr::Binary { lhs, rhs, .. } => {
+                let mut fields = lhs.fields();
+                fields.extend(rhs.fields());
+                fields
+            }
+        }
+    }
+
+    /// Number of arithmetic gates the expression lowers to.
+    pub fn num_operations(&self) -> usize {
+        match self {
+            ArithExpr::Binary { lhs, rhs, .. } => 1 + lhs.num_operations() + rhs.num_operations(),
+            _ => 0,
+        }
+    }
+
+    /// Whether the expression contains a division.
+    pub fn divides(&self) -> bool {
+        match self {
+            ArithExpr::Binary { op, lhs, rhs } => {
+                *op == ArithOp::Div || lhs.divides() || rhs.divides()
+            }
+            _ => false,
+        }
+    }
+
+    /// Smallest `b` with `|value| < 2^b` for every value the expression can take, given the
+    /// bit width of each field (`None` for fields without one, such as `Field`).
+    ///
+    /// Returns `None` if a field has no width or the expression divides.
+    pub fn magnitude_bits(&self, field_bits: &impl Fn(&str) -> Option<usize>) -> Option<usize> {
+        match self {
+            ArithExpr::Field(name) => field_bits(name),
+            ArithExpr::Constant(value) => Some(128 - value.leading_zeros() as usize),
+            ArithExpr::Binary { op, lhs, rhs } => {
+                let (l, r) = (lhs.magnitude_bits(field_bits)?, rhs.magnitude_bits(field_bits)?);
+                match op {
+                    ArithOp::Add | ArithOp::Sub => Some(l.max(r) + 1),
+                    ArithOp::Mul => Some(l + r),
+                    ArithOp::Div => None,
+                }
+            }
+        }
+    }
+
+    fn binary(op: ArithOp, lhs: ArithExpr, rhs: ArithExpr) -> ArithExpr {
+        ArithExpr::Binary { op, lhs: Box::new(lhs), rhs: Box::new(rhs) }
+    }
+}
+
+impl std::ops::Add for ArithExpr {
+    type Output = ArithExpr;
+
+    fn add(self, rhs: ArithExpr) -> ArithExpr {
+        ArithExpr::binary(ArithOp::Add, self, rhs)
+    }
+}
+
+impl std::ops::Sub for ArithExpr {
+    type Output = ArithExpr;
+
+    fn sub(self, rhs: ArithExpr) -> ArithExpr {
+        ArithExpr::binary(ArithOp::Sub, self, rhs)
+    }
+}
+
+impl std::ops::Mul for ArithExpr {
+    type Output = ArithExpr;
+
+    fn mul(self, rhs: ArithExpr) -> ArithExpr {
+        ArithExpr::binary(ArithOp::Mul, self, rhs)
+    }
+}
+
+/// Witness-derived value a public input is bound to.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PublicInputSource {
+    /// A scalar private field.
+    Field(String),
+    /// `operands[0] ∘ operands[1] ∘ …` with `∘ = operation`, left to right. Signed operands
+    /// take their decoded value, so the input is the integer result modulo `p`.
+    Arithmetic { operation: ArithOp, operands: Vec<String> },
 }
 
 impl CircuitIR {
-    /// Estimates the number of rows this circuit requires.
+    /// Estimates the number of rows this circuit requires, without synthesizing it.
     ///
     /// Accounts for witness assignments, public inputs, per-field constraints,
-    /// and inter-field constraints. Includes a 2x safety factor.
+    /// and inter-field constraints. Includes a 2x safety factor. This is a fast upper
+    /// bound; [`measure_rows`](crate::measure_rows) gives the exact count.
     pub fn estimate_rows(&self) -> usize {
-        let witness_rows = self.private_witnesses.len();
+        let witness_rows = self.num_witness_cells();
         let public_rows = self.public_inputs.len();
 
-        let constraint_rows: usize = self
-            .private_witnesses
-            .iter()
-            .chain(&self.public_inputs)
-            .flat_map(|f| &f.constraints)
-            .map(estimate_constraint_rows)
-            .sum();
+        let constraint_rows: usize =
+            self.private_witnesses.iter().chain(&self.public_inputs).map(estimate_field_rows).sum();
 
         let inter_field_rows: usize =
             self.inter_field_constraints.iter().map(estimate_inter_field_rows).sum();
@@ -144,25 +362,70 @@ impl CircuitIR {
         (witness_rows + public_rows + constraint_rows + inter_field_rows) * 2
     }
 
+    /// Total number of private witness cells once array fields are flattened.
+    pub fn num_witness_cells(&self) -> usize {
+        self.private_witnesses.iter().map(|f| f.field_type.num_elements()).sum()
+    }
+
     /// Adds a public input field to the circuit.
+    ///
+    /// The input still has to be bound to a witness-derived value (see
+    /// [`CircuitIR::bind_public_input`]) before the IR passes validation.
     pub fn add_public_input(&mut self, field: ZkField) {
         self.public_inputs.push(field);
     }
 
+    /// Adds a public input field constrained to equal `source`.
+    pub fn bind_public_input(&mut self, field: ZkField, source: PublicInputSource) {
+        self.inter_field_constraints.push(InterFieldConstraint::PublicInputBinding {
+            public_input: field.name.clone(),
+            source,
+        });
+        self.public_inputs.push(field);
+    }
+
     /// Recomputes the circuit config (k parameter) from current fields and constraints.
+    ///
+    /// Uses the minimal k from a dry-run synthesis ([`measure_rows`](crate::measure_rows)).
+    /// If the IR cannot be synthesized yet (e.g. it references undeclared fields), falls
+    /// back to [`CircuitIR::estimate_rows`]; validation then reports the actual problem.
+    /// The selected [`RangeChip`](zerostyl_runtime::RangeChip) is kept.
     pub fn recompute_config(&mut self) -> Result<()> {
-        let k = compute_k(self.estimate_rows());
+        let k = match crate::layout::measure_rows(self) {
+            Ok(report) => report.min_k,
+            Err(_) => compute_k(self.estimate_rows()),
+        };
         self.circuit_config = CircuitConfig::minimal(k)
-            .map_err(|e| CompilerError::Other(format!("Failed to create circuit config: {}", e)))?;
+            .map_err(|e| CompilerError::Other(format!("Failed to create circuit config: {}", e)))?
+            .with_range_chip(self.circuit_config.range_chip());
         Ok(())
     }
 }
 
-fn estimate_constraint_rows(constraint: &Constraint) -> usize {
+/// Constraint rows for one field, counting element-wise constraints once per array element.
+fn estimate_field_rows(field: &ZkField) -> usize {
+    let elements = field.field_type.num_elements();
+    field
+        .constraints
+        .iter()
+        .map(|c| {
+            let rows = estimate_constraint_rows(c);
+            if c.is_element_wise() {
+                rows * elements
+            } else {
+                rows
+            }
+        })
+        .sum()
+}
+
+pub(crate) fn estimate_constraint_rows(constraint: &Constraint) -> usize {
     match constraint {
-        Constraint::Range { num_bits } => num_bits + 2,
+        Constraint::Range { num_bits } => range_check_rows(*num_bits),
         Constraint::Boolean => 3,
-        Constraint::RangeProof { .. } => 128,
+        Constraint::RangeProof { min, max } => {
+            2 * (range_check_rows(bounded_range_bits(*min, *max)) + 1)
+        }
         Constraint::Comparison { .. } => 68,
         Constraint::Commitment { .. } => 64,
         Constraint::MerkleProof { tree_depth, .. } => tree_depth * 64,
@@ -170,12 +433,35 @@ fn estimate_constraint_rows(constraint: &Constraint) -> usize {
     }
 }
 
+/// Rows used by a `RangeProofChip::check_range` of `num_bits`: one row per bit plus the
+/// accumulator and value rows, and a limb recomposition region above 64 bits.
+fn range_check_rows(num_bits: usize) -> usize {
+    if num_bits <= 64 {
+        return num_bits + 2;
+    }
+    let limbs = num_bits.div_ceil(64);
+    let top_bits = num_bits - 64 * (limbs - 1);
+    limbs + 2 + range_check_rows(top_bits) + (limbs - 1) * range_check_rows(64)
+}
+
+/// Smallest bit width `N` with `max - min < 2^N`, as used for bounded range proofs.
+pub(crate) fn bounded_range_bits(min: u128, max: u128) -> usize {
+    (128 - max.saturating_sub(min).leading_zeros() as usize).max(1)
+}
+
 fn estimate_inter_field_rows(constraint: &InterFieldConstraint) -> usize {
     match constraint {
         InterFieldConstraint::ArithmeticRelation { operands, .. } => 1 + operands.len(),
         InterFieldConstraint::MerkleVerification { .. } => 32 * 64,
         InterFieldConstraint::CommitmentVerification { .. } => 64,
         InterFieldConstraint::ComparisonCheck { .. } => 68,
+        InterFieldConstraint::PublicInputBinding { source, .. } => match source {
+            PublicInputSource::Field(_) => 1,
+            PublicInputSource::Arithmetic { operands, .. } => operands.len(),
+        },
+        InterFieldConstraint::ExpressionCheck { lhs, rhs, .. } => {
+            lhs.num_operations() + rhs.num_operations() + 68
+        }
     }
 }
 
@@ -193,13 +479,20 @@ pub fn compute_k(estimated_rows: usize) -> u32 {
 
 pub fn transform_to_ir(parsed: ParsedContract) -> Result<CircuitIR> {
     validate_zk_types(&parsed.private_fields)?;
+    validate_field_references(&parsed.private_fields)?;
 
+    let mut inter_field_constraints = Vec::new();
+    // Declaring field of each inter-field constraint, to locate later type errors
+    let mut origins = Vec::new();
     let private_witnesses: Result<Vec<ZkField>> = parsed
         .private_fields
         .into_iter()
         .map(|field| {
             let field_type = parse_zk_type(&field.field_type)?;
-            let constraints = default_constraints(&field_type);
+            let mut constraints = default_constraints(&field_type);
+            constraints.extend(field.constraints);
+            origins.extend(field.inter_field_constraints.iter().map(|_| field.span.clone()));
+            inter_field_constraints.extend(field.inter_field_constraints);
 
             Ok(ZkField { name: field.name, field_type, constraints })
         })
@@ -207,25 +500,132 @@ pub fn transform_to_ir(parsed: ParsedContract) -> Result<CircuitIR> {
 
     let private_witnesses = private_witnesses?;
 
-    // Compute k dynamically from estimated row count
-    let constraint_rows: usize =
-        private_witnesses.iter().flat_map(|f| &f.constraints).map(estimate_constraint_rows).sum();
-    let estimated_rows = (private_witnesses.len() + constraint_rows) * 2;
-    let k = compute_k(estimated_rows);
+    // Each commitment is checked against a public input of the same name.
+    let mut public_inputs: Vec<ZkField> = Vec::new();
+    for constraint in &inter_field_constraints {
+        if let InterFieldConstraint::CommitmentVerification { commitment, .. } = constraint {
+            if !public_inputs.iter().any(|f| &f.name == commitment) {
+                public_inputs.push(ZkField {
+                    name: commitment.clone(),
+                    field_type: ZkType::Field,
+                    constraints: vec![],
+                });
+            }
+        }
+    }
 
-    Ok(CircuitIR {
+    let mut ir = CircuitIR {
         name: parsed.contract_name,
-        public_inputs: vec![],
+        public_inputs,
         private_witnesses,
-        inter_field_constraints: vec![],
-        circuit_config: CircuitConfig::minimal(k)
+        inter_field_constraints,
+        circuit_config: CircuitConfig::minimal(CircuitConfig::MIN_K)
             .map_err(|e| CompilerError::Other(format!("Failed to create circuit config: {}", e)))?,
-    })
+    };
+    for (constraint, origin) in ir.inter_field_constraints.iter().zip(&origins) {
+        if let InterFieldConstraint::ExpressionCheck { lhs, op, rhs } = constraint {
+            expression_check_bits(&ir, lhs, *op, rhs).map_err(|e| e.at(origin.as_ref()))?;
+        }
+    }
+    ir.recompute_config()?;
+    Ok(ir)
+}
+
+/// Transforms several parsed contracts, producing one [`CircuitIR`] per contract.
+///
+/// Fails on the first contract that cannot be transformed; the error names that contract.
+pub fn transform_all_to_ir(parsed: Vec<ParsedContract>) -> Result<Vec<CircuitIR>> {
+    parsed
+        .into_iter()
+        .map(|contract| {
+            let name = contract.contract_name.clone();
+            transform_to_ir(contract)
+                .map_err(|e| e.context(format!("Failed to transform contract '{}'", name)))
+        })
+        .collect()
 }
 
 pub fn validate_zk_types(fields: &[PrivateField]) -> Result<()> {
     for field in fields {
-        parse_zk_type(&field.field_type)?;
+        field_zk_type(field)?;
+    }
+    Ok(())
+}
+
+/// The zk type of a private field, with unsupported types located at the field.
+pub(crate) fn field_zk_type(field: &PrivateField) -> Result<ZkType> {
+    parse_zk_type(&field.field_type).map_err(|e| {
+        e.at_with_help(
+            field.span.as_ref(),
+            "remove #[zk_private] to keep this field out of the circuit",
+        )
+    })
+}
+
+/// Checks that every field named by an annotation argument is itself a private field.
+fn validate_field_references(fields: &[PrivateField]) -> Result<()> {
+    let known = |name: &str| fields.iter().any(|f| f.name == name);
+
+    for field in fields {
+        let mut referenced: Vec<&str> = Vec::new();
+        for constraint in &field.constraints {
+            if let Constraint::MerkleProof { path_field, root_field, indices_field, .. } =
+                constraint
+            {
+                referenced.extend([
+                    path_field.as_str(),
+                    root_field.as_str(),
+                    indices_field.as_str(),
+                ]);
+            }
+            if let Constraint::ArithmeticRelation { lhs_field, rhs_fields, .. } = constraint {
+                referenced.push(lhs_field);
+                referenced.extend(rhs_fields.iter().map(|(name, _)| name.as_str()));
+            }
+        }
+        for constraint in &field.inter_field_constraints {
+            match constraint {
+                InterFieldConstraint::ComparisonCheck { right_field, .. } => {
+                    referenced.push(right_field);
+                }
+                InterFieldConstraint::ArithmeticRelation { result_field, operands, .. } => {
+                    referenced.push(result_field);
+                    referenced.extend(operands.iter().map(String::as_str));
+                }
+                InterFieldConstraint::CommitmentVerification { randomness, .. } => {
+                    referenced.push(randomness);
+                }
+                InterFieldConstraint::PublicInputBinding { source, .. } => match source {
+                    PublicInputSource::Field(name) => referenced.push(name),
+                    PublicInputSource::Arithmetic { operands, .. } => {
+                        referenced.extend(operands.iter().map(String::as_str));
+                    }
+                },
+                InterFieldConstraint::MerkleVerification { leaf, path, root, indices } => {
+                    referenced.extend([
+                        leaf.as_str(),
+                        path.as_str(),
+                        root.as_str(),
+                        indices.as_str(),
+                    ]);
+                }
+                InterFieldConstraint::ExpressionCheck { lhs, rhs, .. } => {
+                    referenced.extend(lhs.fields());
+                    referenced.extend(rhs.fields());
+                }
+            }
+        }
+
+        if let Some(missing) = referenced.into_iter().find(|name| !known(name)) {
+            return Err(CompilerError::InvalidAnnotation(format!(
+                "field '{}' references '{}', which is not a #[zk_private] field",
+                field.name, missing
+            ))
+            .at_with_help(
+                field.span.as_ref(),
+                format!("declare '{}' as a #[zk_private] field of this struct", missing),
+            ));
+        }
     }
     Ok(())
 }
@@ -244,33 +644,70 @@ fn parse_zk_type(type_str: &str) -> Result<ZkType> {
         "Address" => Ok(ZkType::Address),
         _ => {
             if type_str.starts_with('[') && type_str.ends_with(']') {
-                return Err(CompilerError::UnsupportedZkType(format!(
-                    "Array type '{}' parsing not yet fully implemented. Currently only [u8;32] is supported.",
-                    type_str
-                )));
+                return parse_array_type(type_str);
             }
 
             Err(CompilerError::UnsupportedZkType(format!(
-                "Type '{}' is not supported for zk-SNARK circuits. Supported types: u8, u16, u32, u64, u128, i64, bool, Field, [u8;32], Address",
+                "Type '{}' is not supported for zk-SNARK circuits. Supported types: u8, u16, u32, u64, u128, i64, bool, Field, [u8;32], Address, and fixed-size arrays [T; N] of these",
                 type_str
             )))
         }
     }
 }
 
-fn default_constraints(zk_type: &ZkType) -> Vec<Constraint> {
+/// Parses a fixed-size array type such as `[u64;4]` or `[[Field;2];8]`.
+fn parse_array_type(type_str: &str) -> Result<ZkType> {
+    let array: syn::TypeArray = syn::parse_str(type_str).map_err(|e| {
+        CompilerError::UnsupportedZkType(format!("Invalid array type '{}': {}", type_str, e))
+    })?;
+
+    let size = match &array.len {
+        syn::Expr::Lit(syn::ExprLit { lit: syn::Lit::Int(int), .. }) => {
+            int.base10_parse::<usize>().ok()
+        }
+        _ => None,
+    }
+    .ok_or_else(|| {
+        CompilerError::UnsupportedZkType(format!(
+            "Array type '{}' must have an integer literal length",
+            type_str
+        ))
+    })?;
+    if size == 0 {
+        return Err(CompilerError::UnsupportedZkType(format!(
+            "Array type '{}' must have at least one element",
+            type_str
+        )));
+    }
+
+    let element_str = array.elem.to_token_stream().to_string().replace(' ', "");
+    let element_type = parse_zk_type(&element_str)?;
+
+    let zk_type = ZkType::Array { element_type: Box::new(element_type), size };
+    if zk_type.num_elements() > MAX_ARRAY_ELEMENTS {
+        return Err(CompilerError::UnsupportedZkType(format!(
+            "Array type '{}' flattens to {} elements, exceeding maximum {}",
+            type_str,
+            zk_type.num_elements(),
+            MAX_ARRAY_ELEMENTS
+        )));
+    }
+    Ok(zk_type)
+}
+
+pub(crate) fn default_constraints(zk_type: &ZkType) -> Vec<Constraint> {
     match zk_type {
         ZkType::U8 => vec![Constraint::Range { num_bits: 8 }],
         ZkType::U16 => vec![Constraint::Range { num_bits: 16 }],
         ZkType::U32 => vec![Constraint::Range { num_bits: 32 }],
         ZkType::U64 => vec![Constraint::Range { num_bits: 64 }],
-        ZkType::U128 => vec![Constraint::RangeProof { min: 0, max: u128::MAX }],
-        ZkType::I64 => vec![],
+        ZkType::U128 => vec![Constraint::Range { num_bits: 128 }],
+        ZkType::I64 => vec![Constraint::Range { num_bits: 64 }],
         ZkType::Bool => vec![Constraint::Boolean],
         ZkType::Field => vec![],
-        ZkType::Bytes32 => vec![],
-        ZkType::Array { .. } => vec![],
-        ZkType::Address => vec![],
+        ZkType::Bytes32 => vec![Constraint::Range { num_bits: BYTES32_LIMB_BITS }],
+        ZkType::Array { element_type, .. } => default_constraints(element_type),
+        ZkType::Address => vec![Constraint::Range { num_bits: ADDRESS_BITS }],
     }
 }
 
@@ -316,12 +753,17 @@ mod tests {
     fn test_default_constraints_u128() {
         let constraints = default_constraints(&ZkType::U128);
         assert_eq!(constraints.len(), 1);
-        if let Constraint::RangeProof { min, max } = constraints[0] {
-            assert_eq!(min, 0);
-            assert_eq!(max, u128::MAX);
-        } else {
-            panic!("Expected RangeProof constraint");
-        }
+        assert!(matches!(constraints[0], Constraint::Range { num_bits: 128 }));
+    }
+
+    #[test]
+    fn test_range_check_rows_counts_limbs() {
+        assert_eq!(range_check_rows(64), 66);
+        // 2 limbs: 4 recomposition rows plus two 64-bit limb checks
+        assert_eq!(range_check_rows(128), 4 + 2 * 66);
+        assert_eq!(bounded_range_bits(0, u128::MAX), 128);
+        assert_eq!(bounded_range_bits(150, 300), 8);
+        assert_eq!(bounded_range_bits(5, 5), 1);
     }
 
     #[test]
@@ -356,7 +798,60 @@ mod tests {
     fn test_default_constraints_array_type() {
         let array_type = ZkType::Array { element_type: Box::new(ZkType::U64), size: 10 };
         let constraints = default_constraints(&array_type);
-        assert_eq!(constraints.len(), 0);
+        assert_eq!(constraints, vec![Constraint::Range { num_bits: 64 }]);
+
+        let nested = ZkType::Array { element_type: Box::new(array_type), size: 2 };
+        assert_eq!(default_constraints(&nested), vec![Constraint::Range { num_bits: 64 }]);
+    }
+
+    #[test]
+    fn test_parse_zk_type_arrays() {
+        assert_eq!(
+            parse_zk_type("[u64;4]").unwrap(),
+            ZkType::Array { element_type: Box::new(ZkType::U64), size: 4 }
+        );
+        assert_eq!(
+            parse_zk_type("[bool;20]").unwrap(),
+            ZkType::Array { element_type: Box::new(ZkType::Bool), size: 20 }
+        );
+        assert_eq!(
+            parse_zk_type("[[Field;2];8]").unwrap(),
+            ZkType::Array {
+                element_type: Box::new(ZkType::Array {
+                    element_type: Box::new(ZkType::Field),
+                    size: 2
+                }),
+                size: 8
+            }
+        );
+        // [u8; 32] keeps its dedicated Bytes32 representation
+        assert_eq!(parse_zk_type("[u8;32]").unwrap(), ZkType::Bytes32);
+    }
+
+    #[test]
+    fn test_parse_zk_type_array_errors() {
+        assert!(parse_zk_type("[u64;0]").is_err());
+        assert!(parse_zk_type("[u64;N]").is_err());
+        assert!(parse_zk_type("[String;4]").is_err());
+        assert!(parse_zk_type("[[u64;64];32]").is_err()); // 2048 elements
+    }
+
+    #[test]
+    fn test_estimate_rows_scales_with_array_size() {
+        let ir_for = |size: usize| CircuitIR {
+            name: "Test".to_string(),
+            public_inputs: vec![],
+            private_witnesses: vec![ZkField {
+                name: "vals".to_string(),
+                field_type: ZkType::Array { element_type: Box::new(ZkType::U64), size },
+                constraints: vec![Constraint::Range { num_bits: 64 }],
+            }],
+            inter_field_constraints: vec![],
+            circuit_config: CircuitConfig::minimal(4).unwrap(),
+        };
+        // (4 cells + 4 * 66 constraint rows) * 2 = 536
+        assert_eq!(ir_for(4).estimate_rows(), 536);
+        assert_eq!(ir_for(8).estimate_rows(), 2 * ir_for(4).estimate_rows());
     }
 
     #[test]
@@ -371,7 +866,16 @@ mod tests {
         assert_eq!(constraints.len(), 1);
 
         let constraints = default_constraints(&ZkType::I64);
-        assert_eq!(constraints.len(), 0);
+        assert_eq!(constraints.len(), 1);
+        assert!(matches!(constraints[0], Constraint::Range { num_bits: 64 }));
+    }
+
+    #[test]
+    fn test_encode_i64_is_monotonic_offset() {
+        assert_eq!(encode_i64(i64::MIN), 0);
+        assert_eq!(encode_i64(-1), (1 << 63) - 1);
+        assert_eq!(encode_i64(0), 1 << 63);
+        assert_eq!(encode_i64(i64::MAX), u64::MAX);
     }
 
     #[test]
@@ -379,11 +883,14 @@ mod tests {
         let constraints = default_constraints(&ZkType::Field);
         assert_eq!(constraints.len(), 0);
 
+        // Each 128-bit Bytes32 limb and the 160-bit Address limb are range-checked
         let constraints = default_constraints(&ZkType::Bytes32);
-        assert_eq!(constraints.len(), 0);
+        assert!(matches!(constraints[..], [Constraint::Range { num_bits: 128 }]));
+        assert_eq!(ZkType::Bytes32.num_elements(), 2);
 
         let constraints = default_constraints(&ZkType::Address);
-        assert_eq!(constraints.len(), 0);
+        assert!(matches!(constraints[..], [Constraint::Range { num_bits: 160 }]));
+        assert_eq!(ZkType::Address.num_elements(), 1);
     }
 
     #[test]
@@ -477,8 +984,8 @@ mod tests {
         };
         assert_eq!(ir.circuit_config.k(), 4);
         ir.recompute_config().unwrap();
-        // (1 + 66) * 2 = 134 → k=8
-        assert_eq!(ir.circuit_config.k(), 8);
+        // Measured: a 65-row range check plus blinding rows fits in 2^7
+        assert_eq!(ir.circuit_config.k(), 7);
     }
 
     #[test]
@@ -507,10 +1014,13 @@ mod tests {
             private_fields: vec![PrivateField {
                 name: "val".to_string(),
                 field_type: "u64".to_string(),
+                constraints: vec![],
+                inter_field_constraints: vec![],
+                span: None,
             }],
         };
         let ir = transform_to_ir(parsed).unwrap();
-        // 1 u64 → Range{64} → (1 + 66) * 2 = 134 → k=8
-        assert_eq!(ir.circuit_config.k(), 8);
+        // 1 u64 → Range{64}: 65 measured rows plus blinding rows → k=7
+        assert_eq!(ir.circuit_config.k(), 7);
     }
 }
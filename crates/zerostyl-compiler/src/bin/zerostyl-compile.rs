@@ -0,0 +1,233 @@
+//! ZeroStyl Compiler CLI.
+//!
+//! Compiles a contract source file with `#[zk_private]` annotations into a Stylus
+//! verifier: parse → IR → validate → (optional) MockProver run against a sample
+//! witness → WASM codegen. The other subcommands stop early to print the IR, the
+//! circuit size or the generated verifier crate.
+
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+
+use anyhow::{bail, Context, Result};
+use clap::{Parser, Subcommand};
+use zerostyl_circuits::CircuitDescriptor;
+use zerostyl_compiler::codegen::stylus::{check_stylus, StylusLimits};
+use zerostyl_compiler::{
+    compute_k, measure_rows, parse_contracts, transform_all_to_ir, validate_circuit_ir, CircuitIR,
+    CodegenConfig, CompilerError, IrDescriptor, WasmCodegen, WasmSizeReport,
+};
+
+#[derive(Parser)]
+#[command(name = "zerostyl-compile")]
+#[command(about = "Compile #[zk_private] contracts to Stylus verifiers", long_about = None)]
+struct Cli {
+    #[command(subcommand)]
+    command: Commands,
+}
+
+#[derive(Subcommand)]
+enum Commands {
+    /// Run the full pipeline and write the verifier WASM
+    Compile {
+        #[command(flatten)]
+        input: Input,
+        /// Witness JSON to check the circuit against with the MockProver before codegen
+        #[arg(short, long)]
+        witness: Option<PathBuf>,
+        /// Output path (default: the circuit name with a .wasm extension)
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+        /// Skip `wasm-opt`
+        #[arg(long)]
+        no_optimize: bool,
+        /// Fail instead of warning when Stylus would reject the WASM
+        #[arg(long)]
+        strict: bool,
+    },
+
+    /// Print the circuit IR
+    Ir {
+        #[command(flatten)]
+        input: Input,
+        /// Output format: text (default) or json
+        #[arg(short, long, default_value = "text")]
+        format: String,
+    },
+
+    /// Print the estimated k and the k measured by synthesizing the circuit
+    K {
+        #[command(flatten)]
+        input: Input,
+    },
+
+    /// Print the generated verifier crate, or write it to a directory
+    Crate {
+        #[command(flatten)]
+        input: Input,
+        /// Write the complete crate, verifying data included, to this directory
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+}
+
+#[derive(clap::Args)]
+struct Input {
+    /// Contract source file
+    source: PathBuf,
+    /// Struct to compile, when the file declares several
+    #[arg(short, long)]
+    contract: Option<String>,
+}
+
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+    let result = match cli.command {
+        Commands::Compile { input, witness, output, no_optimize, strict } => {
+            cmd_compile(&input, witness.as_deref(), output, no_optimize, strict)
+        }
+        Commands::Ir { input, format } => cmd_ir(&input, &format),
+        Commands::K { input } => cmd_k(&input),
+        Commands::Crate { input, output } => cmd_crate(&input, output.as_deref()),
+    };
+
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            match e.downcast_ref::<CompilerError>().and_then(CompilerError::diagnostic) {
+                Some(diagnostic) => eprint!("{}", diagnostic.render()),
+                None => eprintln!("error: {e:#}"),
+            }
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn cmd_compile(
+    input: &Input,
+    witness: Option<&Path>,
+    output: Option<PathBuf>,
+    no_optimize: bool,
+    strict: bool,
+) -> Result<()> {
+    let ir = load_ir(input)?;
+    eprintln!("circuit '{}': k = {}", ir.name, ir.circuit_config.k());
+
+    if let Some(witness) = witness {
+        mock_prove(&ir, witness)?;
+        eprintln!("mock prover: all constraints satisfied");
+    }
+
+    let config = CodegenConfig { optimize_size: !no_optimize, ..CodegenConfig::default() };
+    let output = output.unwrap_or_else(|| PathBuf::from(format!("{}.wasm", ir.name)));
+    let wasm = WasmCodegen::with_config(ir, config).compile()?;
+    std::fs::write(&output, &wasm)
+        .with_context(|| format!("writing WASM: {}", output.display()))?;
+    eprintln!("wrote {} bytes -> {}", wasm.len(), output.display());
+
+    let report = WasmSizeReport::analyze(&wasm)?;
+    eprintln!("  data segments: {} bytes", report.data_bytes());
+    for function in report.largest_functions(3) {
+        let name = function.name.as_deref().unwrap_or("<unnamed>");
+        eprintln!("  function {name}: {} bytes", function.size_bytes);
+    }
+
+    let violations = check_stylus(&wasm, &StylusLimits::default())?;
+    for violation in &violations {
+        eprintln!("{}: {violation}", if strict { "error" } else { "warning" });
+    }
+    if strict && !violations.is_empty() {
+        bail!("Stylus would reject {} ({} violation(s))", output.display(), violations.len());
+    }
+    Ok(())
+}
+
+fn cmd_ir(input: &Input, format: &str) -> Result<()> {
+    let ir = load_ir(input)?;
+    match format {
+        "text" => print!("{ir}"),
+        "json" => println!("{}", ir.to_json()?),
+        other => bail!("unknown format '{other}' (expected text or json)"),
+    }
+    Ok(())
+}
+
+fn cmd_k(input: &Input) -> Result<()> {
+    let ir = load_ir(input)?;
+    let report = measure_rows(&ir)?;
+    println!("Circuit: {}", ir.name);
+    println!("  estimated k: {}", compute_k(ir.estimate_rows()));
+    println!(
+        "  measured k:  {} ({} rows used, {} blinding)",
+        report.min_k, report.used_rows, report.blinding_rows
+    );
+    println!();
+    println!("Rows by region:");
+    for (region, rows) in report.rows_by_region() {
+        println!("  {:<32} {rows}", region);
+    }
+    Ok(())
+}
+
+fn cmd_crate(input: &Input, output: Option<&Path>) -> Result<()> {
+    let codegen = WasmCodegen::new(load_ir(input)?);
+    match output {
+        Some(dir) => {
+            codegen.write_crate(dir)?;
+            eprintln!("wrote verifier crate '{}' -> {}", codegen.circuit_ir().name, dir.display());
+        }
+        None => {
+            println!("// Cargo.toml");
+            println!("{}", codegen.generate_cargo_toml());
+            println!("// src/lib.rs");
+            print!("{}", codegen.generate_lib_rs());
+        }
+    }
+    Ok(())
+}
+
+/// Parses, transforms and validates the selected contract.
+fn load_ir(input: &Input) -> Result<CircuitIR> {
+    let path = &input.source;
+    let source = std::fs::read_to_string(path)
+        .with_context(|| format!("reading contract source: {}", path.display()))?;
+    let file = path.display().to_string();
+
+    let mut irs =
+        parse_contracts(&source).and_then(transform_all_to_ir).map_err(|e| e.with_file(&file))?;
+    let ir = match &input.contract {
+        Some(name) => {
+            let index = irs.iter().position(|ir| &ir.name == name).with_context(|| {
+                format!("no #[zk_private] struct '{}' in {} (found: {})", name, file, names(&irs))
+            })?;
+            irs.swap_remove(index)
+        }
+        None if irs.len() == 1 => irs.remove(0),
+        None => {
+            bail!("{} declares several contracts ({}); pick one with --contract", file, names(&irs))
+        }
+    };
+
+    validate_circuit_ir(&ir).map_err(|e| e.with_file(&file))?;
+    Ok(ir)
+}
+
+fn names(irs: &[CircuitIR]) -> String {
+    irs.iter().map(|ir| ir.name.as_str()).collect::<Vec<_>>().join(", ")
+}
+
+fn mock_prove(ir: &CircuitIR, witness: &Path) -> Result<()> {
+    let witness_json = std::fs::read_to_string(witness)
+        .with_context(|| format!("reading witness file: {}", witness.display()))?;
+    let descriptor = IrDescriptor::new(ir.clone())?;
+    let report = descriptor
+        .mock_prove(&witness_json, descriptor.default_k())
+        .map_err(|e| anyhow::anyhow!("{e}"))?;
+    if report.satisfied {
+        return Ok(());
+    }
+
+    for failure in &report.failures {
+        eprintln!("  - {:?}: {}", failure.kind, failure.details);
+    }
+    bail!("mock prover: {} constraint failure(s) for {}", report.failures.len(), witness.display())
+}
@@ -21,15 +21,26 @@
 //! let circuit = CircuitBuilder::new(ir).build::<Fp>();
 //! ```
 
-use crate::ast::ComparisonOp;
-use crate::gadgets::{ComparisonChip, ComparisonConfig, RangeProofChip, RangeProofConfig};
+use crate::ast::{
+    bounded_range_bits, ArithExpr, ArithOp, ComparisonOp, HashType, InterFieldConstraint,
+    PublicInputSource, MAX_ARRAY_ELEMENTS,
+};
+use crate::gadgets::{
+    ArithmeticChip, ArithmeticConfig, ComparisonChip, ComparisonConfig, LookupRangeChip,
+    LookupRangeConfig, MerkleTreeChip, MerkleTreeConfig, PoseidonCommitmentChip, RangeProofChip,
+    RangeProofConfig, SignedChip, SignedConfig,
+};
+use crate::layout::measure_rows;
 use crate::{CircuitIR, CompilerError, Constraint, ZkType};
 use halo2_proofs::{
     arithmetic::Field as Halo2Field,
-    circuit::{Layouter, SimpleFloorPlanner, Value},
+    circuit::{AssignedCell, Layouter, SimpleFloorPlanner, Value},
     pasta::Fp,
-    plonk::{Advice, Circuit, Column, ConstraintSystem, Error as Halo2Error, Instance},
+    plonk::{Advice, Circuit, Column, ConstraintSystem, Error as Halo2Error, Fixed, Instance},
 };
+use halo2curves::ff::PrimeField;
+use std::collections::HashMap;
+use zerostyl_runtime::{MerklePath, RangeChip};
 
 pub struct CircuitBuilder {
     circuit_ir: CircuitIR,
@@ -45,12 +56,12 @@ impl CircuitBuilder {
     }
 
     pub fn build<F: Halo2Field>(self) -> ZkCircuit<F> {
-        if self.circuit_ir.private_witnesses.len() > ZkCircuit::<F>::MAX_SINGLE_ROW_WITNESSES {
+        if self.circuit_ir.num_witness_cells() > ZkCircuit::<F>::MAX_SINGLE_ROW_WITNESSES {
             eprintln!(
-                "Warning: Circuit '{}' has {} witnesses (max {} for single-row). \
+                "Warning: Circuit '{}' has {} witness cells (max {} for single-row). \
                  Multi-row layout will be used, which may impact performance.",
                 self.circuit_ir.name,
-                self.circuit_ir.private_witnesses.len(),
+                self.circuit_ir.num_witness_cells(),
                 ZkCircuit::<F>::MAX_SINGLE_ROW_WITNESSES
             );
         }
@@ -66,7 +77,7 @@ impl CircuitBuilder {
         }
 
         // Initialize with zero values - use with_witnesses() to set actual values
-        let witness_values = vec![Value::known(F::ZERO); self.circuit_ir.private_witnesses.len()];
+        let witness_values = vec![Value::known(F::ZERO); self.circuit_ir.num_witness_cells()];
         let public_values = vec![Value::known(F::ZERO); self.circuit_ir.public_inputs.len()];
 
         ZkCircuit { ir: self.circuit_ir, witness_values, public_values }
@@ -78,14 +89,23 @@ pub struct ZkCircuitConfig {
     advice: Vec<Column<Advice>>,
     instance: Column<Instance>,
     range_config: RangeProofConfig,
+    /// Configured alongside `range_config`: `configure` cannot see the IR, so the choice
+    /// between them ([`CircuitConfig::range_chip`](zerostyl_runtime::CircuitConfig::range_chip))
+    /// is made in `synthesize`.
+    lookup_range_config: LookupRangeConfig,
     comparison_config: ComparisonConfig,
+    arithmetic_config: ArithmeticConfig,
+    signed_config: SignedConfig,
+    /// Also provides the Poseidon columns used for commitments.
+    merkle_config: MerkleTreeConfig,
 }
 
 #[derive(Clone, Debug)]
 pub struct ZkCircuit<F: Halo2Field> {
     pub ir: CircuitIR,
     /// Private witness values for the circuit
-    /// Each `Value<F>` corresponds to a private witness field in ir.private_witnesses
+    /// Each `Value<F>` corresponds to one flattened cell of ir.private_witnesses: scalar
+    /// fields take one cell, array fields take one cell per element (row-major, in order)
     /// Uses `Value::unknown()` during key generation, `Value::known()` during proving
     pub witness_values: Vec<Value<F>>,
     /// Public input values for the circuit
@@ -93,6 +113,13 @@ pub struct ZkCircuit<F: Halo2Field> {
     pub public_values: Vec<Value<F>>,
 }
 
+impl ZkCircuitConfig {
+    /// Fixed columns enabled for global constants, as passed to the floor planner.
+    pub(crate) fn constants(&self) -> Vec<Column<Fixed>> {
+        vec![self.merkle_config.poseidon_config().constants_column()]
+    }
+}
+
 impl<F: Halo2Field> ZkCircuit<F> {
     pub const MAX_SINGLE_ROW_WITNESSES: usize = 10;
     pub const MAX_PUBLIC_INPUTS: usize = 5;
@@ -100,7 +127,8 @@ impl<F: Halo2Field> ZkCircuit<F> {
     /// Set witness values for proof generation
     ///
     /// # Arguments
-    /// * `witnesses` - Field elements corresponding to private witness fields
+    /// * `witnesses` - Field elements corresponding to private witness fields, with array
+    ///   fields flattened element by element (see [`CircuitIR::num_witness_cells`])
     ///
     /// # Returns
     /// Self with updated witness values
@@ -124,10 +152,10 @@ impl<F: Halo2Field> ZkCircuit<F> {
     ///     .with_witnesses(vec![Fp::from(42)]);
     /// ```
     pub fn with_witnesses(mut self, witnesses: Vec<F>) -> Result<Self, CompilerError> {
-        if witnesses.len() != self.ir.private_witnesses.len() {
+        if witnesses.len() != self.ir.num_witness_cells() {
             return Err(CompilerError::Other(format!(
                 "Expected {} witnesses but got {}",
-                self.ir.private_witnesses.len(),
+                self.ir.num_witness_cells(),
                 witnesses.len()
             )));
         }
@@ -154,9 +182,9 @@ impl<F: Halo2Field> ZkCircuit<F> {
         Ok(self)
     }
 
-    /// Get the number of expected witnesses
+    /// Get the number of expected witnesses (array fields count one per element)
     pub fn num_witnesses(&self) -> usize {
-        self.ir.private_witnesses.len()
+        self.ir.num_witness_cells()
     }
 
     /// Get the number of expected public inputs
@@ -172,7 +200,7 @@ impl Circuit<Fp> for ZkCircuit<Fp> {
     fn without_witnesses(&self) -> Self {
         Self {
             ir: self.ir.clone(),
-            witness_values: vec![Value::unknown(); self.ir.private_witnesses.len()],
+            witness_values: vec![Value::unknown(); self.ir.num_witness_cells()],
             public_values: vec![Value::unknown(); self.ir.public_inputs.len()],
         }
     }
@@ -180,9 +208,15 @@ impl Circuit<Fp> for ZkCircuit<Fp> {
     fn configure(meta: &mut ConstraintSystem<Fp>) -> Self::Config {
         // Configure gadgets — each allocates its own columns and gates
         let range_config = RangeProofChip::configure(meta);
+        let lookup_range_config = LookupRangeChip::configure(meta);
         let comparison_config = ComparisonChip::configure(meta);
+        let arithmetic_config = ArithmeticChip::configure(meta);
+        let signed_config = SignedChip::configure(meta);
+        // The Merkle chip configures Poseidon internally; commitments reuse those columns,
+        // so membership proofs only cost one extra advice column and selector.
+        let merkle_config = MerkleTreeChip::configure(meta);
 
-        // General-purpose advice columns for unconstrained witnesses
+        // General-purpose advice columns holding every private witness cell
         let advice: Vec<Column<Advice>> = (0..2)
             .map(|_| {
                 let col = meta.advice_column();
@@ -194,7 +228,16 @@ impl Circuit<Fp> for ZkCircuit<Fp> {
         let instance = meta.instance_column();
         meta.enable_equality(instance);
 
-        ZkCircuitConfig { advice, instance, range_config, comparison_config }
+        ZkCircuitConfig {
+            advice,
+            instance,
+            range_config,
+            lookup_range_config,
+            comparison_config,
+            arithmetic_config,
+            signed_config,
+            merkle_config,
+        }
     }
 
     fn synthesize(
@@ -202,198 +245,886 @@ impl Circuit<Fp> for ZkCircuit<Fp> {
         config: Self::Config,
         mut layouter: impl Layouter<Fp>,
     ) -> Result<(), Halo2Error> {
-        let range_chip = RangeProofChip::construct(config.range_config.clone());
-        let comparison_chip = ComparisonChip::construct(config.comparison_config.clone());
-
-        let mut unconstrained: Vec<(usize, Value<Fp>)> = Vec::new();
+        let (range_chip, comparison_chip) = match self.ir.circuit_config.range_chip() {
+            RangeChip::Bits => (
+                FieldRangeChip::Bits(RangeProofChip::construct(config.range_config.clone())),
+                ComparisonChip::construct(config.comparison_config.clone()),
+            ),
+            RangeChip::Lookup => {
+                let chip = LookupRangeChip::construct(config.lookup_range_config.clone());
+                chip.load_table(layouter.namespace(|| "range table"))?;
+                let comparison_config =
+                    config.comparison_config.with_lookup(config.lookup_range_config.clone());
+                (FieldRangeChip::Lookup(chip), ComparisonChip::construct(comparison_config))
+            }
+        };
+        let arithmetic_chip = ArithmeticChip::construct(config.arithmetic_config.clone());
+        let signed_chip = SignedChip::construct(config.signed_config.clone());
+        let merkle_chip = MerkleTreeChip::construct(config.merkle_config.clone());
+        let poseidon_chip =
+            PoseidonCommitmentChip::construct(config.merkle_config.poseidon_config().clone());
+
+        if self.witness_values.len() != self.ir.num_witness_cells() {
+            return Err(Halo2Error::Synthesis);
+        }
 
-        for (idx, (field, &wv)) in
-            self.ir.private_witnesses.iter().zip(self.witness_values.iter()).enumerate()
-        {
-            let mut field_constrained = false;
+        // Every flattened witness cell is assigned exactly once. Gadgets copy from these
+        // cells, so all constraints touching a field are bound to the same value.
+        let witness_cells = layouter.assign_region(
+            || "private_witnesses",
+            |mut region| {
+                self.witness_values
+                    .iter()
+                    .enumerate()
+                    .map(|(idx, value)| {
+                        let col = idx % config.advice.len();
+                        let row = idx / config.advice.len();
+                        region.assign_advice(
+                            || format!("witness_{}", idx),
+                            config.advice[col],
+                            row,
+                            || *value,
+                        )
+                    })
+                    .collect::<Result<Vec<_>, _>>()
+            },
+        )?;
+
+        let mut field_cells: HashMap<&str, &[AssignedCell<Fp, Fp>]> = HashMap::new();
+        let mut offset = 0;
+        for field in &self.ir.private_witnesses {
+            let len = field.field_type.num_elements();
+            field_cells.insert(field.name.as_str(), &witness_cells[offset..offset + len]);
+            offset += len;
+        }
 
+        // Array fields are flattened: every element gets its own copy of the field's
+        // element-wise constraints.
+        let cells = self
+            .ir
+            .private_witnesses
+            .iter()
+            .flat_map(|field| std::iter::repeat_n(field, field.field_type.num_elements()));
+        for (idx, (field, cell)) in cells.zip(&witness_cells).enumerate() {
             for constraint in &field.constraints {
                 match constraint {
                     Constraint::Range { num_bits } => {
-                        let cell = range_chip
-                            .load_value(layouter.namespace(|| format!("load_w{}", idx)), wv)?;
                         range_chip.check_range(
                             layouter.namespace(|| format!("range_{}", idx)),
-                            cell,
+                            cell.clone(),
                             *num_bits,
                         )?;
-                        field_constrained = true;
                     }
                     Constraint::Boolean => {
-                        let cell = range_chip
-                            .load_value(layouter.namespace(|| format!("load_bool_{}", idx)), wv)?;
                         range_chip.check_range(
                             layouter.namespace(|| format!("bool_{}", idx)),
-                            cell,
+                            cell.clone(),
                             1,
                         )?;
-                        field_constrained = true;
                     }
                     Constraint::RangeProof { min, max } => {
-                        // The generic circuit builder only supports bounded range
-                        // checks where min and max fit in u64. For u128 values,
-                        // the proc-macro handles them via DualRange decomposition.
-                        if *min > u64::MAX as u128 || *max > u64::MAX as u128 {
-                            return Err(Halo2Error::Synthesis);
-                        }
-                        let min_fp = Fp::from(*min as u64);
-                        let max_fp = Fp::from(*max as u64);
-                        let cell = range_chip
-                            .load_value(layouter.namespace(|| format!("load_rp_{}", idx)), wv)?;
+                        // Bounds wider than 64 bits are checked limb by limb.
                         range_chip.check_range_bounded(
                             layouter.namespace(|| format!("rangeproof_{}", idx)),
-                            cell,
-                            min_fp,
-                            max_fp,
-                            64,
+                            cell.clone(),
+                            Fp::from_u128(*min),
+                            Fp::from_u128(*max),
+                            bounded_range_bits(*min, *max),
                         )?;
-                        field_constrained = true;
                     }
                     Constraint::Comparison { operator, value } => {
-                        let witness_cell = comparison_chip
-                            .load_value(layouter.namespace(|| format!("load_cmp_{}", idx)), wv)?;
-                        let threshold = Value::known(Fp::from(*value));
-                        let threshold_cell = comparison_chip.load_value(
-                            layouter.namespace(|| format!("load_cmp_thr_{}", idx)),
-                            threshold,
+                        // The threshold is a fixed constant, so the prover cannot choose it.
+                        let threshold_cell = layouter.assign_region(
+                            || format!("cmp_threshold_{}", idx),
+                            |mut region| {
+                                region.assign_advice_from_constant(
+                                    || "threshold",
+                                    config.advice[0],
+                                    0,
+                                    Fp::from_u128(*value),
+                                )
+                            },
                         )?;
-                        match operator {
-                            ComparisonOp::GreaterThan => {
-                                comparison_chip.assert_gt(
-                                    layouter.namespace(|| format!("cmp_gt_{}", idx)),
-                                    witness_cell,
-                                    threshold_cell,
-                                    64,
-                                )?;
-                            }
-                            ComparisonOp::GreaterThanOrEqual => {
-                                comparison_chip.assert_gte(
-                                    layouter.namespace(|| format!("cmp_gte_{}", idx)),
-                                    witness_cell,
-                                    threshold_cell,
-                                    64,
-                                )?;
-                            }
-                            ComparisonOp::LessThan => {
-                                comparison_chip.assert_lt(
-                                    layouter.namespace(|| format!("cmp_lt_{}", idx)),
-                                    witness_cell,
-                                    threshold_cell,
-                                    64,
-                                )?;
-                            }
-                            ComparisonOp::LessThanOrEqual => {
-                                comparison_chip.assert_lte(
-                                    layouter.namespace(|| format!("cmp_lte_{}", idx)),
-                                    witness_cell,
-                                    threshold_cell,
-                                    64,
-                                )?;
-                            }
-                            ComparisonOp::Equal | ComparisonOp::NotEqual => {
-                                // Equal/NotEqual comparisons are not supported in the generic
-                                // circuit builder. Return a synthesis error rather than silently
-                                // marking the field as constrained without any actual constraint.
-                                return Err(Halo2Error::Synthesis);
-                            }
-                        }
-                        field_constrained = true;
+                        // Both operands must fit: the field by its type, the constant by
+                        // its bit length (it may be wider, e.g. `value < 2^70` on a u64).
+                        let threshold_bits = (u128::BITS - value.leading_zeros()) as usize;
+                        assert_comparison(
+                            &comparison_chip,
+                            layouter.namespace(|| format!("cmp_{}", idx)),
+                            *operator,
+                            cell.clone(),
+                            threshold_cell,
+                            comparison_bits([field.field_type.scalar_type()]).max(threshold_bits),
+                        )?;
+                    }
+                    Constraint::Commitment { .. } => {
+                        // Only records the scheme; the hash itself is checked by the
+                        // field's CommitmentVerification inter-field constraint, if any.
                     }
-                    Constraint::Commitment { .. }
-                    | Constraint::ArithmeticRelation { .. }
-                    | Constraint::MerkleProof { .. } => {
-                        // These are cross-field constraints that require hand-written circuits
-                        // (tx_privacy, private_vote, state_mask) using gadgets directly.
-                        // The generic builder cannot generate these constraints, so the field
-                        // is left unconstrained (assigned to general advice columns with a
-                        // warning). Do NOT mark field_constrained = true here.
+                    Constraint::ArithmeticRelation { .. } | Constraint::MerkleProof { .. } => {
+                        // Binds several fields as a whole; synthesized once below.
                     }
                 }
             }
+        }
 
-            if !field_constrained {
-                unconstrained.push((idx, wv));
-            }
+        // Field-to-field comparisons (`constraint = "value >= other_field"`).
+        for (idx, constraint) in self.ir.inter_field_constraints.iter().enumerate() {
+            let InterFieldConstraint::ComparisonCheck { left_field, right_field, op } = constraint
+            else {
+                continue;
+            };
+            let scalar = |name: &str| match field_cells.get(name) {
+                Some([cell]) => Ok(cell.clone()),
+                _ => Err(Halo2Error::Synthesis),
+            };
+            let field_type = |name: &str| {
+                self.ir
+                    .private_witnesses
+                    .iter()
+                    .find(|f| f.name == name)
+                    .map(|f| &f.field_type)
+                    .ok_or(Halo2Error::Synthesis)
+            };
+            assert_comparison(
+                &comparison_chip,
+                layouter.namespace(|| format!("cmp_fields_{}", idx)),
+                *op,
+                scalar(left_field)?,
+                scalar(right_field)?,
+                comparison_bits([field_type(left_field)?, field_type(right_field)?]),
+            )?;
         }
 
-        // Assign unconstrained witnesses to general advice columns
-        if !unconstrained.is_empty() {
-            layouter.assign_region(
-                || "unconstrained_witnesses",
-                |mut region| {
-                    for (i, (idx, value)) in unconstrained.iter().enumerate() {
-                        let col = i % config.advice.len();
-                        let row = i / config.advice.len();
-                        region.assign_advice(
-                            || format!("witness_{}", idx),
-                            config.advice[col],
-                            row,
-                            || *value,
-                        )?;
+        // Expression checks (`constraint = "value * 100 >= debt * 150"`): both sides are
+        // evaluated with arithmetic gates, then compared at a width that fits their difference.
+        for (idx, constraint) in self.ir.inter_field_constraints.iter().enumerate() {
+            let InterFieldConstraint::ExpressionCheck { lhs, op, rhs } = constraint else {
+                continue;
+            };
+            let num_bits = expression_check_bits(&self.ir, lhs, *op, rhs)
+                .map_err(|_| Halo2Error::Synthesis)?;
+            let chips = (&arithmetic_chip, &signed_chip);
+            let mut layouter = layouter.namespace(|| format!("expr_{}", idx));
+            let left = assign_expression(&mut layouter, chips, &self.ir, &field_cells, lhs)?;
+            let right = assign_expression(&mut layouter, chips, &self.ir, &field_cells, rhs)?;
+            assert_comparison(
+                &comparison_chip,
+                layouter.namespace(|| "compare"),
+                *op,
+                left,
+                right,
+                num_bits,
+            )?;
+        }
+
+        // Arithmetic relations apply element by element; every field in a relation must
+        // have the same number of cells (validate_circuit_ir reports mismatches up front).
+        // Signed relations run on decoded values, so the offset cancels out.
+        for (rel_idx, relation) in arithmetic_relations(&self.ir).iter().enumerate() {
+            let lookup = |name: &str| field_cells.get(name).copied().ok_or(Halo2Error::Synthesis);
+            let result = lookup(relation.result)?;
+            let first = lookup(relation.first)?;
+            let rest = relation
+                .rest
+                .iter()
+                .map(|(op, name)| Ok((*op, lookup(name)?)))
+                .collect::<Result<Vec<_>, Halo2Error>>()?;
+            if rest.is_empty()
+                || first.len() != result.len()
+                || rest.iter().any(|(_, cells)| cells.len() != result.len())
+            {
+                return Err(Halo2Error::Synthesis);
+            }
+
+            let signed = self
+                .ir
+                .private_witnesses
+                .iter()
+                .any(|f| f.name == relation.result && f.field_type.is_signed());
+
+            for (elem, out) in result.iter().enumerate() {
+                let out =
+                    decode_if_signed(&signed_chip, signed, layouter.namespace(|| "decode"), out)?;
+                let mut acc = decode_if_signed(
+                    &signed_chip,
+                    signed,
+                    layouter.namespace(|| "decode"),
+                    &first[elem],
+                )?;
+                for (step, (op, cells)) in rest.iter().enumerate() {
+                    let rhs = decode_if_signed(
+                        &signed_chip,
+                        signed,
+                        layouter.namespace(|| "decode"),
+                        &cells[elem],
+                    )?;
+                    let ns = || format!("arith_{}_{}_{}", rel_idx, elem, step);
+                    if step + 1 == rest.len() {
+                        arithmetic_chip.constrain(layouter.namespace(ns), *op, &acc, &rhs, &out)?;
+                    } else {
+                        acc = arithmetic_chip.apply(layouter.namespace(ns), *op, &acc, &rhs)?;
                     }
-                    Ok(())
-                },
+                }
+            }
+        }
+
+        // Commitments: Poseidon(value, randomness) must equal the named public input.
+        for (idx, constraint) in self.ir.inter_field_constraints.iter().enumerate() {
+            let InterFieldConstraint::CommitmentVerification {
+                commitment,
+                value,
+                randomness,
+                hash_type,
+            } = constraint
+            else {
+                continue;
+            };
+            if *hash_type != HashType::Poseidon {
+                return Err(Halo2Error::Synthesis);
+            }
+            let scalar = |name: &str| match field_cells.get(name) {
+                Some([cell]) => Ok(cell.clone()),
+                _ => Err(Halo2Error::Synthesis),
+            };
+            let instance_row = self
+                .ir
+                .public_inputs
+                .iter()
+                .position(|f| &f.name == commitment)
+                .ok_or(Halo2Error::Synthesis)?;
+
+            let hash = poseidon_chip.commit(
+                layouter.namespace(|| format!("commitment_{}", idx)),
+                scalar(value)?,
+                scalar(randomness)?,
             )?;
+            layouter.constrain_instance(hash.cell(), config.instance, instance_row)?;
         }
 
-        // Assign public inputs from the instance column into advice cells.
-        // NOTE: In the generic builder, these public inputs are accessible but
-        // not linked to any private witness via constraints. The generic builder
-        // cannot infer cross-field relationships (e.g. commitment == Poseidon(witness)).
-        // Hand-written circuits (tx_privacy, state_mask, private_vote) handle this
-        // explicitly using constrain_instance() after computing derived values.
-        if !self.public_values.is_empty() {
+        // Merkle membership: hashing the leaf up the sibling path must reproduce the root.
+        for (idx, proof) in merkle_proofs(&self.ir).iter().enumerate() {
+            let lookup = |name: &str| field_cells.get(name).copied().ok_or(Halo2Error::Synthesis);
+            let (leaf, root) = match (lookup(proof.leaf)?, lookup(proof.root)?) {
+                ([leaf], [root]) => (leaf, root),
+                _ => return Err(Halo2Error::Synthesis),
+            };
+            let siblings = lookup(proof.path)?;
+            let indices = lookup(proof.indices)?;
+            if proof.depth.is_some_and(|depth| depth != siblings.len()) {
+                return Err(Halo2Error::Synthesis);
+            }
+
+            let computed = merkle_chip.verify_membership(
+                layouter.namespace(|| format!("merkle_{}", idx)),
+                leaf.clone(),
+                siblings,
+                indices,
+            )?;
             layouter.assign_region(
-                || "public_inputs",
-                |mut region| {
-                    for (idx, _) in self.public_values.iter().enumerate() {
-                        region.assign_advice_from_instance(
-                            || format!("public_{}", idx),
-                            config.instance,
-                            idx,
-                            config.advice[0],
-                            idx,
+                || format!("merkle_root_{}", idx),
+                |mut region| region.constrain_equal(computed.cell(), root.cell()),
+            )?;
+        }
+
+        // Public inputs: each bound value is constrained to its instance row. Inputs that are
+        // checked by a commitment were already constrained above.
+        for (idx, constraint) in self.ir.inter_field_constraints.iter().enumerate() {
+            let InterFieldConstraint::PublicInputBinding { public_input, source } = constraint
+            else {
+                continue;
+            };
+            let instance_row = self
+                .ir
+                .public_inputs
+                .iter()
+                .position(|f| &f.name == public_input)
+                .ok_or(Halo2Error::Synthesis)?;
+            let scalar = |name: &str| match field_cells.get(name) {
+                Some([cell]) => Ok(cell.clone()),
+                _ => Err(Halo2Error::Synthesis),
+            };
+
+            let bound = match source {
+                PublicInputSource::Field(name) => scalar(name)?,
+                PublicInputSource::Arithmetic { operation, operands } => {
+                    // Signed operands enter decoded, as in arithmetic relations, so the
+                    // instance holds the integer result (modulo `p`).
+                    let mut values = Vec::with_capacity(operands.len());
+                    for name in operands {
+                        let signed = self
+                            .ir
+                            .private_witnesses
+                            .iter()
+                            .any(|f| &f.name == name && f.field_type.is_signed());
+                        let cell = scalar(name)?;
+                        values.push(decode_if_signed(
+                            &signed_chip,
+                            signed,
+                            layouter.namespace(|| "decode"),
+                            &cell,
+                        )?);
+                    }
+                    let (first, rest) = values.split_first().ok_or(Halo2Error::Synthesis)?;
+                    let mut acc = first.clone();
+                    for (step, value) in rest.iter().enumerate() {
+                        acc = arithmetic_chip.apply(
+                            layouter.namespace(|| format!("public_{}_{}", idx, step)),
+                            *operation,
+                            &acc,
+                            value,
                         )?;
                     }
-                    Ok(())
-                },
-            )?;
+                    acc
+                }
+            };
+            layouter.constrain_instance(bound.cell(), config.instance, instance_row)?;
         }
 
         Ok(())
     }
 }
 
-pub fn validate_circuit_ir(ir: &CircuitIR) -> Result<(), CompilerError> {
-    let max_rows = 1 << ir.circuit_config.k();
-    let required_rows = estimate_required_rows(ir);
+/// Range chip for field constraints, as selected by the IR's circuit config.
+enum FieldRangeChip {
+    Bits(RangeProofChip),
+    Lookup(LookupRangeChip),
+}
 
-    if required_rows > max_rows {
-        return Err(CompilerError::Other(format!(
-            "Circuit requires {} rows but k={} only provides {} rows",
-            required_rows,
-            ir.circuit_config.k(),
-            max_rows
-        )));
+impl FieldRangeChip {
+    fn check_range(
+        &self,
+        layouter: impl Layouter<Fp>,
+        value: AssignedCell<Fp, Fp>,
+        num_bits: usize,
+    ) -> Result<(), Halo2Error> {
+        match self {
+            FieldRangeChip::Bits(chip) => chip.check_range(layouter, value, num_bits),
+            FieldRangeChip::Lookup(chip) => chip.check_range(layouter, value, num_bits),
+        }
     }
 
+    fn check_range_bounded(
+        &self,
+        layouter: impl Layouter<Fp>,
+        value: AssignedCell<Fp, Fp>,
+        min: Fp,
+        max: Fp,
+        num_bits: usize,
+    ) -> Result<(), Halo2Error> {
+        match self {
+            FieldRangeChip::Bits(chip) => {
+                chip.check_range_bounded(layouter, value, min, max, num_bits)
+            }
+            FieldRangeChip::Lookup(chip) => {
+                chip.check_range_bounded(layouter, value, min, max, num_bits)
+            }
+        }
+    }
+}
+
+/// Proves `left op right`. Ordering comparisons assume both operands fit in `num_bits` bits.
+fn assert_comparison(
+    chip: &ComparisonChip,
+    layouter: impl Layouter<Fp>,
+    op: ComparisonOp,
+    left: AssignedCell<Fp, Fp>,
+    right: AssignedCell<Fp, Fp>,
+    num_bits: usize,
+) -> Result<(), Halo2Error> {
+    match op {
+        ComparisonOp::GreaterThan => chip.assert_gt(layouter, left, right, num_bits),
+        ComparisonOp::GreaterThanOrEqual => chip.assert_gte(layouter, left, right, num_bits),
+        ComparisonOp::LessThan => chip.assert_lt(layouter, left, right, num_bits),
+        ComparisonOp::LessThanOrEqual => chip.assert_lte(layouter, left, right, num_bits),
+        ComparisonOp::Equal => chip.assert_eq(layouter, left, right),
+        ComparisonOp::NotEqual => chip.assert_neq(layouter, left, right),
+    }
+}
+
+/// Decodes an offset-encoded signed cell to its field-native value; unsigned cells pass through.
+fn decode_if_signed(
+    chip: &SignedChip,
+    signed: bool,
+    layouter: impl Layouter<Fp>,
+    cell: &AssignedCell<Fp, Fp>,
+) -> Result<AssignedCell<Fp, Fp>, Halo2Error> {
+    if signed {
+        chip.decode(layouter, cell)
+    } else {
+        Ok(cell.clone())
+    }
+}
+
+/// Assigns the value of `expr`. Signed fields enter decoded, so the result is the integer
+/// value of the expression (modulo `p`).
+fn assign_expression(
+    layouter: &mut impl Layouter<Fp>,
+    chips: (&ArithmeticChip, &SignedChip),
+    ir: &CircuitIR,
+    field_cells: &HashMap<&str, &[AssignedCell<Fp, Fp>]>,
+    expr: &ArithExpr,
+) -> Result<AssignedCell<Fp, Fp>, Halo2Error> {
+    let (arithmetic_chip, signed_chip) = chips;
+    match expr {
+        ArithExpr::Field(name) => {
+            let Some([cell]) = field_cells.get(name.as_str()) else {
+                return Err(Halo2Error::Synthesis);
+            };
+            let signed =
+                ir.private_witnesses.iter().any(|f| &f.name == name && f.field_type.is_signed());
+            decode_if_signed(signed_chip, signed, layouter.namespace(|| "decode"), cell)
+        }
+        ArithExpr::Constant(value) => {
+            arithmetic_chip.load_constant(layouter.namespace(|| "constant"), Fp::from_u128(*value))
+        }
+        ArithExpr::Binary { op, lhs, rhs } => {
+            let lhs = assign_expression(layouter, chips, ir, field_cells, lhs)?;
+            let rhs = assign_expression(layouter, chips, ir, field_cells, rhs)?;
+            arithmetic_chip.apply(layouter.namespace(|| format!("{:?}", op)), *op, &lhs, &rhs)
+        }
+    }
+}
+
+/// Bit width for an ordering comparison between operands of the given types.
+///
+/// At least 64 bits, so that `u64` thresholds compare correctly against narrower fields;
+/// wider when an operand is a `u128`.
+fn comparison_bits<'a>(types: impl IntoIterator<Item = &'a ZkType>) -> usize {
+    types.into_iter().filter_map(ZkType::range_bits).fold(64, usize::max)
+}
+
+pub fn validate_circuit_ir(ir: &CircuitIR) -> Result<(), CompilerError> {
     for field in ir.private_witnesses.iter().chain(&ir.public_inputs) {
         validate_zk_type(&field.field_type)?;
     }
+    validate_arithmetic_relations(ir)?;
+    validate_commitments(ir)?;
+    validate_merkle_proofs(ir)?;
+    validate_public_inputs(ir)?;
+    validate_comparison_checks(ir)?;
+    validate_expression_checks(ir)?;
+
+    // Exact rows from a dry-run synthesis, so a hand-picked k is never too small
+    let report = measure_rows(ir)?;
+    if report.min_k > ir.circuit_config.k() {
+        let k = ir.circuit_config.k();
+        return Err(CompilerError::Other(format!(
+            "Circuit requires {} rows but k={} only provides {} usable rows (minimum k={})",
+            report.used_rows,
+            k,
+            (1usize << k).saturating_sub(report.blinding_rows),
+            report.min_k
+        )));
+    }
+
+    Ok(())
+}
+
+/// An arithmetic relation normalized to `result = first ∘ rest[0] ∘ rest[1] ∘ …`.
+struct ArithmeticRelation<'a> {
+    result: &'a str,
+    first: &'a str,
+    rest: Vec<(ArithOp, &'a str)>,
+}
+
+/// Collects per-field and inter-field arithmetic relations in synthesis order.
+fn arithmetic_relations(ir: &CircuitIR) -> Vec<ArithmeticRelation<'_>> {
+    let per_field =
+        ir.private_witnesses.iter().flat_map(|f| &f.constraints).filter_map(|c| match c {
+            Constraint::ArithmeticRelation { lhs_field, rhs_fields, .. } => {
+                let (first, rest) = rhs_fields.split_first()?;
+                Some(ArithmeticRelation {
+                    result: lhs_field,
+                    first: &first.0,
+                    rest: rest.iter().map(|(name, op)| (*op, name.as_str())).collect(),
+                })
+            }
+            _ => None,
+        });
+    let inter_field = ir.inter_field_constraints.iter().filter_map(|c| match c {
+        InterFieldConstraint::ArithmeticRelation { result_field, operation, operands } => {
+            let (first, rest) = operands.split_first()?;
+            Some(ArithmeticRelation {
+                result: result_field,
+                first,
+                rest: rest.iter().map(|name| (*operation, name.as_str())).collect(),
+            })
+        }
+        _ => None,
+    });
+    per_field.chain(inter_field).collect()
+}
+
+/// Fields used as Merkle direction bits. The Merkle swap gate constrains them to be boolean.
+pub(crate) fn merkle_index_fields(ir: &CircuitIR) -> Vec<&str> {
+    merkle_proofs(ir).into_iter().map(|proof| proof.indices).collect()
+}
+
+/// A Merkle membership check, from either a field constraint or an inter-field constraint.
+struct MerkleProofRef<'a> {
+    leaf: &'a str,
+    path: &'a str,
+    root: &'a str,
+    indices: &'a str,
+    /// Declared depth; inter-field checks take it from the length of `path`.
+    depth: Option<usize>,
+}
+
+fn merkle_proofs(ir: &CircuitIR) -> Vec<MerkleProofRef<'_>> {
+    let per_field =
+        ir.private_witnesses.iter().flat_map(|f| &f.constraints).filter_map(|c| match c {
+            Constraint::MerkleProof {
+                leaf_field,
+                path_field,
+                root_field,
+                indices_field,
+                tree_depth,
+            } => Some(MerkleProofRef {
+                leaf: leaf_field,
+                path: path_field,
+                root: root_field,
+                indices: indices_field,
+                depth: Some(*tree_depth),
+            }),
+            _ => None,
+        });
+    let inter_field = ir.inter_field_constraints.iter().filter_map(|c| match c {
+        InterFieldConstraint::MerkleVerification { leaf, path, root, indices } => {
+            Some(MerkleProofRef { leaf, path, root, indices, depth: None })
+        }
+        _ => None,
+    });
+    per_field.chain(inter_field).collect()
+}
+
+fn validate_merkle_proofs(ir: &CircuitIR) -> Result<(), CompilerError> {
+    for proof in merkle_proofs(ir) {
+        let size = |name: &str| {
+            ir.private_witnesses
+                .iter()
+                .find(|f| f.name == name)
+                .map(|f| f.field_type.num_elements())
+                .ok_or_else(|| {
+                    CompilerError::Other(format!(
+                        "Merkle proof for '{}' references unknown private field '{}'",
+                        proof.leaf, name
+                    ))
+                })
+        };
+        for name in [proof.leaf, proof.root] {
+            if size(name)? != 1 {
+                return Err(CompilerError::Other(format!(
+                    "Merkle proof for '{}': '{}' must be a scalar field",
+                    proof.leaf, name
+                )));
+            }
+        }
+        let depth = proof.depth.unwrap_or(size(proof.path)?);
+        if depth == 0 || depth > MerklePath::MAX_DEPTH {
+            return Err(CompilerError::Other(format!(
+                "Merkle proof for '{}': depth {} must be between 1 and {}",
+                proof.leaf,
+                depth,
+                MerklePath::MAX_DEPTH
+            )));
+        }
+        for name in [proof.path, proof.indices] {
+            let len = size(name)?;
+            if len != depth {
+                return Err(CompilerError::Other(format!(
+                    "Merkle proof for '{}': '{}' has {} elements, expected depth {}",
+                    proof.leaf, name, len, depth
+                )));
+            }
+        }
+    }
+    Ok(())
+}
+
+fn validate_arithmetic_relations(ir: &CircuitIR) -> Result<(), CompilerError> {
+    let num_operands = |c: &Constraint| match c {
+        Constraint::ArithmeticRelation { rhs_fields, .. } => Some(rhs_fields.len()),
+        _ => None,
+    };
+    let too_few = ir
+        .private_witnesses
+        .iter()
+        .flat_map(|f| &f.constraints)
+        .filter_map(num_operands)
+        .chain(ir.inter_field_constraints.iter().filter_map(|c| match c {
+            InterFieldConstraint::ArithmeticRelation { operands, .. } => Some(operands.len()),
+            _ => None,
+        }))
+        .any(|n| n < 2);
+    if too_few {
+        return Err(CompilerError::Other(
+            "Arithmetic relation requires at least 2 operands".to_string(),
+        ));
+    }
+
+    for relation in arithmetic_relations(ir) {
+        let names = std::iter::once(relation.result)
+            .chain(std::iter::once(relation.first))
+            .chain(relation.rest.iter().map(|(_, name)| *name));
+        let mut sizes = Vec::new();
+        let mut signedness = Vec::new();
+        for name in names {
+            let field = ir.private_witnesses.iter().find(|f| f.name == name).ok_or_else(|| {
+                CompilerError::Other(format!(
+                    "Arithmetic relation for '{}' references unknown private field '{}'",
+                    relation.result, name
+                ))
+            })?;
+            sizes.push((name, field.field_type.num_elements()));
+            signedness.push((name, field.field_type.is_signed()));
+        }
+        if let Some((name, _)) = signedness.iter().find(|(_, s)| *s != signedness[0].1) {
+            return Err(CompilerError::Other(format!(
+                "Arithmetic relation for '{}' mixes signed and unsigned fields ('{}' and '{}')",
+                relation.result, signedness[0].0, name
+            )));
+        }
+        if let Some((name, size)) = sizes.iter().find(|(_, size)| *size != sizes[0].1) {
+            return Err(CompilerError::Other(format!(
+                "Arithmetic relation for '{}' mixes fields of different sizes ('{}' has {} \
+                 elements, '{}' has {})",
+                relation.result, sizes[0].0, sizes[0].1, name, size
+            )));
+        }
+    }
 
     Ok(())
 }
 
-fn estimate_required_rows(ir: &CircuitIR) -> usize {
-    ir.estimate_rows()
+/// Checks that every public input is bound to a witness-derived value, either directly or
+/// as the output of a commitment, and that every binding refers to scalar private fields.
+fn validate_public_inputs(ir: &CircuitIR) -> Result<(), CompilerError> {
+    for constraint in &ir.inter_field_constraints {
+        let InterFieldConstraint::PublicInputBinding { public_input, source } = constraint else {
+            continue;
+        };
+        if !ir.public_inputs.iter().any(|f| &f.name == public_input) {
+            return Err(CompilerError::Other(format!(
+                "Binding for '{}' does not refer to a public input",
+                public_input
+            )));
+        }
+        let operands = match source {
+            PublicInputSource::Field(name) => std::slice::from_ref(name),
+            PublicInputSource::Arithmetic { operands, .. } => {
+                if operands.len() < 2 {
+                    return Err(CompilerError::Other(format!(
+                        "Binding for '{}' requires at least 2 operands",
+                        public_input
+                    )));
+                }
+                operands.as_slice()
+            }
+        };
+        for name in operands {
+            match ir.private_witnesses.iter().find(|f| &f.name == name) {
+                Some(field) if field.field_type.num_elements() == 1 => {}
+                Some(_) => {
+                    return Err(CompilerError::Other(format!(
+                        "Binding for '{}': '{}' must be a scalar field",
+                        public_input, name
+                    )));
+                }
+                None => {
+                    return Err(CompilerError::Other(format!(
+                        "Binding for '{}' references unknown private field '{}'",
+                        public_input, name
+                    )));
+                }
+            }
+        }
+    }
+
+    for input in &ir.public_inputs {
+        let bound = ir.inter_field_constraints.iter().any(|c| match c {
+            InterFieldConstraint::PublicInputBinding { public_input, .. } => {
+                public_input == &input.name
+            }
+            InterFieldConstraint::CommitmentVerification { commitment, .. } => {
+                commitment == &input.name
+            }
+            _ => false,
+        });
+        if !bound {
+            return Err(CompilerError::Other(format!(
+                "Public input '{}' is not bound to any private witness or expression",
+                input.name
+            )));
+        }
+    }
+    Ok(())
+}
+
+/// Checks field-to-field comparisons. Ordering comparisons are only sound when both
+/// operands are range-checked to a known bit width.
+fn validate_comparison_checks(ir: &CircuitIR) -> Result<(), CompilerError> {
+    for constraint in &ir.inter_field_constraints {
+        let InterFieldConstraint::ComparisonCheck { left_field, right_field, op } = constraint
+        else {
+            continue;
+        };
+        let mut signedness = Vec::with_capacity(2);
+        for name in [left_field, right_field] {
+            let field = ir.private_witnesses.iter().find(|f| &f.name == name).ok_or_else(|| {
+                CompilerError::Other(format!(
+                    "Comparison '{} {:?} {}' references unknown private field '{}'",
+                    left_field, op, right_field, name
+                ))
+            })?;
+            if field.field_type.num_elements() != 1 {
+                return Err(CompilerError::Other(format!(
+                    "Comparison '{} {:?} {}': '{}' must be a scalar field",
+                    left_field, op, right_field, name
+                )));
+            }
+            let ordering = !matches!(op, ComparisonOp::Equal | ComparisonOp::NotEqual);
+            if ordering && field.field_type.range_bits().is_none() {
+                return Err(CompilerError::Other(format!(
+                    "Comparison '{} {:?} {}': '{}' has type {:?}, but ordering comparisons \
+                     require a range-checked integer type",
+                    left_field, op, right_field, name, field.field_type
+                )));
+            }
+            signedness.push(field.field_type.is_signed());
+        }
+        // Signed fields are offset-encoded, so their cells are not comparable to unsigned ones
+        if signedness[0] != signedness[1] {
+            return Err(CompilerError::Other(format!(
+                "Comparison '{} {:?} {}' mixes signed and unsigned fields",
+                left_field, op, right_field
+            )));
+        }
+    }
+    Ok(())
+}
+
+fn validate_expression_checks(ir: &CircuitIR) -> Result<(), CompilerError> {
+    for constraint in &ir.inter_field_constraints {
+        if let InterFieldConstraint::ExpressionCheck { lhs, op, rhs } = constraint {
+            expression_check_bits(ir, lhs, *op, rhs)?;
+        }
+    }
+    Ok(())
+}
+
+/// Type-checks `lhs op rhs` and returns the bit width its comparison is proven at.
+///
+/// Every field must be a scalar private witness. Ordering comparisons also need integer
+/// fields, so that `|lhs - rhs| < 2^n` for the returned `n`; `n` may not exceed
+/// [`RangeProofChip::MAX_BOUNDED_BITS`], otherwise the range-checked difference could wrap
+/// modulo `p`. Equality holds modulo `p` and accepts any scalar field.
+pub(crate) fn expression_check_bits(
+    ir: &CircuitIR,
+    lhs: &ArithExpr,
+    op: ComparisonOp,
+    rhs: &ArithExpr,
+) -> Result<usize, CompilerError> {
+    let check = || format!("{} {} {}", lhs, op, rhs);
+    for name in lhs.fields().into_iter().chain(rhs.fields()) {
+        let field = ir.private_witnesses.iter().find(|f| f.name == name).ok_or_else(|| {
+            CompilerError::Other(format!(
+                "Expression check '{}' references unknown private field '{}'",
+                check(),
+                name
+            ))
+        })?;
+        if field.field_type.num_elements() != 1 {
+            return Err(CompilerError::Other(format!(
+                "Expression check '{}': '{}' must be a scalar field",
+                check(),
+                name
+            )));
+        }
+    }
+    if [lhs, rhs].into_iter().any(ArithExpr::divides) {
+        return Err(CompilerError::Other(format!(
+            "Expression check '{}': division is not supported",
+            check()
+        )));
+    }
+    if matches!(op, ComparisonOp::Equal | ComparisonOp::NotEqual) {
+        return Ok(0);
+    }
+
+    // Signed fields are compared decoded, so an i64 spans (-2^63, 2^63]
+    let field_bits = |name: &str| {
+        let field = ir.private_witnesses.iter().find(|f| f.name == name)?;
+        if field.field_type.is_signed() {
+            Some(64)
+        } else {
+            field.field_type.range_bits()
+        }
+    };
+    let (Some(l), Some(r)) = (lhs.magnitude_bits(&field_bits), rhs.magnitude_bits(&field_bits))
+    else {
+        return Err(CompilerError::Other(format!(
+            "Expression check '{}': ordering comparisons require range-checked integer fields",
+            check()
+        )));
+    };
+    let num_bits = l.max(r) + 1;
+    if num_bits > RangeProofChip::MAX_BOUNDED_BITS {
+        return Err(CompilerError::Other(format!(
+            "Expression check '{}' needs {} bits, more than the {} a comparison can prove",
+            check(),
+            num_bits,
+            RangeProofChip::MAX_BOUNDED_BITS
+        )));
+    }
+    Ok(num_bits)
+}
+
+fn validate_commitments(ir: &CircuitIR) -> Result<(), CompilerError> {
+    for constraint in &ir.inter_field_constraints {
+        let InterFieldConstraint::CommitmentVerification {
+            commitment,
+            value,
+            randomness,
+            hash_type,
+        } = constraint
+        else {
+            continue;
+        };
+        if *hash_type != HashType::Poseidon {
+            return Err(CompilerError::Other(format!(
+                "Commitment '{}' uses {:?}, but the circuit builder only supports Poseidon",
+                commitment, hash_type
+            )));
+        }
+        if !ir.public_inputs.iter().any(|f| &f.name == commitment) {
+            return Err(CompilerError::Other(format!(
+                "Commitment '{}' is not a public input",
+                commitment
+            )));
+        }
+        for name in [value, randomness] {
+            match ir.private_witnesses.iter().find(|f| &f.name == name) {
+                Some(field) if field.field_type.num_elements() == 1 => {}
+                Some(_) => {
+                    return Err(CompilerError::Other(format!(
+                        "Commitment '{}' input '{}' must be a scalar field",
+                        commitment, name
+                    )));
+                }
+                None => {
+                    return Err(CompilerError::Other(format!(
+                        "Commitment '{}' references unknown private field '{}'",
+                        commitment, name
+                    )));
+                }
+            }
+        }
+    }
+    Ok(())
 }
 
-fn validate_zk_type(zk_type: &ZkType) -> Result<(), CompilerError> {
+pub(crate) fn validate_zk_type(zk_type: &ZkType) -> Result<(), CompilerError> {
     match zk_type {
         ZkType::Field => Ok(()),
         ZkType::U8 | ZkType::U16 | ZkType::U32 | ZkType::U64 | ZkType::U128 => Ok(()),
@@ -401,14 +1132,15 @@ fn validate_zk_type(zk_type: &ZkType) -> Result<(), CompilerError> {
         ZkType::Bool => Ok(()),
         ZkType::Address => Ok(()),
         ZkType::Bytes32 => Ok(()),
-        ZkType::Array { element_type: _, size } => {
-            if *size > 1024 {
+        ZkType::Array { element_type, .. } => {
+            if zk_type.num_elements() > MAX_ARRAY_ELEMENTS {
                 Err(CompilerError::UnsupportedZkType(format!(
-                    "Array size {} exceeds maximum 1024",
-                    size
+                    "Array size {} exceeds maximum {}",
+                    zk_type.num_elements(),
+                    MAX_ARRAY_ELEMENTS
                 )))
             } else {
-                Ok(())
+                validate_zk_type(element_type)
             }
         }
     }
@@ -468,7 +1200,7 @@ mod tests {
     }
 
     #[test]
-    fn test_estimate_required_rows() {
+    fn test_measured_required_rows() {
         let input = r#"
             struct MultiField {
                 #[zk_private]
@@ -483,9 +1215,11 @@ mod tests {
         let parsed = parse_contract(input).unwrap();
         let ir = transform_to_ir(parsed).unwrap();
 
-        let rows = estimate_required_rows(&ir);
-        // 3 witnesses + constraints + padding
-        assert!(rows >= 6); // 3 witnesses * 2 (padding factor)
+        let report = measure_rows(&ir).unwrap();
+        // Two 64-bit decompositions (65 rows each) and a boolean check, stacked by the
+        // floor planner in the shared range-check columns
+        assert!(report.used_rows >= 2 * 65 + 2);
+        assert_eq!(report.min_k, ir.circuit_config.k());
     }
 
     #[test]
@@ -0,0 +1,230 @@
+//! Verifying data embedded in generated verifier crates
+//!
+//! halo2_proofs 0.3 cannot serialize a `VerifyingKey`, so a crate generated by
+//! [`WasmCodegen`](super::WasmCodegen) carries what it takes to rebuild one without this
+//! compiler: the circuit's [`CircuitLayout`](zerostyl_layout::CircuitLayout), the IPA
+//! params for its `k`, and a digest of the verifying key computed at codegen time.
+//! `zerostyl_verifier::CompiledVerifier` replays the layout through `keygen_vk` and
+//! refuses to verify anything if the regenerated key's digest differs.
+//!
+//! Public inputs cross the FFI boundary as consecutive 32-byte little-endian field reprs,
+//! one per public input (see [`encode_public_inputs`]).
+
+use std::path::Path;
+
+use halo2_proofs::{plonk::keygen_vk, poly::commitment::Params};
+use halo2curves::pasta::{EqAffine, Fp};
+
+use crate::circuit::{validate_circuit_ir, CircuitBuilder};
+use crate::{CircuitIR, CompilerError, Result};
+
+pub use zerostyl_layout::{
+    decode_public_inputs, encode_public_inputs, vk_digest, PUBLIC_INPUT_LEN, VERIFY_OK,
+    VERIFY_REJECTED, VERIFY_UNAVAILABLE,
+};
+
+/// File names of the verifying data inside a generated crate's `src/` directory.
+pub const LAYOUT_FILE: &str = "circuit_layout.bin";
+pub const PARAMS_FILE: &str = "params.bin";
+pub const VK_DIGEST_FILE: &str = "vk_digest.bin";
+
+/// Everything a generated verifier needs to rebuild the circuit's verifying key.
+#[derive(Debug, Clone)]
+pub struct VerifierData {
+    /// The circuit's layout, as written by `CircuitLayout::to_bytes`.
+    pub layout: Vec<u8>,
+    /// IPA params for the circuit's `k`, as written by `Params::write`.
+    pub params: Vec<u8>,
+    /// [`vk_digest`] of the circuit's verifying key for `params`.
+    pub vk_digest: [u8; 32],
+}
+
+impl VerifierData {
+    /// Generates params, the verifying key and the layout for `ir`.
+    ///
+    /// The params are the same `Params::new(k)` that [`NativeProver`](super::prover::NativeProver)
+    /// uses, so its proofs verify against this data.
+    ///
+    /// # Errors
+    ///
+    /// Returns the validation error if `ir` is not a well-formed circuit, or
+    /// [`CompilerError::Other`] if key generation or layout recording fails.
+    pub fn generate(ir: &CircuitIR) -> Result<VerifierData> {
+        validate_circuit_ir(ir)?;
+        VerifierData::from_params(ir, &Params::<EqAffine>::new(ir.circuit_config.k()))
+    }
+
+    /// Like [`VerifierData::generate`], with params already at hand (e.g. loaded from a
+    /// [`KeyManager`](super::keys::KeyManager) cache). `params` must be for the circuit's `k`.
+    pub fn from_params(ir: &CircuitIR, params: &Params<EqAffine>) -> Result<VerifierData> {
+        validate_circuit_ir(ir)?;
+        let k = ir.circuit_config.k();
+        if params.k() != k {
+            return Err(CompilerError::Other(format!(
+                "Params are for k={}, circuit '{}' needs k={}",
+                params.k(),
+                ir.name,
+                k
+            )));
+        }
+        let circuit = CircuitBuilder::new(ir.clone()).build::<Fp>();
+        let vk = keygen_vk(params, &circuit).map_err(|e| {
+            CompilerError::Other(format!("Failed to generate verification key: {:?}", e))
+        })?;
+        let layout = zerostyl_layout::record(k, &circuit).map_err(|e| {
+            CompilerError::Other(format!("Failed to record the layout of '{}': {}", ir.name, e))
+        })?;
+
+        let mut params_bytes = Vec::new();
+        params
+            .write(&mut params_bytes)
+            .map_err(|e| CompilerError::Other(format!("Failed to serialize params: {}", e)))?;
+
+        Ok(VerifierData {
+            layout: layout.to_bytes(),
+            params: params_bytes,
+            vk_digest: vk_digest(&vk),
+        })
+    }
+
+    /// Writes the data to [`LAYOUT_FILE`], [`PARAMS_FILE`] and [`VK_DIGEST_FILE`] in `dir`.
+    pub fn write_to(&self, dir: &Path) -> Result<()> {
+        let write = |name: &str, bytes: &[u8]| {
+            std::fs::write(dir.join(name), bytes)
+                .map_err(|e| CompilerError::Other(format!("Failed to write {}: {}", name, e)))
+        };
+        write(LAYOUT_FILE, &self.layout)?;
+        write(PARAMS_FILE, &self.params)?;
+        write(VK_DIGEST_FILE, &self.vk_digest)
+    }
+
+    /// Reads data written by [`VerifierData::write_to`].
+    pub fn read_from(dir: &Path) -> Result<VerifierData> {
+        let read = |name: &str| {
+            std::fs::read(dir.join(name))
+                .map_err(|e| CompilerError::Other(format!("Failed to read {}: {}", name, e)))
+        };
+        let vk_digest = read(VK_DIGEST_FILE)?
+            .try_into()
+            .map_err(|_| CompilerError::Other(format!("{} must hold 32 bytes", VK_DIGEST_FILE)))?;
+        Ok(VerifierData { layout: read(LAYOUT_FILE)?, params: read(PARAMS_FILE)?, vk_digest })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::codegen::keys::KeyMetadata;
+    use crate::codegen::prover::NativeProver;
+    use crate::gadgets::PoseidonCommitmentChip;
+    use crate::IrBuilder;
+    use crate::{RangeChip, ZkType};
+    use halo2_proofs::plonk::{verify_proof, SingleVerifier};
+    use halo2_proofs::transcript::{Blake2bRead, Challenge255};
+    use std::sync::OnceLock;
+    use tempfile::TempDir;
+    use zerostyl_layout::{EmbeddedLayout, LayoutCircuit};
+
+    fn note_ir() -> CircuitIR {
+        IrBuilder::new("Note")
+            .private("amount", ZkType::U64)
+            .range(0..1_000)
+            .private("blinding", ZkType::Field)
+            .public("commitment")
+            .commit_poseidon("amount", "blinding")
+            .build()
+            .unwrap()
+    }
+
+    /// The verifying data of [`note_ir`], loaded through the files a generated crate embeds.
+    fn note_data() -> &'static VerifierData {
+        static DATA: OnceLock<VerifierData> = OnceLock::new();
+        DATA.get_or_init(|| {
+            let dir = TempDir::new().unwrap();
+            VerifierData::generate(&note_ir()).unwrap().write_to(dir.path()).unwrap();
+            VerifierData::read_from(dir.path()).unwrap()
+        })
+    }
+
+    struct NoteLayout;
+
+    impl EmbeddedLayout for NoteLayout {
+        fn layout_bytes() -> &'static [u8] {
+            &note_data().layout
+        }
+    }
+
+    #[test]
+    fn test_layout_regenerates_the_verifying_key() {
+        let ir = note_ir();
+        let k = ir.circuit_config.k();
+        let (amount, blinding) = (Fp::from(250), Fp::from(99));
+        let commitment = PoseidonCommitmentChip::hash_outside_circuit(amount, blinding);
+
+        let circuit = CircuitBuilder::new(ir.clone())
+            .build::<Fp>()
+            .with_witnesses(vec![amount, blinding])
+            .unwrap()
+            .with_public_inputs(vec![commitment])
+            .unwrap();
+        let cache = TempDir::new().unwrap();
+        let mut prover = NativeProver::with_cache_dir(circuit, k, cache.path()).unwrap();
+        prover
+            .setup(KeyMetadata {
+                circuit_name: ir.name.clone(),
+                k,
+                num_public_inputs: 1,
+                num_private_witnesses: 2,
+            })
+            .unwrap();
+        let proof = prover.generate_proof(&[vec![commitment]]).unwrap();
+
+        let data = note_data();
+        assert_eq!(vk_digest(prover.verifying_key().unwrap()), data.vk_digest);
+
+        // The replayed layout yields the prover's key, without the compiler's circuit
+        let params = Params::<EqAffine>::read(&mut &data.params[..]).unwrap();
+        let circuit = LayoutCircuit::<NoteLayout>::load().unwrap();
+        let vk = keygen_vk(&params, &circuit).unwrap();
+        assert_eq!(vk_digest(&vk), data.vk_digest);
+
+        let verifies = |inputs: &[Fp]| {
+            let mut transcript = Blake2bRead::<_, _, Challenge255<_>>::init(&proof[..]);
+            let strategy = SingleVerifier::new(&params);
+            verify_proof(&params, &vk, strategy, &[&[inputs]], &mut transcript).is_ok()
+        };
+        assert!(verifies(&[commitment]));
+        assert!(!verifies(&[commitment + Fp::one()]));
+    }
+
+    #[test]
+    fn test_digest_identifies_the_circuit() {
+        let other = IrBuilder::new("Other")
+            .private("amount", ZkType::U64)
+            .private("blinding", ZkType::Field)
+            .public("commitment")
+            .bind_field("amount")
+            .build()
+            .unwrap();
+        let other = VerifierData::generate(&other).unwrap();
+        assert_ne!(other.vk_digest, note_data().vk_digest);
+        assert_ne!(other.layout, note_data().layout);
+    }
+
+    #[test]
+    fn test_layout_replays_the_lookup_table() {
+        // `record` checks that the replayed table matches the circuit's before returning
+        let mut ir = note_ir();
+        ir.circuit_config = ir.circuit_config.with_range_chip(RangeChip::Lookup);
+        ir.recompute_config().unwrap();
+        let lookup = VerifierData::generate(&ir).unwrap();
+        assert_ne!(lookup.vk_digest, note_data().vk_digest);
+    }
+
+    #[test]
+    fn test_from_params_rejects_wrong_k() {
+        let ir = note_ir();
+        let params = Params::<EqAffine>::new(ir.circuit_config.k() + 1);
+        assert!(VerifierData::from_params(&ir, &params).is_err());
+    }
+}
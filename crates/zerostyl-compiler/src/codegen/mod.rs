@@ -3,14 +3,48 @@
 //! Generates a Rust crate from CircuitIR, compiles it to WebAssembly
 //! via `cargo build --target wasm32-unknown-unknown`, and returns the
 //! resulting `.wasm` bytes for Arbitrum Stylus deployment.
+//!
+//! The generated crate verifies halo2 proofs for its circuit: it embeds the
+//! verifying data described in [`embedded`] and exports `verify()` on top of
+//! `zerostyl_verifier::CompiledVerifier`. It is `no_std` and depends on
+//! `zerostyl-verifier` alone, by version; builds from this compiler patch that
+//! dependency to the verifier next to it in the source tree, when there is one.
+//!
+//! Each compile generates the crate in its own temporary directory. With a
+//! [`CodegenConfig::cache_dir`], builds share one cargo target directory and
+//! finished artifacts are cached under a key that hashes the generated crate, the
+//! toolchain and the build options, so recompiling an unchanged circuit skips cargo
+//! entirely. The cache directory holds:
+//!
+//! - `artifacts/<key>.wasm`: finished builds;
+//! - `target/`: the shared cargo target directory;
+//! - `params/`: IPA params, cached by [`keys::KeyManager`].
 
+pub mod embedded;
 pub mod keys;
 pub mod prover;
+pub mod size_report;
+pub mod stylus;
 pub mod wasm_builder;
 
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use blake2::{Blake2s256, Digest};
+use halo2_proofs::poly::commitment::Params;
+use halo2curves::pasta::EqAffine;
+
+use crate::codegen::embedded::{VerifierData, LAYOUT_FILE, PARAMS_FILE, VK_DIGEST_FILE};
+use crate::codegen::keys::KeyManager;
 use crate::codegen::wasm_builder::WasmBuilder;
 use crate::{CircuitIR, CompilerError, Result};
 
+/// Package name of the generated verifier crate.
+const GENERATED_CRATE_NAME: &str = "zerostyl-circuit-verifier";
+
+/// Package the generated crate verifies with.
+const VERIFIER_CRATE_NAME: &str = "zerostyl-verifier";
+
 /// WASM code generator that transforms a `CircuitIR` into a compilable Rust crate
 /// and builds it to WebAssembly for Arbitrum Stylus deployment.
 #[derive(Debug, Clone)]
@@ -30,6 +64,10 @@ pub struct CodegenConfig {
     pub debug_symbols: bool,
     /// Target Stylus SDK version.
     pub stylus_version: String,
+    /// Directory for cached artifacts, the shared target directory and IPA params (see
+    /// the [module docs](self)). `None` builds every crate from scratch. Defaults to
+    /// [`user_cache_dir`].
+    pub cache_dir: Option<PathBuf>,
 }
 
 impl Default for CodegenConfig {
@@ -39,6 +77,7 @@ impl Default for CodegenConfig {
             max_size_bytes: 150_000,
             debug_symbols: false,
             stylus_version: "0.9.0".to_string(),
+            cache_dir: user_cache_dir(),
         }
     }
 }
@@ -81,65 +120,199 @@ impl WasmCodegen {
 
     /// Compile the circuit to WASM by generating a temporary Rust crate
     /// and building it with `cargo build --target wasm32-unknown-unknown --release`.
+    ///
+    /// Returns the cached artifact instead when an identical crate was already built
+    /// with the same toolchain and options.
     pub fn compile(&self) -> Result<Vec<u8>> {
-        let temp_dir =
-            std::env::temp_dir().join(format!("zerostyl_codegen_{}", self.circuit_ir.name));
+        let build_dir = tempfile::Builder::new()
+            .prefix(&format!("zerostyl_codegen_{}_", self.circuit_ir.name))
+            .tempdir()
+            .map_err(|e| CompilerError::Other(format!("Failed to create temp directory: {}", e)))?;
+        let crate_dir = build_dir.path();
+        self.write_crate(crate_dir)?;
+
+        let wasm_bytes = match &self.config.cache_dir {
+            None => self.build_crate(crate_dir, GENERATED_CRATE_NAME, None)?,
+            Some(cache_dir) => {
+                let key = self.cache_key(crate_dir)?;
+                let artifact = artifact_path(cache_dir, &key);
+                match std::fs::read(&artifact) {
+                    Ok(cached) => cached,
+                    Err(_) => self.build_cached(crate_dir, &key, cache_dir, &artifact)?,
+                }
+            }
+        };
+
+        if wasm_bytes.len() > self.config.max_size_bytes {
+            eprintln!(
+                "Warning: WASM size {} bytes exceeds target {} bytes",
+                wasm_bytes.len(),
+                self.config.max_size_bytes
+            );
+        }
+
+        Ok(wasm_bytes)
+    }
+
+    fn build_cached(
+        &self,
+        crate_dir: &Path,
+        key: &str,
+        cache_dir: &Path,
+        artifact: &Path,
+    ) -> Result<Vec<u8>> {
+        // Builds share the target directory, so each crate needs its own output name
+        let crate_name = format!("{}-{}", GENERATED_CRATE_NAME, &key[..16]);
+        std::fs::write(crate_dir.join("Cargo.toml"), self.cargo_toml(&crate_name))
+            .map_err(|e| CompilerError::Other(format!("Failed to write Cargo.toml: {}", e)))?;
+        let wasm_bytes =
+            self.build_crate(crate_dir, &crate_name, Some(&cache_dir.join("target")))?;
+        store_artifact(artifact, &wasm_bytes)?;
+        Ok(wasm_bytes)
+    }
+
+    fn build_crate(
+        &self,
+        crate_dir: &Path,
+        crate_name: &str,
+        target_dir: Option<&Path>,
+    ) -> Result<Vec<u8>> {
+        let mut builder = WasmBuilder::new(crate_dir)
+            .with_lib_name(&crate_name.replace('-', "_"))
+            .with_optimization(self.config.optimize_size);
+        if let Some(target_dir) = target_dir {
+            builder = builder.with_target_dir(target_dir);
+        }
+        if let Some(verifier_dir) = local_verifier_dir() {
+            builder = builder.with_patch(VERIFIER_CRATE_NAME, verifier_dir);
+        }
+        builder.build()
+    }
+
+    /// Key of the artifact built from the crate in `crate_dir`: a hash of the generated
+    /// files, the compiler version, the sources of the local verifier the build is
+    /// patched to, the `rustc` and `wasm-opt` versions, and the build options.
+    fn cache_key(&self, crate_dir: &Path) -> Result<String> {
+        let mut hasher = Blake2s256::new();
+        let mut update = |label: &str, bytes: &[u8]| {
+            hasher.update((label.len() as u64).to_le_bytes());
+            hasher.update(label.as_bytes());
+            hasher.update((bytes.len() as u64).to_le_bytes());
+            hasher.update(bytes);
+        };
+
+        let src_dir = crate_dir.join("src");
+        let files = [
+            crate_dir.join("Cargo.toml"),
+            crate_dir.join("Cargo.lock"),
+            src_dir.join("lib.rs"),
+            src_dir.join(LAYOUT_FILE),
+            src_dir.join(PARAMS_FILE),
+            src_dir.join(VK_DIGEST_FILE),
+        ];
+        for file in &files {
+            let name = file.strip_prefix(crate_dir).unwrap_or(file).to_string_lossy();
+            match std::fs::read(file) {
+                Ok(bytes) => update(&name, &bytes),
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => update(&name, b""),
+                Err(e) => {
+                    return Err(CompilerError::Other(format!("Failed to read {}: {}", name, e)))
+                }
+            }
+        }
+
+        update("compiler", env!("CARGO_PKG_VERSION").as_bytes());
+        if let Some(verifier_dir) = local_verifier_dir() {
+            // The verifier's path dependency is patched in along with it
+            for dir in [verifier_dir.clone(), verifier_dir.join("../zerostyl-layout")] {
+                hash_sources(&dir, &dir, &mut update)?;
+            }
+        }
+
+        let rustc = std::env::var("RUSTC").unwrap_or_else(|_| "rustc".to_string());
+        let rustc_version = tool_version(&rustc, "-vV")
+            .ok_or_else(|| CompilerError::Other(format!("Failed to run `{} -vV`", rustc)))?;
+        update("rustc", rustc_version.as_bytes());
+        let wasm_opt = if self.config.optimize_size {
+            tool_version("wasm-opt", "--version").unwrap_or_default()
+        } else {
+            String::new()
+        };
+        update("wasm-opt", wasm_opt.as_bytes());
+        update("optimize_size", &[u8::from(self.config.optimize_size)]);
 
-        // Clean up any previous run
-        let _ = std::fs::remove_dir_all(&temp_dir);
+        Ok(hex::encode(hasher.finalize()))
+    }
 
-        let src_dir = temp_dir.join("src");
+    /// Write the verifier crate for this circuit to `dir`: `Cargo.toml`, `src/lib.rs`
+    /// and the verifying data files (see [`embedded`]).
+    ///
+    /// Generating the verifying data runs key generation for the circuit, so this
+    /// fails on IR that does not validate.
+    pub fn write_crate(&self, dir: &Path) -> Result<()> {
+        let src_dir = dir.join("src");
         std::fs::create_dir_all(&src_dir)
             .map_err(|e| CompilerError::Other(format!("Failed to create temp directory: {}", e)))?;
 
-        // Write generated source files
-        std::fs::write(temp_dir.join("Cargo.toml"), self.generate_cargo_toml())
+        std::fs::write(dir.join("Cargo.toml"), self.generate_cargo_toml())
             .map_err(|e| CompilerError::Other(format!("Failed to write Cargo.toml: {}", e)))?;
 
         std::fs::write(src_dir.join("lib.rs"), self.generate_lib_rs())
             .map_err(|e| CompilerError::Other(format!("Failed to write lib.rs: {}", e)))?;
 
-        // Build using WasmBuilder
-        let builder = WasmBuilder::new(&temp_dir).with_optimization(self.config.optimize_size);
-        let wasm_bytes = builder.build();
-
-        // Clean up temp directory regardless of build result
-        let _ = std::fs::remove_dir_all(&temp_dir);
+        self.verifier_data()?.write_to(&src_dir)?;
 
-        let wasm_bytes = wasm_bytes?;
-
-        if wasm_bytes.len() > self.config.max_size_bytes {
-            eprintln!(
-                "Warning: WASM size {} bytes exceeds target {} bytes",
-                wasm_bytes.len(),
-                self.config.max_size_bytes
-            );
+        // Pin dependency versions to the ones this compiler was built and tested with
+        let lockfile = Path::new(env!("CARGO_MANIFEST_DIR")).join("../../Cargo.lock");
+        if lockfile.exists() {
+            std::fs::copy(&lockfile, dir.join("Cargo.lock"))
+                .map_err(|e| CompilerError::Other(format!("Failed to copy Cargo.lock: {}", e)))?;
         }
 
-        Ok(wasm_bytes)
+        Ok(())
+    }
+
+    /// Verifying data for the circuit, with params from the cache directory if set.
+    fn verifier_data(&self) -> Result<VerifierData> {
+        let Some(cache_dir) = &self.config.cache_dir else {
+            return VerifierData::generate(&self.circuit_ir);
+        };
+        create_cache_dir(cache_dir)?;
+        crate::circuit::validate_circuit_ir(&self.circuit_ir)?;
+        let params: Params<EqAffine> = KeyManager::new(cache_dir.join("params"))
+            .and_then(|keys| keys.generate_params(self.circuit_ir.circuit_config.k()))
+            .map_err(|e| CompilerError::Other(format!("Failed to load params: {}", e)))?;
+        VerifierData::from_params(&self.circuit_ir, &params)
     }
 
     /// Generate the Cargo.toml for the temporary verifier crate.
     ///
-    /// NOTE: The generated crate is `no_std` and does not include `halo2_proofs`
-    /// as a dependency because full halo2 verification in WASM requires a
-    /// significant binary size (~2MB+) which exceeds Arbitrum Stylus contract
-    /// size limits. Real on-chain verification should use a precompile-based
-    /// approach or a custom verifier compiled separately. The generated `verify()`
-    /// function is a placeholder that returns an error code.
+    /// The crate depends on the `zerostyl-verifier` release matching this compiler; to
+    /// build it against a local checkout, patch crates-io as [`WasmCodegen::compile`] does.
+    /// Full halo2 verification makes the WASM far larger than `max_size_bytes`; see
+    /// [`embedded`].
     pub fn generate_cargo_toml(&self) -> String {
-        r#"[package]
-name = "zerostyl-verifier"
+        self.cargo_toml(GENERATED_CRATE_NAME)
+    }
+
+    fn cargo_toml(&self, crate_name: &str) -> String {
+        format!(
+            r#"[package]
+name = "{crate_name}"
 version = "0.1.0"
 edition = "2021"
 
-# NOTE: halo2_proofs is intentionally not included as a dependency.
-# Full halo2 verification in WASM exceeds Stylus contract size limits (~24KB).
-# On-chain proof verification should use a dedicated verifier contract or
-# precompile. This crate provides circuit metadata and a placeholder verify().
+# Standalone, even when generated inside another workspace
+[workspace]
 
 [lib]
-crate-type = ["cdylib"]
+crate-type = ["cdylib", "rlib"]
+
+# To build against a local checkout, add to .cargo/config.toml:
+#   [patch.crates-io]
+#   {verifier} = {{ path = "/path/to/zerostyl/crates/{verifier}" }}
+[dependencies]
+{verifier} = {{ version = "{version}", default-features = false }}
 
 [profile.release]
 opt-level = "z"
@@ -147,15 +320,21 @@ lto = true
 codegen-units = 1
 panic = "abort"
 strip = true
-"#
-        .to_string()
+"#,
+            crate_name = crate_name,
+            verifier = VERIFIER_CRATE_NAME,
+            version = env!("CARGO_PKG_VERSION"),
+        )
     }
 
     /// Generate the lib.rs source for the temporary verifier crate.
     ///
-    /// Produces a minimal `#![no_std]` crate with circuit metadata constants,
-    /// a `verify()` FFI export (returns -2 "not implemented"), and a
-    /// `get_circuit_metadata()` export returning embedded JSON.
+    /// Produces a `no_std` crate with circuit metadata constants, a `verify()` FFI
+    /// export backed by `zerostyl_verifier::CompiledVerifier`, a
+    /// `get_circuit_metadata()` export returning embedded JSON, and on WASM the Stylus
+    /// `user_entrypoint` (see [`stylus`]), which verifies a proof passed as calldata.
+    /// The verifying data files are included from next to `lib.rs` (see
+    /// [`WasmCodegen::write_crate`]).
     pub fn generate_lib_rs(&self) -> String {
         let metadata = self.build_metadata();
         let metadata_json = format!(
@@ -169,10 +348,15 @@ strip = true
         let metadata_json_escaped = metadata_json.replace('"', "\\\"");
 
         format!(
-            r#"#![no_std]
-#![no_main]
+            r#"// ZeroStyl Generated Verifier — Circuit: {name}
+
+#![no_std]
+// The metadata constants document the circuit; not all of them are read
+#![allow(dead_code)]
+
+extern crate alloc;
 
-// ZeroStyl Generated Verifier — Circuit: {name}
+use zerostyl_verifier::{{CompiledVerifier, EmbeddedLayout, VERIFY_REJECTED, VERIFY_UNAVAILABLE}};
 
 const CIRCUIT_NAME: &str = "{name}";
 const CIRCUIT_K: u32 = {k};
@@ -182,34 +366,59 @@ const COMPILER_VERSION: &str = "{compiler_version}";
 
 static METADATA_JSON: &[u8] = b"{metadata_json}\0";
 
-#[panic_handler]
-fn panic(_info: &core::panic::PanicInfo) -> ! {{
-    loop {{}}
+// Verifying data, written next to this file by the code generator
+static LAYOUT: &[u8] = include_bytes!("{layout_file}");
+static PARAMS: &[u8] = include_bytes!("{params_file}");
+static VK_DIGEST: &[u8; 32] = include_bytes!("{vk_digest_file}");
+
+/// The circuit layout the verifying key is regenerated from.
+struct Layout;
+
+impl EmbeddedLayout for Layout {{
+    fn layout_bytes() -> &'static [u8] {{
+        LAYOUT
+    }}
+}}
+
+/// The verifier, rebuilt from the embedded data. A Stylus call verifies one proof,
+/// so there is nothing to gain from keeping it between calls.
+fn verifier() -> Option<CompiledVerifier> {{
+    CompiledVerifier::load::<Layout>(PARAMS, VK_DIGEST, NUM_PUBLIC_INPUTS).ok()
+}}
+
+unsafe fn bytes<'a>(ptr: *const u8, len: u32) -> Option<&'a [u8]> {{
+    match (ptr.is_null(), len) {{
+        (_, 0) => Some(&[]),
+        (true, _) => None,
+        (false, len) => Some(core::slice::from_raw_parts(ptr, len as usize)),
+    }}
 }}
 
 /// Verify a ZK proof.
 ///
+/// `inputs` holds `NUM_PUBLIC_INPUTS` field elements, 32 little-endian bytes each.
+///
 /// # Return codes
 /// - `0`: proof is valid
-/// - `-1`: proof verification failed (invalid proof)
-/// - `-2`: verification not implemented (placeholder)
+/// - `-1`: proof verification failed (invalid proof, or malformed proof/input bytes)
+/// - `-2`: the embedded verifying data does not match this circuit
 ///
-/// # Current status
-/// This is a placeholder. Full halo2 verification requires embedding the
-/// verification key and a WASM-compatible verifier, which exceeds Stylus
-/// contract size limits. Use an off-chain verifier or a dedicated on-chain
-/// verifier contract for production proof verification.
+/// # Safety
+/// Each pointer must be valid for reads of its length, or null with a zero length.
 #[no_mangle]
-pub extern "C" fn verify(
-    _proof_ptr: *const u8,
-    _proof_len: u32,
-    _inputs_ptr: *const u8,
-    _inputs_len: u32,
+pub unsafe extern "C" fn verify(
+    proof_ptr: *const u8,
+    proof_len: u32,
+    inputs_ptr: *const u8,
+    inputs_len: u32,
 ) -> i32 {{
-    // Error code -2: verification not implemented
-    // Distinct from -1 (invalid proof) so callers can distinguish
-    // "not yet supported" from "proof rejected".
-    -2
+    let Some(verifier) = verifier() else {{
+        return VERIFY_UNAVAILABLE;
+    }};
+    match (bytes(proof_ptr, proof_len), bytes(inputs_ptr, inputs_len)) {{
+        (Some(proof), Some(inputs)) => verifier.verify(proof, inputs),
+        _ => VERIFY_REJECTED,
+    }}
 }}
 
 /// Get circuit metadata as a pointer to a null-terminated JSON byte string.
@@ -217,6 +426,34 @@ pub extern "C" fn verify(
 pub extern "C" fn get_circuit_metadata() -> *const u8 {{
     METADATA_JSON.as_ptr()
 }}
+
+#[cfg(target_arch = "wasm32")]
+#[link(wasm_import_module = "vm_hooks")]
+extern "C" {{
+    fn read_args(dest: *mut u8);
+    fn write_result(data: *const u8, len: usize);
+}}
+
+/// Stylus entrypoint.
+///
+/// Calldata holds the public inputs (as for `verify()`) followed by the proof. The
+/// result is one 32-byte word, `1` if the proof is valid and `0` otherwise.
+#[cfg(target_arch = "wasm32")]
+#[no_mangle]
+pub extern "C" fn user_entrypoint(len: usize) -> usize {{
+    use zerostyl_verifier::{{PUBLIC_INPUT_LEN, VERIFY_OK}};
+
+    let mut calldata = alloc::vec![0u8; len];
+    unsafe {{ read_args(calldata.as_mut_ptr()) }};
+
+    let split = NUM_PUBLIC_INPUTS * PUBLIC_INPUT_LEN;
+    let (inputs, proof) = calldata.split_at(split.min(len));
+    let valid = len >= split && verifier().is_some_and(|v| v.verify(proof, inputs) == VERIFY_OK);
+    let mut result = [0u8; 32];
+    result[31] = u8::from(valid);
+    unsafe {{ write_result(result.as_ptr(), result.len()) }};
+    0
+}}
 "#,
             name = metadata.name,
             k = metadata.k_param,
@@ -224,6 +461,9 @@ pub extern "C" fn get_circuit_metadata() -> *const u8 {{
             num_public_inputs = metadata.num_public_inputs,
             compiler_version = metadata.compiler_version,
             metadata_json = metadata_json_escaped,
+            layout_file = LAYOUT_FILE,
+            params_file = PARAMS_FILE,
+            vk_digest_file = VK_DIGEST_FILE,
         )
     }
 
@@ -249,7 +489,105 @@ pub extern "C" fn get_circuit_metadata() -> *const u8 {{
     }
 }
 
-/// Validate WASM bytes: checks magic number, version, and runs `wasmparser` validation.
+/// The per-user cache directory, `$XDG_CACHE_HOME/zerostyl` or `~/.cache/zerostyl`;
+/// `None` without a home directory.
+///
+/// Compiles load cached artifacts without rebuilding them, so the cache must not be
+/// writable by other users: it is created with mode `0700`, and an existing directory
+/// that is group- or world-writable is refused.
+pub fn user_cache_dir() -> Option<PathBuf> {
+    let base = std::env::var_os("XDG_CACHE_HOME")
+        .map(PathBuf::from)
+        .filter(|dir| dir.is_absolute())
+        .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".cache")))?;
+    Some(base.join("zerostyl"))
+}
+
+/// Creates `dir` if needed, accessible to the current user only, and refuses an existing
+/// one that other users can write to.
+fn create_cache_dir(dir: &Path) -> Result<()> {
+    let error = |e: std::io::Error| {
+        CompilerError::Other(format!("Failed to create cache directory {}: {}", dir.display(), e))
+    };
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::{DirBuilderExt, PermissionsExt};
+
+        std::fs::DirBuilder::new().recursive(true).mode(0o700).create(dir).map_err(error)?;
+        let mode = std::fs::metadata(dir).map_err(error)?.permissions().mode();
+        if mode & 0o022 != 0 {
+            return Err(CompilerError::Other(format!(
+                "Cache directory {} is writable by other users (mode {:o})",
+                dir.display(),
+                mode & 0o777
+            )));
+        }
+        Ok(())
+    }
+    #[cfg(not(unix))]
+    std::fs::create_dir_all(dir).map_err(error)
+}
+
+/// Hashes every file under `dir` except build output, labelled by its path relative to
+/// `root`, in a stable order.
+fn hash_sources(root: &Path, dir: &Path, update: &mut impl FnMut(&str, &[u8])) -> Result<()> {
+    let error = |e: std::io::Error| {
+        CompilerError::Other(format!("Failed to read {}: {}", dir.display(), e))
+    };
+    let mut entries = std::fs::read_dir(dir)
+        .and_then(|entries| {
+            entries.map(|entry| entry.map(|e| e.path())).collect::<std::io::Result<Vec<_>>>()
+        })
+        .map_err(error)?;
+    entries.sort();
+    for path in entries {
+        if path.is_dir() {
+            if path.file_name().is_some_and(|name| name != "target") {
+                hash_sources(root, &path, update)?;
+            }
+        } else {
+            let name = path.strip_prefix(root).unwrap_or(&path).to_string_lossy().into_owned();
+            update(&name, &std::fs::read(&path).map_err(error)?);
+        }
+    }
+    Ok(())
+}
+
+/// The `zerostyl-verifier` crate in the source tree this compiler was built from, if it
+/// is still there. Builds patch the generated crate's dependency to it, so that the
+/// crate verifies with the same verifier the compiler was developed against.
+fn local_verifier_dir() -> Option<PathBuf> {
+    let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("..").join(VERIFIER_CRATE_NAME);
+    dir.join("Cargo.toml").exists().then_some(dir)
+}
+
+fn artifact_path(cache_dir: &Path, key: &str) -> PathBuf {
+    cache_dir.join("artifacts").join(format!("{}.wasm", key))
+}
+
+/// Stores an artifact atomically: readers see either no file or the complete one.
+fn store_artifact(path: &Path, wasm_bytes: &[u8]) -> Result<()> {
+    let dir = path.parent().unwrap_or(Path::new("."));
+    std::fs::create_dir_all(dir)
+        .and_then(|()| tempfile::NamedTempFile::new_in(dir))
+        .and_then(|mut file| {
+            std::io::Write::write_all(&mut file, wasm_bytes)?;
+            file.persist(path).map_err(|e| e.error)?;
+            Ok(())
+        })
+        .map_err(|e| CompilerError::Other(format!("Failed to cache WASM artifact: {}", e)))
+}
+
+/// Standard output of `program arg`, or `None` if it cannot be run.
+fn tool_version(program: &str, arg: &str) -> Option<String> {
+    let output = Command::new(program).arg(arg).output().ok()?;
+    output.status.success().then(|| String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+/// Validate WASM bytes: checks magic number, version, runs `wasmparser` validation and
+/// the Stylus activation checks of [`stylus::check_stylus`] with default limits.
+///
+/// Stylus violations are all reported together as [`CompilerError::StylusIncompatible`].
 pub fn validate_wasm(wasm_bytes: &[u8]) -> Result<()> {
     if wasm_bytes.len() < 4 {
         return Err(CompilerError::Other("WASM too short".to_string()));
@@ -268,8 +606,14 @@ pub fn validate_wasm(wasm_bytes: &[u8]) -> Result<()> {
     }
 
     wasmparser::validate(wasm_bytes)
-        .map(|_| ())
-        .map_err(|e| CompilerError::Other(format!("WASM validation failed: {}", e)))
+        .map_err(|e| CompilerError::Other(format!("WASM validation failed: {}", e)))?;
+
+    let violations = stylus::check_stylus(wasm_bytes, &stylus::StylusLimits::default())?;
+    if violations.is_empty() {
+        Ok(())
+    } else {
+        Err(CompilerError::StylusIncompatible(violations))
+    }
 }
 
 #[cfg(test)]
@@ -314,6 +658,7 @@ mod tests {
             max_size_bytes: 200_000,
             debug_symbols: true,
             stylus_version: "0.9.0".to_string(),
+            cache_dir: None,
         };
 
         let codegen = WasmCodegen::with_config(circuit_ir, config);
@@ -330,6 +675,33 @@ mod tests {
         assert_eq!(config.max_size_bytes, 150_000);
         assert!(!config.debug_symbols);
         assert_eq!(config.stylus_version, "0.9.0");
+        assert_eq!(config.cache_dir, user_cache_dir());
+        assert!(!config.cache_dir.is_some_and(|dir| dir.starts_with(std::env::temp_dir())));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_cache_dir_is_private() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let root = tempfile::TempDir::new().unwrap();
+        let dir = root.path().join("zerostyl");
+        create_cache_dir(&dir).unwrap();
+        assert_eq!(std::fs::metadata(&dir).unwrap().permissions().mode() & 0o777, 0o700);
+
+        std::fs::set_permissions(&dir, std::fs::Permissions::from_mode(0o777)).unwrap();
+        assert!(create_cache_dir(&dir).is_err());
+    }
+
+    #[test]
+    fn test_cache_key_tracks_verifier_sources() {
+        let mut hashed = Vec::new();
+        let verifier_dir = local_verifier_dir().unwrap();
+        hash_sources(&verifier_dir, &verifier_dir, &mut |name, _| hashed.push(name.to_string()))
+            .unwrap();
+        assert!(hashed.contains(&"Cargo.toml".to_string()));
+        assert!(hashed.contains(&"src/compiled.rs".to_string()));
+        assert!(!hashed.iter().any(|name| name.starts_with("target")));
     }
 
     #[test]
@@ -465,13 +837,19 @@ mod tests {
     }
 
     #[test]
-    fn test_generate_lib_rs_has_no_std() {
+    fn test_generate_lib_rs_embeds_verifying_data() {
         let circuit_ir = create_test_circuit_ir();
         let codegen = WasmCodegen::new(circuit_ir);
         let source = codegen.generate_lib_rs();
 
-        assert!(source.contains("#![no_std]"), "Source must be no_std");
-        assert!(source.contains("#[panic_handler]"), "Source must have panic handler");
+        assert!(source.contains("#![no_std]"));
+        assert!(source.contains(r#"include_bytes!("circuit_layout.bin")"#));
+        assert!(source.contains(r#"include_bytes!("params.bin")"#));
+        assert!(source.contains(r#"include_bytes!("vk_digest.bin")"#));
+        assert!(source
+            .contains("CompiledVerifier::load::<Layout>(PARAMS, VK_DIGEST, NUM_PUBLIC_INPUTS)"));
+        assert!(!source.contains("zerostyl_compiler"), "Must not depend on the compiler");
+        assert!(!source.contains("-2\n}"), "verify() must not be a placeholder");
     }
 
     #[test]
@@ -480,11 +858,16 @@ mod tests {
         let codegen = WasmCodegen::new(circuit_ir);
         let source = codegen.generate_lib_rs();
 
-        assert!(source.contains("pub extern \"C\" fn verify("), "Source must export verify");
+        assert!(source.contains("pub unsafe extern \"C\" fn verify("), "Source must export verify");
         assert!(
             source.contains("pub extern \"C\" fn get_circuit_metadata()"),
             "Source must export get_circuit_metadata"
         );
+        assert!(
+            source.contains("pub extern \"C\" fn user_entrypoint(len: usize) -> usize"),
+            "Source must export the Stylus entrypoint"
+        );
+        assert!(source.contains("#[link(wasm_import_module = \"vm_hooks\")]"));
         assert!(source.contains("#[no_mangle]"), "Exports must be #[no_mangle]");
     }
 
@@ -506,8 +889,21 @@ mod tests {
         let codegen = WasmCodegen::new(circuit_ir);
         let toml = codegen.generate_cargo_toml();
 
-        assert!(toml.contains(r#"name = "zerostyl-verifier""#), "Must have correct crate name");
-        assert!(toml.contains(r#"crate-type = ["cdylib"]"#), "Must be cdylib");
+        assert!(
+            toml.contains(r#"name = "zerostyl-circuit-verifier""#),
+            "Must have correct crate name"
+        );
+        assert!(
+            toml.contains(&format!(
+                r#"zerostyl-verifier = {{ version = "{}", default-features = false }}"#,
+                env!("CARGO_PKG_VERSION")
+            )),
+            "Must depend on the no_std verifier of this release"
+        );
+        assert!(!toml.contains("zerostyl-compiler"), "Must not depend on the compiler");
+        assert!(!toml.contains(env!("CARGO_MANIFEST_DIR")), "Must not embed local paths");
+        assert!(toml.contains("[workspace]"), "Must be a standalone workspace");
+        assert!(toml.contains(r#"crate-type = ["cdylib", "rlib"]"#), "Must be cdylib");
         assert!(toml.contains("[profile.release]"), "Must have release profile");
         assert!(toml.contains(r#"opt-level = "z""#), "Must optimize for size");
         assert!(toml.contains("panic = \"abort\""), "Must use panic abort");
@@ -570,19 +966,195 @@ mod tests {
         assert!(source.contains("EmptyCircuit"));
     }
 
+    fn commitment_ir(name: &str, max: u128) -> CircuitIR {
+        crate::IrBuilder::new(name)
+            .private("amount", ZkType::U64)
+            .range(0..max)
+            .private("blinding", ZkType::Field)
+            .public("commitment")
+            .commit_poseidon("amount", "blinding")
+            .build()
+            .unwrap()
+    }
+
+    fn cached_codegen(ir: CircuitIR, cache_dir: &Path) -> WasmCodegen {
+        let config = CodegenConfig {
+            optimize_size: false,
+            cache_dir: Some(cache_dir.to_path_buf()),
+            ..CodegenConfig::default()
+        };
+        WasmCodegen::with_config(ir, config)
+    }
+
+    fn key_for(codegen: &WasmCodegen) -> String {
+        let dir = tempfile::TempDir::new().unwrap();
+        codegen.write_crate(dir.path()).unwrap();
+        codegen.cache_key(dir.path()).unwrap()
+    }
+
+    #[test]
+    fn test_cache_key_tracks_generated_crate() {
+        let cache = tempfile::TempDir::new().unwrap();
+        let codegen = cached_codegen(commitment_ir("Note", 1000), cache.path());
+        let key = key_for(&codegen);
+
+        assert_eq!(key, key_for(&codegen), "Same crate in another directory");
+        assert_ne!(key, key_for(&cached_codegen(commitment_ir("Note", 2000), cache.path())));
+
+        let mut optimized = codegen.clone();
+        optimized.config.optimize_size = true;
+        assert_ne!(key, key_for(&optimized));
+    }
+
+    #[test]
+    fn test_compile_returns_cached_artifacts() {
+        let cache = tempfile::TempDir::new().unwrap();
+        // Same circuit name, different circuits: compiled in parallel, each gets its own
+        let codegens: Vec<WasmCodegen> = [1000, 2000]
+            .into_iter()
+            .map(|max| cached_codegen(commitment_ir("Note", max), cache.path()))
+            .collect();
+        for (i, codegen) in codegens.iter().enumerate() {
+            let artifact = artifact_path(cache.path(), &key_for(codegen));
+            store_artifact(&artifact, &[0x00, 0x61, 0x73, 0x6d, i as u8]).unwrap();
+        }
+
+        let outputs: Vec<Vec<u8>> = std::thread::scope(|scope| {
+            let handles: Vec<_> =
+                codegens.iter().map(|codegen| scope.spawn(|| codegen.compile())).collect();
+            handles.into_iter().map(|h| h.join().unwrap().unwrap()).collect()
+        });
+        assert_eq!(outputs[0], vec![0x00, 0x61, 0x73, 0x6d, 0]);
+        assert_eq!(outputs[1], vec![0x00, 0x61, 0x73, 0x6d, 1]);
+    }
+
+    #[test]
+    fn test_write_crate_rejects_invalid_ir() {
+        // The public input is not bound to any witness
+        let codegen = WasmCodegen::new(create_test_circuit_ir());
+        let dir = tempfile::TempDir::new().unwrap();
+        assert!(codegen.write_crate(dir.path()).is_err());
+    }
+
+    /// Test run against a generated crate: its `verify()` export on a native proof.
+    const GENERATED_CRATE_TEST: &str = r#"
+use zerostyl_circuit_verifier::verify;
+
+fn run(proof: &[u8], inputs: &[u8]) -> i32 {
+    unsafe { verify(proof.as_ptr(), proof.len() as u32, inputs.as_ptr(), inputs.len() as u32) }
+}
+
+#[test]
+fn test_verify_native_proof() {
+    let proof = include_bytes!("proof.bin");
+    let inputs = include_bytes!("inputs.bin");
+    assert_eq!(run(proof, inputs), 0);
+
+    let mut wrong_inputs = inputs.to_vec();
+    wrong_inputs[0] ^= 1;
+    assert_eq!(run(proof, &wrong_inputs), -1);
+    assert_eq!(run(&proof[1..], inputs), -1);
+    assert_eq!(run(proof, &inputs[1..]), -1);
+}
+"#;
+
+    #[test]
+    #[ignore] // Builds and tests the generated crate with a nested `cargo test --offline`
+    fn test_generated_crate_verifies_native_proofs() {
+        use crate::circuit::CircuitBuilder;
+        use crate::codegen::embedded::encode_public_inputs;
+        use crate::codegen::keys::KeyMetadata;
+        use crate::codegen::prover::NativeProver;
+        use crate::gadgets::PoseidonCommitmentChip;
+        use halo2curves::pasta::Fp;
+
+        let ir = commitment_ir("Native", 1000);
+        let k = ir.circuit_config.k();
+        let dir = tempfile::TempDir::new().unwrap();
+        let codegen = WasmCodegen::with_config(
+            ir.clone(),
+            CodegenConfig { cache_dir: None, ..Default::default() },
+        );
+        codegen.write_crate(dir.path()).unwrap();
+
+        let (amount, blinding) = (Fp::from(250), Fp::from(99));
+        let commitment = PoseidonCommitmentChip::hash_outside_circuit(amount, blinding);
+        let circuit = CircuitBuilder::new(ir.clone())
+            .build::<Fp>()
+            .with_witnesses(vec![amount, blinding])
+            .unwrap()
+            .with_public_inputs(vec![commitment])
+            .unwrap();
+        let mut prover = NativeProver::with_cache_dir(circuit, k, dir.path().join("keys")).unwrap();
+        prover
+            .setup(KeyMetadata {
+                circuit_name: ir.name.clone(),
+                k,
+                num_public_inputs: 1,
+                num_private_witnesses: 2,
+            })
+            .unwrap();
+        let proof = prover.generate_proof(&[vec![commitment]]).unwrap();
+
+        let tests_dir = dir.path().join("tests");
+        std::fs::create_dir_all(&tests_dir).unwrap();
+        std::fs::write(tests_dir.join("verify.rs"), GENERATED_CRATE_TEST).unwrap();
+        std::fs::write(tests_dir.join("proof.bin"), &proof).unwrap();
+        std::fs::write(tests_dir.join("inputs.bin"), encode_public_inputs(&[commitment])).unwrap();
+
+        // Next to this test binary's target directory, so reruns reuse the dependencies
+        let target_dir =
+            std::env::current_exe().unwrap().ancestors().nth(3).unwrap().join("generated-crates");
+        let verifier_dir = local_verifier_dir().expect("built from the source tree");
+        let output = std::process::Command::new(env!("CARGO"))
+            .args(["test", "--offline", "--quiet", "--manifest-path"])
+            .arg(dir.path().join("Cargo.toml"))
+            .arg("--config")
+            .arg(wasm_builder::patch_config(VERIFIER_CRATE_NAME, &verifier_dir))
+            .env("CARGO_TARGET_DIR", target_dir)
+            .output()
+            .unwrap();
+        assert!(
+            output.status.success(),
+            "Generated crate tests failed:\n{}{}",
+            String::from_utf8_lossy(&output.stdout),
+            String::from_utf8_lossy(&output.stderr)
+        );
+    }
+
     #[test]
     #[ignore] // Requires wasm32-unknown-unknown target installed
     fn test_compile_produces_valid_wasm() {
-        let circuit_ir = create_test_circuit_ir();
         let codegen = WasmCodegen::with_config(
-            circuit_ir,
-            CodegenConfig { optimize_size: false, ..CodegenConfig::default() },
+            commitment_ir("Wasm", 1000),
+            CodegenConfig { optimize_size: false, cache_dir: None, ..CodegenConfig::default() },
         );
 
         let wasm_bytes = codegen.compile().expect("Compilation should succeed");
 
         assert!(!wasm_bytes.is_empty(), "WASM output should not be empty");
         assert_eq!(&wasm_bytes[0..4], &[0x00, 0x61, 0x73, 0x6d], "Should have WASM magic number");
-        validate_wasm(&wasm_bytes).expect("Generated WASM should be valid");
+
+        // A full halo2 verifier is well-formed and exports the Stylus ABI, but far exceeds
+        // the compressed code size limit (see `embedded`)
+        let Err(CompilerError::StylusIncompatible(violations)) = validate_wasm(&wasm_bytes) else {
+            panic!("Generated WASM should only fail the Stylus checks");
+        };
+        assert!(violations
+            .iter()
+            .any(|v| matches!(v, stylus::StylusViolation::CompressedSizeTooLarge { .. })));
+        for violation in &violations {
+            assert!(
+                !matches!(
+                    violation,
+                    stylus::StylusViolation::MissingEntrypoint
+                        | stylus::StylusViolation::InvalidEntrypoint { .. }
+                        | stylus::StylusViolation::MissingMemoryExport
+                        | stylus::StylusViolation::DisallowedImport { .. }
+                ),
+                "Generated crate breaks the Stylus ABI: {}",
+                violation
+            );
+        }
     }
 }
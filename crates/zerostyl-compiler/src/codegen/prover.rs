@@ -133,6 +133,8 @@ pub fn field_to_string(f: &Fp) -> String {
 }
 
 /// Parse a field element from a decimal or hex (`0x`-prefixed) string.
+///
+/// Decimal strings may be negative (`"-42"`), which maps to the field-native value `p - 42`.
 pub fn string_to_field(s: &str) -> Result<Fp> {
     use halo2curves::group::ff::PrimeField;
 
@@ -142,12 +144,23 @@ pub fn string_to_field(s: &str) -> Result<Fp> {
         let len = bytes.len().min(repr.as_ref().len());
         repr.as_mut()[..len].copy_from_slice(&bytes[..len]);
         Option::from(Fp::from_repr(repr)).ok_or_else(|| anyhow::anyhow!("Invalid field element"))
+    } else if let Some(magnitude) = s.strip_prefix('-') {
+        let val: u64 = magnitude.parse().context("Invalid field element string")?;
+        Ok(-Fp::from(val))
     } else {
         let val: u64 = s.parse().context("Invalid field element string")?;
         Ok(Fp::from(val))
     }
 }
 
+/// Parse a decimal `i64` string into the offset-encoded witness for an `i64` field.
+///
+/// See [`SignedChip::encode`](crate::gadgets::SignedChip::encode).
+pub fn string_to_signed_witness(s: &str) -> Result<Fp> {
+    let val: i64 = s.parse().context("Invalid i64 string")?;
+    Ok(crate::gadgets::SignedChip::encode(val))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -335,6 +348,24 @@ mod tests {
         assert!(string_to_field("0x0").is_err());
     }
 
+    #[test]
+    fn test_string_to_field_negative() {
+        assert_eq!(string_to_field("-42").unwrap(), -Fp::from(42));
+        assert_eq!(string_to_field("-42").unwrap() + Fp::from(42), Fp::from(0));
+        assert_eq!(string_to_field("-0").unwrap(), Fp::from(0));
+        assert!(string_to_field("-").is_err());
+        assert!(string_to_field("--1").is_err());
+    }
+
+    #[test]
+    fn test_string_to_signed_witness() {
+        assert_eq!(string_to_signed_witness("0").unwrap(), Fp::from(1u64 << 63));
+        assert_eq!(string_to_signed_witness("-1").unwrap(), Fp::from((1u64 << 63) - 1));
+        assert_eq!(string_to_signed_witness("-9223372036854775808").unwrap(), Fp::from(0));
+        assert!(string_to_signed_witness("9223372036854775808").is_err());
+        assert!(string_to_signed_witness("0x10").is_err());
+    }
+
     #[test]
     fn test_field_to_string_not_empty() {
         let values = vec![0u64, 1, 42, 255, 1000, 999999];
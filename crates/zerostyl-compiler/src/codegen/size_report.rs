@@ -0,0 +1,484 @@
+//! Size breakdown of WASM artifacts
+//!
+//! Stylus caps contract size, so [`WasmSizeReport`] shows where the bytes of a build go:
+//! section sizes, the largest function bodies, data segments (where embedded params and
+//! verifying data end up) and the import/export surface. [`WasmSizeReport::compare`]
+//! diffs two builds, e.g. before and after a gadget change:
+//!
+//! ```rust,no_run
+//! use zerostyl_compiler::codegen::size_report::WasmSizeReport;
+//!
+//! let before = WasmSizeReport::analyze(&std::fs::read("before.wasm").unwrap()).unwrap();
+//! let after = WasmSizeReport::analyze(&std::fs::read("after.wasm").unwrap()).unwrap();
+//! println!("{}", before.compare(&after).to_json().unwrap());
+//! ```
+//!
+//! Sizes are payload sizes as reported by `wasmparser`, without the section id and
+//! length prefix. Function names come from the `name` custom section, falling back to
+//! export names (release builds are usually stripped).
+
+use std::collections::{BTreeMap, HashMap};
+
+use serde::{Deserialize, Serialize};
+use wasmparser::{DataKind, ExternalKind, KnownCustom, Name, Parser, Payload, TypeRef};
+
+use crate::{CompilerError, Result};
+
+/// Size breakdown of one WASM module.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WasmSizeReport {
+    /// Size of the whole module in bytes.
+    pub total_bytes: usize,
+    /// Sections in module order. Custom sections are named `custom:<name>`.
+    pub sections: Vec<SectionSize>,
+    /// Function bodies, largest first.
+    pub functions: Vec<FunctionSize>,
+    /// Data segments in module order.
+    pub data_segments: Vec<DataSegmentSize>,
+    /// Imported items.
+    pub imports: Vec<ImportEntry>,
+    /// Exported items.
+    pub exports: Vec<ExportEntry>,
+}
+
+/// Payload size of one section.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SectionSize {
+    pub name: String,
+    pub size_bytes: usize,
+}
+
+/// Size of one function body.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FunctionSize {
+    /// Index in the function index space (imported functions come first).
+    pub index: u32,
+    pub name: Option<String>,
+    pub size_bytes: usize,
+}
+
+/// Size of one data segment.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DataSegmentSize {
+    pub index: u32,
+    /// Bytes of data, excluding the segment header.
+    pub size_bytes: usize,
+    /// Whether the segment is copied into memory at instantiation.
+    pub active: bool,
+}
+
+/// One imported item; `kind` is `func`, `table`, `memory`, `global` or `tag`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ImportEntry {
+    pub module: String,
+    pub name: String,
+    pub kind: String,
+}
+
+/// One exported item; `kind` is `func`, `table`, `memory`, `global` or `tag`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ExportEntry {
+    pub name: String,
+    pub kind: String,
+    pub index: u32,
+}
+
+impl WasmSizeReport {
+    /// Parses `wasm` and measures it.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`CompilerError::Other`] if `wasm` is not a well-formed module.
+    pub fn analyze(wasm: &[u8]) -> Result<WasmSizeReport> {
+        let malformed = |e: wasmparser::BinaryReaderError| {
+            CompilerError::Other(format!("Malformed WASM: {}", e))
+        };
+
+        let mut report = WasmSizeReport {
+            total_bytes: wasm.len(),
+            sections: Vec::new(),
+            functions: Vec::new(),
+            data_segments: Vec::new(),
+            imports: Vec::new(),
+            exports: Vec::new(),
+        };
+        let mut num_imported_funcs = 0;
+        let mut names = HashMap::new();
+
+        for payload in Parser::new(0).parse_all(wasm) {
+            let payload = payload.map_err(malformed)?;
+            if let Some((id, range)) = payload.as_section() {
+                let name = match &payload {
+                    Payload::CustomSection(custom) => format!("custom:{}", custom.name()),
+                    _ => section_name(id).to_string(),
+                };
+                report.sections.push(SectionSize { name, size_bytes: range.len() });
+            }
+
+            match payload {
+                Payload::ImportSection(reader) => {
+                    for import in reader {
+                        let import = import.map_err(malformed)?;
+                        if matches!(import.ty, TypeRef::Func(_)) {
+                            num_imported_funcs += 1;
+                        }
+                        report.imports.push(ImportEntry {
+                            module: import.module.to_string(),
+                            name: import.name.to_string(),
+                            kind: type_ref_kind(&import.ty).to_string(),
+                        });
+                    }
+                }
+                Payload::ExportSection(reader) => {
+                    for export in reader {
+                        let export = export.map_err(malformed)?;
+                        report.exports.push(ExportEntry {
+                            name: export.name.to_string(),
+                            kind: external_kind(export.kind).to_string(),
+                            index: export.index,
+                        });
+                    }
+                }
+                Payload::CodeSectionEntry(body) => {
+                    let index = num_imported_funcs + report.functions.len() as u32;
+                    report.functions.push(FunctionSize {
+                        index,
+                        name: None,
+                        size_bytes: body.range().len(),
+                    });
+                }
+                Payload::DataSection(reader) => {
+                    for (index, data) in reader.into_iter().enumerate() {
+                        let data = data.map_err(malformed)?;
+                        report.data_segments.push(DataSegmentSize {
+                            index: index as u32,
+                            size_bytes: data.data.len(),
+                            active: matches!(data.kind, DataKind::Active { .. }),
+                        });
+                    }
+                }
+                Payload::CustomSection(custom) => {
+                    if let KnownCustom::Name(reader) = custom.as_known() {
+                        for subsection in reader {
+                            if let Ok(Name::Function(map)) = subsection {
+                                for naming in map.into_iter().flatten() {
+                                    names.insert(naming.index, naming.name.to_string());
+                                }
+                            }
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        for export in report.exports.iter().filter(|e| e.kind == "func") {
+            names.entry(export.index).or_insert_with(|| export.name.clone());
+        }
+        for function in &mut report.functions {
+            function.name = names.get(&function.index).cloned();
+        }
+        report
+            .functions
+            .sort_by(|a, b| b.size_bytes.cmp(&a.size_bytes).then(a.index.cmp(&b.index)));
+
+        Ok(report)
+    }
+
+    /// The `n` largest function bodies.
+    pub fn largest_functions(&self, n: usize) -> &[FunctionSize] {
+        &self.functions[..n.min(self.functions.len())]
+    }
+
+    /// Payload size of the section named `name` (e.g. `code`, `custom:name`), summed over
+    /// sections of that name.
+    pub fn section_size(&self, name: &str) -> usize {
+        self.sections.iter().filter(|s| s.name == name).map(|s| s.size_bytes).sum()
+    }
+
+    /// Total bytes of data held in data segments.
+    pub fn data_bytes(&self) -> usize {
+        self.data_segments.iter().map(|d| d.size_bytes).sum()
+    }
+
+    /// Serializes the report as pretty-printed JSON.
+    pub fn to_json(&self) -> Result<String> {
+        serde_json::to_string_pretty(self)
+            .map_err(|e| CompilerError::Other(format!("Failed to serialize size report: {}", e)))
+    }
+
+    /// Compares this build (before) with `after`.
+    pub fn compare(&self, after: &WasmSizeReport) -> SizeComparison {
+        let section_sizes = |report: &WasmSizeReport| {
+            let mut sizes = BTreeMap::new();
+            for section in &report.sections {
+                *sizes.entry(section.name.clone()).or_insert(0) += section.size_bytes;
+            }
+            sizes
+        };
+        // Functions are matched by name; unnamed ones cannot be matched across builds
+        let function_sizes = |report: &WasmSizeReport| {
+            report
+                .functions
+                .iter()
+                .map(|f| (f.name.clone().unwrap_or_else(|| format!("#{}", f.index)), f.size_bytes))
+                .collect::<BTreeMap<_, _>>()
+        };
+        let imports = |report: &WasmSizeReport| {
+            report.imports.iter().map(|i| format!("{}::{}", i.module, i.name)).collect::<Vec<_>>()
+        };
+        let exports = |report: &WasmSizeReport| {
+            report.exports.iter().map(|e| e.name.clone()).collect::<Vec<_>>()
+        };
+
+        let (imports_before, imports_after) = (imports(self), imports(after));
+        let (exports_before, exports_after) = (exports(self), exports(after));
+        let mut functions = deltas(function_sizes(self), function_sizes(after));
+        functions.retain(|d| d.delta() != 0);
+        functions.sort_by(|a, b| b.delta().abs().cmp(&a.delta().abs()).then(a.name.cmp(&b.name)));
+
+        SizeComparison {
+            total: SizeDelta {
+                name: "total".to_string(),
+                before: self.total_bytes,
+                after: after.total_bytes,
+            },
+            data: SizeDelta {
+                name: "data".to_string(),
+                before: self.data_bytes(),
+                after: after.data_bytes(),
+            },
+            sections: deltas(section_sizes(self), section_sizes(after)),
+            functions,
+            imports_added: missing_from(&imports_after, &imports_before),
+            imports_removed: missing_from(&imports_before, &imports_after),
+            exports_added: missing_from(&exports_after, &exports_before),
+            exports_removed: missing_from(&exports_before, &exports_after),
+        }
+    }
+}
+
+/// Difference between two [`WasmSizeReport`]s (see [`WasmSizeReport::compare`]).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SizeComparison {
+    pub total: SizeDelta,
+    /// Bytes held in data segments.
+    pub data: SizeDelta,
+    /// Every section present in either build, by name.
+    pub sections: Vec<SizeDelta>,
+    /// Functions whose size changed, largest change first.
+    pub functions: Vec<SizeDelta>,
+    /// Imports as `module::name`.
+    pub imports_added: Vec<String>,
+    pub imports_removed: Vec<String>,
+    pub exports_added: Vec<String>,
+    pub exports_removed: Vec<String>,
+}
+
+impl SizeComparison {
+    /// Serializes the comparison as pretty-printed JSON.
+    pub fn to_json(&self) -> Result<String> {
+        serde_json::to_string_pretty(self)
+            .map_err(|e| CompilerError::Other(format!("Failed to serialize comparison: {}", e)))
+    }
+}
+
+/// Size of one item in two builds; 0 where the item is absent.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SizeDelta {
+    pub name: String,
+    pub before: usize,
+    pub after: usize,
+}
+
+impl SizeDelta {
+    /// `after - before`.
+    pub fn delta(&self) -> i64 {
+        self.after as i64 - self.before as i64
+    }
+}
+
+fn deltas(before: BTreeMap<String, usize>, after: BTreeMap<String, usize>) -> Vec<SizeDelta> {
+    let mut names: Vec<&String> = before.keys().chain(after.keys()).collect();
+    names.sort();
+    names.dedup();
+    names
+        .into_iter()
+        .map(|name| SizeDelta {
+            name: name.clone(),
+            before: before.get(name).copied().unwrap_or(0),
+            after: after.get(name).copied().unwrap_or(0),
+        })
+        .collect()
+}
+
+fn missing_from(items: &[String], other: &[String]) -> Vec<String> {
+    items.iter().filter(|item| !other.contains(item)).cloned().collect()
+}
+
+fn section_name(id: u8) -> &'static str {
+    match id {
+        0 => "custom",
+        1 => "type",
+        2 => "import",
+        3 => "function",
+        4 => "table",
+        5 => "memory",
+        6 => "global",
+        7 => "export",
+        8 => "start",
+        9 => "element",
+        10 => "code",
+        11 => "data",
+        12 => "datacount",
+        13 => "tag",
+        _ => "unknown",
+    }
+}
+
+fn type_ref_kind(ty: &TypeRef) -> &'static str {
+    match ty {
+        TypeRef::Func(_) => "func",
+        TypeRef::Table(_) => "table",
+        TypeRef::Memory(_) => "memory",
+        TypeRef::Global(_) => "global",
+        TypeRef::Tag(_) => "tag",
+    }
+}
+
+fn external_kind(kind: ExternalKind) -> &'static str {
+    match kind {
+        ExternalKind::Func => "func",
+        ExternalKind::Table => "table",
+        ExternalKind::Memory => "memory",
+        ExternalKind::Global => "global",
+        ExternalKind::Tag => "tag",
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+
+    fn section(id: u8, payload: &[u8]) -> Vec<u8> {
+        assert!(payload.len() < 128, "single-byte LEB128 lengths only");
+        let mut bytes = vec![id, payload.len() as u8];
+        bytes.extend_from_slice(payload);
+        bytes
+    }
+
+    fn name(s: &str) -> Vec<u8> {
+        let mut bytes = vec![s.len() as u8];
+        bytes.extend_from_slice(s.as_bytes());
+        bytes
+    }
+
+    /// Module with one imported and two defined functions, a memory, `data_len` bytes of
+    /// active data and a `name` section naming the defined functions.
+    pub(crate) fn sample_module(import: &str, export: &str, data_len: u8) -> Vec<u8> {
+        let mut wasm = b"\0asm\x01\0\0\0".to_vec();
+        // type 0: (i32) -> i32, the Stylus entrypoint type
+        wasm.extend(section(1, &[0x01, 0x60, 0x01, 0x7f, 0x01, 0x7f]));
+        let mut imports = vec![0x01];
+        imports.extend(name("vm_hooks"));
+        imports.extend(name(import));
+        imports.extend([0x00, 0x00]);
+        wasm.extend(section(2, &imports));
+        wasm.extend(section(3, &[0x02, 0x00, 0x00]));
+        wasm.extend(section(5, &[0x01, 0x00, 0x01]));
+        let mut exports = vec![0x02];
+        exports.extend(name(export));
+        exports.extend([0x00, 0x01]);
+        exports.extend(name("memory"));
+        exports.extend([0x02, 0x00]);
+        wasm.extend(section(7, &exports));
+        // Bodies of 4 and 7 bytes
+        wasm.extend(section(
+            10,
+            &[0x02, 0x04, 0x00, 0x41, 0x01, 0x0b, 0x07, 0x00, 0x41, 0x2a, 0x1a, 0x41, 0x07, 0x0b],
+        ));
+        let mut data = vec![0x01, 0x00, 0x41, 0x00, 0x0b, data_len];
+        data.extend(std::iter::repeat_n(0xab, data_len as usize));
+        wasm.extend(section(11, &data));
+        let mut function_names = vec![0x02, 0x01];
+        function_names.extend(name("small"));
+        function_names.push(0x02);
+        function_names.extend(name("big"));
+        let mut custom = name("name");
+        custom.extend([0x01, function_names.len() as u8]);
+        custom.extend(function_names);
+        wasm.extend(section(0, &custom));
+        wasm
+    }
+
+    #[test]
+    fn test_analyze_sample_module() {
+        let wasm = sample_module("msg_value", "user_entrypoint", 100);
+        crate::validate_wasm(&wasm).unwrap();
+        let report = WasmSizeReport::analyze(&wasm).unwrap();
+
+        assert_eq!(report.total_bytes, wasm.len());
+        let names: Vec<&str> = report.sections.iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(
+            names,
+            vec!["type", "import", "function", "memory", "export", "code", "data", "custom:name"]
+        );
+        assert_eq!(report.section_size("code"), 14);
+        assert_eq!(report.section_size("data"), 106);
+
+        assert_eq!(
+            report.largest_functions(1),
+            &[FunctionSize { index: 2, name: Some("big".into()), size_bytes: 7 }]
+        );
+        assert_eq!(report.functions[1].name.as_deref(), Some("small"));
+        assert_eq!(report.largest_functions(10).len(), 2);
+
+        assert_eq!(
+            report.data_segments,
+            vec![DataSegmentSize { index: 0, size_bytes: 100, active: true }]
+        );
+        assert_eq!(report.data_bytes(), 100);
+        assert_eq!(
+            report.imports,
+            vec![ImportEntry {
+                module: "vm_hooks".into(),
+                name: "msg_value".into(),
+                kind: "func".into()
+            }]
+        );
+        assert_eq!(report.exports[0].name, "user_entrypoint");
+        assert_eq!(report.exports[1].kind, "memory");
+    }
+
+    #[test]
+    fn test_report_json_roundtrip() {
+        let report = WasmSizeReport::analyze(&sample_module("msg_value", "main", 10)).unwrap();
+        let json = report.to_json().unwrap();
+        assert!(json.contains("\"custom:name\""));
+        assert_eq!(serde_json::from_str::<WasmSizeReport>(&json).unwrap(), report);
+    }
+
+    #[test]
+    fn test_compare_builds() {
+        let before = WasmSizeReport::analyze(&sample_module("msg_value", "main", 10)).unwrap();
+        let after =
+            WasmSizeReport::analyze(&sample_module("read_args", "user_entrypoint", 110)).unwrap();
+        let diff = before.compare(&after);
+
+        assert_eq!(diff.total.delta(), after.total_bytes as i64 - before.total_bytes as i64);
+        assert_eq!(diff.data, SizeDelta { name: "data".into(), before: 10, after: 110 });
+        let data_section = diff.sections.iter().find(|s| s.name == "data").unwrap();
+        assert_eq!(data_section.delta(), 100);
+        assert!(diff.functions.is_empty(), "function bodies are unchanged");
+        assert_eq!(diff.imports_added, vec!["vm_hooks::read_args"]);
+        assert_eq!(diff.imports_removed, vec!["vm_hooks::msg_value"]);
+        assert_eq!(diff.exports_added, vec!["user_entrypoint"]);
+        assert_eq!(diff.exports_removed, vec!["main"]);
+        assert!(diff.to_json().unwrap().contains("\"imports_added\""));
+    }
+
+    #[test]
+    fn test_analyze_rejects_garbage() {
+        assert!(WasmSizeReport::analyze(b"not wasm").is_err());
+    }
+}
@@ -0,0 +1,321 @@
+//! Arbitrum Stylus activation checks
+//!
+//! Stylus re-validates a contract when it is activated and rejects programs that a
+//! generic WASM validator accepts. [`check_stylus`] runs the same checks offline so CI
+//! fails before deployment; each problem is reported as a [`StylusViolation`]:
+//!
+//! - the `user_entrypoint` export must exist with type `(i32) -> i32`, next to an
+//!   exported `memory`;
+//! - imports must come from the `vm_hooks` module and name a known host I/O;
+//! - no floating-point instructions or types, and no WASM features Stylus does not
+//!   enable (SIMD, threads, reference types, ...);
+//! - the initial memory must fit in the page limit;
+//! - the brotli-compressed program must fit in the code size limit.
+
+use std::io::Write;
+
+use thiserror::Error;
+use wasmparser::{
+    ExternalKind, FuncType, Parser, Payload, TypeRef, ValType, Validator, WasmFeatures,
+};
+
+use crate::{CompilerError, Result};
+
+/// Export Stylus calls with the calldata length.
+pub const ENTRYPOINT: &str = "user_entrypoint";
+
+/// Import module providing the Stylus host I/Os.
+pub const HOST_MODULE: &str = "vm_hooks";
+
+/// Host I/Os a program may import from [`HOST_MODULE`].
+pub const VM_HOOKS: &[&str] = &[
+    "account_balance",
+    "account_code",
+    "account_code_size",
+    "account_codehash",
+    "block_basefee",
+    "block_coinbase",
+    "block_gas_limit",
+    "block_number",
+    "block_timestamp",
+    "call_contract",
+    "chainid",
+    "contract_address",
+    "create1",
+    "create2",
+    "delegate_call_contract",
+    "emit_log",
+    "evm_gas_left",
+    "evm_ink_left",
+    "exit_early",
+    "math_add_mod",
+    "math_div",
+    "math_mod",
+    "math_mul_mod",
+    "math_pow",
+    "msg_reentrant",
+    "msg_sender",
+    "msg_value",
+    "native_keccak256",
+    "pay_for_memory_grow",
+    "read_args",
+    "read_return_data",
+    "return_data_size",
+    "static_call_contract",
+    "storage_cache_bytes32",
+    "storage_flush_cache",
+    "storage_load_bytes32",
+    "transient_load_bytes32",
+    "transient_store_bytes32",
+    "tx_gas_price",
+    "tx_ink_price",
+    "tx_origin",
+    "write_result",
+];
+
+/// WASM features Stylus enables; notably no floats, SIMD, threads or reference types.
+const STYLUS_FEATURES: WasmFeatures = WasmFeatures::MUTABLE_GLOBAL
+    .union(WasmFeatures::SATURATING_FLOAT_TO_INT)
+    .union(WasmFeatures::SIGN_EXTENSION)
+    .union(WasmFeatures::MULTI_VALUE)
+    .union(WasmFeatures::BULK_MEMORY)
+    .union(WasmFeatures::GC_TYPES);
+
+/// Limits a program must stay within to activate.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StylusLimits {
+    /// Maximum brotli-compressed program size in bytes.
+    pub max_compressed_bytes: usize,
+    /// Maximum initial memory size in 64 KiB pages.
+    pub max_memory_pages: u64,
+}
+
+impl Default for StylusLimits {
+    fn default() -> Self {
+        Self { max_compressed_bytes: 24 * 1024, max_memory_pages: 128 }
+    }
+}
+
+/// A reason Stylus would refuse to activate a program.
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum StylusViolation {
+    #[error("missing `{ENTRYPOINT}` export")]
+    MissingEntrypoint,
+
+    #[error("`{ENTRYPOINT}` must be a function of type (i32) -> i32, found {found}")]
+    InvalidEntrypoint { found: String },
+
+    #[error("missing `memory` export")]
+    MissingMemoryExport,
+
+    #[error("disallowed import `{module}::{name}`")]
+    DisallowedImport { module: String, name: String },
+
+    #[error("floating point is not supported: {message}")]
+    FloatingPoint { message: String },
+
+    #[error("unsupported WASM feature: {message}")]
+    UnsupportedFeature { message: String },
+
+    #[error("initial memory of {pages} pages exceeds the limit of {max_pages}")]
+    MemoryTooLarge { pages: u64, max_pages: u64 },
+
+    #[error("compressed size of {compressed_bytes} bytes exceeds the limit of {max_bytes}")]
+    CompressedSizeTooLarge { compressed_bytes: usize, max_bytes: usize },
+}
+
+/// Check a well-formed module against Stylus activation rules, returning every violation.
+///
+/// # Errors
+///
+/// Returns [`CompilerError::Other`] if `wasm_bytes` is not a valid module at all.
+pub fn check_stylus(wasm_bytes: &[u8], limits: &StylusLimits) -> Result<Vec<StylusViolation>> {
+    let types = Validator::new()
+        .validate_all(wasm_bytes)
+        .map_err(|e| CompilerError::Other(format!("WASM validation failed: {}", e)))?;
+    let types = types.as_ref();
+    let mut violations = Vec::new();
+
+    let mut entrypoint = None;
+    let mut memory_exported = false;
+    for payload in Parser::new(0).parse_all(wasm_bytes) {
+        let payload = payload.map_err(|e| CompilerError::Other(e.to_string()))?;
+        match payload {
+            Payload::ImportSection(reader) => {
+                for import in reader {
+                    let import = import.map_err(|e| CompilerError::Other(e.to_string()))?;
+                    let is_hook = import.module == HOST_MODULE
+                        && matches!(import.ty, TypeRef::Func(_))
+                        && VM_HOOKS.contains(&import.name);
+                    if !is_hook {
+                        violations.push(StylusViolation::DisallowedImport {
+                            module: import.module.to_string(),
+                            name: import.name.to_string(),
+                        });
+                    }
+                }
+            }
+            Payload::ExportSection(reader) => {
+                for export in reader {
+                    let export = export.map_err(|e| CompilerError::Other(e.to_string()))?;
+                    match (export.name, export.kind) {
+                        (ENTRYPOINT, kind) => entrypoint = Some((kind, export.index)),
+                        ("memory", ExternalKind::Memory) => memory_exported = true,
+                        _ => {}
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    match entrypoint {
+        None => violations.push(StylusViolation::MissingEntrypoint),
+        Some((ExternalKind::Func, index)) => {
+            let ty = types[types.core_function_at(index)].unwrap_func();
+            if ty.params() != [ValType::I32] || ty.results() != [ValType::I32] {
+                violations.push(StylusViolation::InvalidEntrypoint { found: signature(ty) });
+            }
+        }
+        Some((kind, _)) => violations
+            .push(StylusViolation::InvalidEntrypoint { found: format!("{:?} export", kind) }),
+    }
+    if !memory_exported {
+        violations.push(StylusViolation::MissingMemoryExport);
+    }
+
+    if let Err(e) = Validator::new_with_features(STYLUS_FEATURES).validate_all(wasm_bytes) {
+        let message = e.message().to_string();
+        let with_floats = STYLUS_FEATURES.union(WasmFeatures::FLOATS);
+        if Validator::new_with_features(with_floats).validate_all(wasm_bytes).is_ok() {
+            violations.push(StylusViolation::FloatingPoint { message });
+        } else {
+            violations.push(StylusViolation::UnsupportedFeature { message });
+        }
+    }
+
+    if let Some(pages) = (0..types.memory_count()).map(|i| types.memory_at(i).initial).max() {
+        if pages > limits.max_memory_pages {
+            violations.push(StylusViolation::MemoryTooLarge {
+                pages,
+                max_pages: limits.max_memory_pages,
+            });
+        }
+    }
+
+    let compressed_bytes = compressed_size(wasm_bytes)?;
+    if compressed_bytes > limits.max_compressed_bytes {
+        violations.push(StylusViolation::CompressedSizeTooLarge {
+            compressed_bytes,
+            max_bytes: limits.max_compressed_bytes,
+        });
+    }
+
+    Ok(violations)
+}
+
+/// Renders a function type as e.g. `(i32, i64) -> i32`.
+fn signature(ty: &FuncType) -> String {
+    let list = |types: &[ValType]| types.iter().map(ToString::to_string).collect::<Vec<_>>();
+    let results = match ty.results() {
+        [result] => result.to_string(),
+        results => format!("({})", list(results).join(", ")),
+    };
+    format!("({}) -> {}", list(ty.params()).join(", "), results)
+}
+
+/// Size of `wasm_bytes` after brotli compression with the settings `cargo stylus` deploys
+/// with (quality 11, window 22).
+pub fn compressed_size(wasm_bytes: &[u8]) -> Result<usize> {
+    let mut compressed = Vec::new();
+    {
+        let mut writer = brotli::CompressorWriter::new(&mut compressed, 4096, 11, 22);
+        writer
+            .write_all(wasm_bytes)
+            .map_err(|e| CompilerError::Other(format!("Failed to compress WASM: {}", e)))?;
+    }
+    Ok(compressed.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::codegen::size_report::tests::sample_module;
+
+    fn check(wasm: &[u8]) -> Vec<StylusViolation> {
+        check_stylus(wasm, &StylusLimits::default()).unwrap()
+    }
+
+    /// Header, a `() -> f32` type and one function returning an f32 constant, exported
+    /// as `user_entrypoint` next to a memory of `pages` pages.
+    fn float_module(pages: u8) -> Vec<u8> {
+        let mut wasm = b"\0asm\x01\0\0\0".to_vec();
+        wasm.extend([0x01, 0x05, 0x01, 0x60, 0x00, 0x01, 0x7d]);
+        wasm.extend([0x03, 0x02, 0x01, 0x00]);
+        wasm.extend([0x05, 0x04, 0x01, 0x00, 0x80 | (pages & 0x7f), pages >> 7]);
+        let mut exports = vec![0x02, 0x0f];
+        exports.extend(ENTRYPOINT.as_bytes());
+        exports.extend([0x00, 0x00, 0x06]);
+        exports.extend(b"memory");
+        exports.extend([0x02, 0x00]);
+        wasm.extend([0x07, exports.len() as u8]);
+        wasm.extend(exports);
+        wasm.extend([0x0a, 0x09, 0x01, 0x07, 0x00, 0x43, 0x00, 0x00, 0x80, 0x3f, 0x0b]);
+        wasm
+    }
+
+    #[test]
+    fn test_compatible_module_passes() {
+        let wasm = sample_module("msg_value", ENTRYPOINT, 10);
+        assert_eq!(check(&wasm), vec![]);
+        crate::validate_wasm(&wasm).unwrap();
+    }
+
+    #[test]
+    fn test_missing_entrypoint_and_bad_import() {
+        let wasm = sample_module("print_f64", "main", 10);
+        assert_eq!(
+            check(&wasm),
+            vec![
+                StylusViolation::DisallowedImport {
+                    module: HOST_MODULE.to_string(),
+                    name: "print_f64".to_string()
+                },
+                StylusViolation::MissingEntrypoint,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_floats_and_memory_limit() {
+        let violations = check(&float_module(200));
+        assert!(violations
+            .contains(&StylusViolation::InvalidEntrypoint { found: "() -> f32".to_string() }));
+        assert!(violations.iter().any(|v| matches!(v, StylusViolation::FloatingPoint { .. })));
+        assert!(
+            violations.contains(&StylusViolation::MemoryTooLarge { pages: 200, max_pages: 128 })
+        );
+    }
+
+    #[test]
+    fn test_compressed_size_limit() {
+        let wasm = sample_module("msg_value", ENTRYPOINT, 100);
+        let limits = StylusLimits { max_compressed_bytes: 16, ..StylusLimits::default() };
+        let violations = check_stylus(&wasm, &limits).unwrap();
+        let compressed_bytes = compressed_size(&wasm).unwrap();
+        assert!(compressed_bytes < wasm.len(), "repeated data compresses");
+        assert!(violations.contains(&StylusViolation::CompressedSizeTooLarge {
+            compressed_bytes,
+            max_bytes: 16
+        }));
+    }
+
+    #[test]
+    fn test_violation_display() {
+        let violation = StylusViolation::DisallowedImport {
+            module: "env".to_string(),
+            name: "abort".to_string(),
+        };
+        assert_eq!(violation.to_string(), "disallowed import `env::abort`");
+    }
+}
@@ -1,5 +1,6 @@
 //! Rust to WASM compilation for zerostyl-verifier
 
+use super::size_report::WasmSizeReport;
 use crate::{CompilerError, Result};
 use std::path::{Path, PathBuf};
 use std::process::Command;
@@ -8,6 +9,9 @@ use std::process::Command;
 pub struct WasmBuilder {
     verifier_crate_path: PathBuf,
     target: String,
+    lib_name: String,
+    target_dir: Option<PathBuf>,
+    patches: Vec<(String, PathBuf)>,
     optimize: bool,
 }
 
@@ -17,6 +21,9 @@ impl WasmBuilder {
         Self {
             verifier_crate_path: verifier_crate_path.as_ref().to_path_buf(),
             target: "wasm32-unknown-unknown".to_string(),
+            lib_name: "zerostyl_verifier".to_string(),
+            target_dir: None,
+            patches: Vec::new(),
             optimize: true,
         }
     }
@@ -27,6 +34,28 @@ impl WasmBuilder {
         self
     }
 
+    /// Override the library name of the crate, which names the `.wasm` output
+    /// (default: `zerostyl_verifier`).
+    pub fn with_lib_name(mut self, lib_name: &str) -> Self {
+        self.lib_name = lib_name.to_string();
+        self
+    }
+
+    /// Build into `target_dir` instead of the crate's own `target/`, so that crates
+    /// sharing dependencies reuse their compiled artifacts. Cargo locks the directory,
+    /// so concurrent builds wait for each other instead of clobbering it.
+    pub fn with_target_dir<P: AsRef<Path>>(mut self, target_dir: P) -> Self {
+        self.target_dir = Some(target_dir.as_ref().to_path_buf());
+        self
+    }
+
+    /// Build with the crates.io package `package` replaced by the crate at `path`, as a
+    /// `[patch.crates-io]` entry would, without editing the crate's manifest.
+    pub fn with_patch<P: AsRef<Path>>(mut self, package: &str, path: P) -> Self {
+        self.patches.push((package.to_string(), path.as_ref().to_path_buf()));
+        self
+    }
+
     /// Enable or disable post-build WASM optimization via `wasm-opt`.
     pub fn with_optimization(mut self, optimize: bool) -> Self {
         self.optimize = optimize;
@@ -52,6 +81,12 @@ impl WasmBuilder {
             .arg("--manifest-path")
             .arg(&manifest_path)
             .arg("--quiet");
+        for (package, path) in &self.patches {
+            command.arg("--config").arg(patch_config(package, path));
+        }
+        if let Some(target_dir) = &self.target_dir {
+            command.env("CARGO_TARGET_DIR", target_dir);
+        }
 
         let output = command
             .output()
@@ -75,11 +110,12 @@ impl WasmBuilder {
     }
 
     fn get_wasm_output_path(&self) -> PathBuf {
-        self.verifier_crate_path
-            .join("target")
+        self.target_dir
+            .clone()
+            .unwrap_or_else(|| self.verifier_crate_path.join("target"))
             .join(&self.target)
             .join("release")
-            .join("zerostyl_verifier.wasm")
+            .join(format!("{}.wasm", self.lib_name))
     }
 
     fn optimize_wasm(&self, wasm_bytes: &[u8]) -> Result<Vec<u8>> {
@@ -95,8 +131,13 @@ impl WasmBuilder {
             return Ok(wasm_bytes.to_vec());
         }
 
-        let temp_input = std::env::temp_dir().join("zerostyl_verifier_input.wasm");
-        let temp_output = std::env::temp_dir().join("zerostyl_verifier_output.wasm");
+        // Private directory, so parallel builds do not overwrite each other's files
+        let temp_dir = tempfile::Builder::new()
+            .prefix("zerostyl_wasm_opt_")
+            .tempdir()
+            .map_err(|e| CompilerError::Other(format!("Failed to create temp directory: {}", e)))?;
+        let temp_input = temp_dir.path().join("input.wasm");
+        let temp_output = temp_dir.path().join("output.wasm");
 
         std::fs::write(&temp_input, wasm_bytes)
             .map_err(|e| CompilerError::Other(format!("Failed to write temp WASM file: {}", e)))?;
@@ -120,9 +161,6 @@ impl WasmBuilder {
         let optimized = std::fs::read(&temp_output)
             .map_err(|e| CompilerError::Other(format!("Failed to read optimized WASM: {}", e)))?;
 
-        let _ = std::fs::remove_file(&temp_input);
-        let _ = std::fs::remove_file(&temp_output);
-
         println!(
             "WASM optimization: {} bytes → {} bytes ({:.1}% reduction)",
             wasm_bytes.len(),
@@ -144,6 +182,12 @@ impl WasmBuilder {
     }
 }
 
+/// `--config` value patching `package` on crates.io to the crate at `path`.
+pub(crate) fn patch_config(package: &str, path: &Path) -> String {
+    // Debug formatting quotes and escapes the path as a TOML basic string
+    format!("patch.crates-io.{}.path={:?}", package, path.to_string_lossy())
+}
+
 /// Result of a WASM build, including the output bytes and build metadata.
 #[derive(Debug, Clone)]
 pub struct WasmBuildOutput {
@@ -155,6 +199,13 @@ pub struct WasmBuildOutput {
     pub optimized: bool,
 }
 
+impl WasmBuildOutput {
+    /// Breaks the output down by section, function and data segment.
+    pub fn size_report(&self) -> Result<WasmSizeReport> {
+        WasmSizeReport::analyze(&self.wasm_bytes)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -200,6 +251,29 @@ mod tests {
         assert!(path.to_string_lossy().contains("wasm32-wasi"));
     }
 
+    #[test]
+    fn test_wasm_output_path_with_target_dir() {
+        let builder = WasmBuilder::new("test/path").with_target_dir("/shared/target");
+        assert_eq!(
+            builder.get_wasm_output_path(),
+            Path::new("/shared/target/wasm32-unknown-unknown/release/zerostyl_verifier.wasm")
+        );
+    }
+
+    #[test]
+    fn test_wasm_output_path_with_lib_name() {
+        let builder = WasmBuilder::new("test/path").with_lib_name("my_verifier");
+        assert!(builder.get_wasm_output_path().ends_with("release/my_verifier.wasm"));
+    }
+
+    #[test]
+    fn test_patch_config() {
+        assert_eq!(
+            patch_config("zerostyl-verifier", Path::new("/src/zerostyl/crates/zerostyl-verifier")),
+            r#"patch.crates-io.zerostyl-verifier.path="/src/zerostyl/crates/zerostyl-verifier""#
+        );
+    }
+
     #[test]
     fn test_optimize_wasm_without_wasm_opt() {
         let builder = WasmBuilder::new("crates/zerostyl-verifier").with_optimization(true);
@@ -223,6 +297,15 @@ mod tests {
         assert!(output.optimized);
     }
 
+    #[test]
+    fn test_build_output_size_report() {
+        let wasm = crate::codegen::size_report::tests::sample_module("msg_value", "main", 8);
+        let output = WasmBuildOutput { size_bytes: wasm.len(), wasm_bytes: wasm, optimized: false };
+        let report = output.size_report().unwrap();
+        assert_eq!(report.total_bytes, output.size_bytes);
+        assert_eq!(report.data_bytes(), 8);
+    }
+
     #[test]
     #[ignore] // Only run with --ignored flag
     fn test_real_wasm_build() {
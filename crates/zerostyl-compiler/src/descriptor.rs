@@ -0,0 +1,616 @@
+//! [`CircuitDescriptor`] for compiled circuits
+//!
+//! [`IrDescriptor`] plugs the [`ZkCircuit`] for a [`CircuitIR`] into the toolkit (CLI,
+//! debugger, exporter) without a hand-written `descriptor.rs`. Both schemas are derived
+//! from the IR:
+//!
+//! - every private witness is a witness JSON field, typed from its [`ZkType`];
+//! - every public input is computed from the witnesses, as its commitment or binding
+//!   defines it, so the witness JSON never carries public values.
+//!
+//! Witness values follow [`FieldType::encode_witness`]. Public inputs are exchanged as
+//! `{"inputs": [["0x…", …]]}`, each element the hex of its little-endian repr, as in the
+//! example circuits.
+//!
+//! ```rust
+//! use zerostyl_circuits::Registry;
+//! use zerostyl_compiler::{parse_contract, transform_to_ir, IrDescriptor};
+//!
+//! let input = r#"
+//!     struct Vault {
+//!         #[zk_private(range = "10..=20")]
+//!         level: u64,
+//!     }
+//! "#;
+//! let ir = transform_to_ir(parse_contract(input).unwrap()).unwrap();
+//!
+//! let registry = Registry::new();
+//! let vault = registry.register_owned(Box::new(IrDescriptor::new(ir).unwrap())).unwrap();
+//! let k = vault.default_k();
+//! assert!(vault.mock_prove(r#"{"level": "15"}"#, k).unwrap().satisfied);
+//! assert!(!vault.mock_prove(r#"{"level": "21"}"#, k).unwrap().satisfied);
+//! ```
+
+use std::path::Path;
+
+use halo2_proofs::arithmetic::Field;
+use halo2_proofs::{
+    dev::{MockProver, VerifyFailure},
+    pasta::Fp,
+    plonk::{Circuit, ConstraintSystem},
+};
+use halo2curves::ff::PrimeField;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use zerostyl_circuits::{
+    CircuitDescriptor, CircuitError, CircuitIntrospection, FailureEntry, FailureKind, FieldType,
+    FieldVisibility, MockProverReport, ProofArtifact, PublicInputField, PublicInputsSchema,
+    WitnessField, WitnessSchema,
+};
+
+use crate::ast::{ArithOp, CircuitIR, InterFieldConstraint, PublicInputSource, ZkType};
+use crate::circuit::{validate_circuit_ir, CircuitBuilder, ZkCircuit};
+use crate::codegen::keys::KeyMetadata;
+use crate::codegen::prover::{string_to_field, NativeProver};
+use crate::error::CompilerError;
+use crate::gadgets::{PoseidonCommitmentChip, SignedChip};
+
+/// How a public input is computed from the witnesses.
+#[derive(Debug, Clone)]
+enum PublicValue {
+    Commitment { value: String, randomness: String },
+    Bound(PublicInputSource),
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct PublicInputsJson {
+    inputs: Vec<Vec<String>>,
+}
+
+/// [`CircuitDescriptor`] for the circuit compiled from a [`CircuitIR`] (see the
+/// [module docs](self)).
+///
+/// The trait hands out names and schemas as `'static`, so each descriptor leaks them once
+/// on creation: build one per circuit, not one per request.
+#[derive(Debug)]
+pub struct IrDescriptor {
+    ir: CircuitIR,
+    name: &'static str,
+    description: &'static str,
+    witness_schema: &'static WitnessSchema,
+    public_inputs_schema: &'static PublicInputsSchema,
+    public_values: Vec<PublicValue>,
+}
+
+impl IrDescriptor {
+    /// Creates the descriptor for `ir`.
+    ///
+    /// # Errors
+    ///
+    /// Returns the [`validate_circuit_ir`] error if the IR cannot be synthesized.
+    pub fn new(ir: CircuitIR) -> Result<IrDescriptor, CompilerError> {
+        validate_circuit_ir(&ir)?;
+
+        // Validation guarantees every public input is bound.
+        let public_values: Vec<PublicValue> = ir
+            .public_inputs
+            .iter()
+            .map(|input| {
+                public_value(&ir, &input.name).ok_or_else(|| {
+                    CompilerError::Other(format!("Public input '{}' is not bound", input.name))
+                })
+            })
+            .collect::<Result<_, _>>()?;
+
+        let witness_fields: Vec<WitnessField> = ir
+            .private_witnesses
+            .iter()
+            .map(|field| WitnessField {
+                name: field.name.clone(),
+                kind: field_type(&field.field_type),
+                visibility: FieldVisibility::Private,
+                description: None,
+            })
+            .collect();
+        let public_fields = ir
+            .public_inputs
+            .iter()
+            .zip(&public_values)
+            .map(|(input, value)| PublicInputField {
+                name: input.name.clone(),
+                kind: FieldType::Fp,
+                description: Some(value.to_string()),
+            })
+            .collect();
+
+        let description = format!(
+            "Compiled circuit '{}': {} private witness(es), {} public input(s).",
+            ir.name,
+            ir.private_witnesses.len(),
+            ir.public_inputs.len()
+        );
+        Ok(IrDescriptor {
+            name: Box::leak(ir.name.clone().into_boxed_str()),
+            description: Box::leak(description.into_boxed_str()),
+            witness_schema: Box::leak(Box::new(WitnessSchema { fields: witness_fields })),
+            public_inputs_schema: Box::leak(Box::new(PublicInputsSchema { fields: public_fields })),
+            public_values,
+            ir,
+        })
+    }
+
+    /// The IR the circuit is compiled from.
+    pub fn ir(&self) -> &CircuitIR {
+        &self.ir
+    }
+
+    /// Encodes the witness JSON into the circuit's witness cells and public inputs.
+    fn witnesses(&self, witness_json: &str) -> zerostyl_circuits::Result<(Vec<Fp>, Vec<Fp>)> {
+        let json: Map<String, Value> = serde_json::from_str(witness_json).map_err(|e| {
+            CircuitError::InvalidWitness(format!("{} witness JSON: {e}", self.name))
+        })?;
+        let read = |name: &str, kind: &FieldType| {
+            let value = json.get(name).ok_or_else(|| {
+                CircuitError::InvalidWitness(format!("missing witness field '{name}'"))
+            })?;
+            kind.encode_witness(value)
+                .map_err(|e| CircuitError::InvalidWitness(format!("field '{name}': {e}")))?
+                .into_iter()
+                .map(|repr| {
+                    Option::from(Fp::from_repr(repr)).ok_or_else(|| {
+                        CircuitError::InvalidWitness(format!(
+                            "field '{name}': value is not a field element"
+                        ))
+                    })
+                })
+                .collect::<zerostyl_circuits::Result<Vec<Fp>>>()
+        };
+
+        let mut cells = Vec::with_capacity(self.ir.num_witness_cells());
+        let mut scalars = std::collections::HashMap::new();
+        for field in &self.ir.private_witnesses {
+            let values = read(&field.name, &field_type(&field.field_type))?;
+            if let [value] = values[..] {
+                scalars.insert(field.name.as_str(), value);
+            }
+            cells.extend(values);
+        }
+
+        let scalar = |name: &str| {
+            scalars.get(name).copied().ok_or_else(|| {
+                CircuitError::InvalidWitness(format!("'{name}' is not a scalar witness"))
+            })
+        };
+        // Binding operands as the circuit computes with them: signed fields decoded
+        let operand = |name: &str| {
+            let signed = self
+                .ir
+                .private_witnesses
+                .iter()
+                .any(|f| f.name == name && f.field_type.is_signed());
+            let value = scalar(name)?;
+            Ok::<_, CircuitError>(if signed { value - Fp::from(SignedChip::OFFSET) } else { value })
+        };
+        let public = self
+            .ir
+            .public_inputs
+            .iter()
+            .zip(&self.public_values)
+            .map(|(input, value)| match value {
+                PublicValue::Commitment { value, randomness } => {
+                    Ok(PoseidonCommitmentChip::hash_outside_circuit(
+                        scalar(value)?,
+                        scalar(randomness)?,
+                    ))
+                }
+                PublicValue::Bound(PublicInputSource::Field(name)) => scalar(name),
+                PublicValue::Bound(PublicInputSource::Arithmetic { operation, operands }) => {
+                    let (first, rest) = operands.split_first().ok_or_else(|| {
+                        CircuitError::InvalidWitness(format!("'{}' has no operands", input.name))
+                    })?;
+                    rest.iter().try_fold(operand(first)?, |acc, name| {
+                        apply(*operation, acc, operand(name)?).ok_or_else(|| {
+                            CircuitError::InvalidWitness(format!(
+                                "'{}' divides by zero",
+                                input.name
+                            ))
+                        })
+                    })
+                }
+            })
+            .collect::<zerostyl_circuits::Result<Vec<Fp>>>()?;
+
+        Ok((cells, public))
+    }
+
+    fn circuit(&self, witness_json: &str) -> zerostyl_circuits::Result<(ZkCircuit<Fp>, Vec<Fp>)> {
+        let (cells, public) = self.witnesses(witness_json)?;
+        let circuit = CircuitBuilder::new(self.ir.clone())
+            .build::<Fp>()
+            .with_witnesses(cells)
+            .and_then(|c| c.with_public_inputs(public.clone()))
+            .map_err(|e| CircuitError::InvalidWitness(e.to_string()))?;
+        Ok((circuit, public))
+    }
+
+    fn prover(
+        &self,
+        circuit: ZkCircuit<Fp>,
+        k: u32,
+        cache_dir: &Path,
+    ) -> anyhow::Result<NativeProver<ZkCircuit<Fp>>> {
+        let mut prover = NativeProver::with_cache_dir(circuit, k, cache_dir)?;
+        prover.setup(KeyMetadata {
+            circuit_name: self.name.to_string(),
+            k,
+            num_public_inputs: self.ir.public_inputs.len(),
+            num_private_witnesses: self.ir.num_witness_cells(),
+        })?;
+        Ok(prover)
+    }
+}
+
+impl CircuitDescriptor for IrDescriptor {
+    fn name(&self) -> &'static str {
+        self.name
+    }
+    fn version(&self) -> &'static str {
+        env!("CARGO_PKG_VERSION")
+    }
+    fn description(&self) -> &'static str {
+        self.description
+    }
+    fn default_k(&self) -> u32 {
+        self.ir.circuit_config.k()
+    }
+    fn num_public_inputs(&self) -> usize {
+        self.ir.public_inputs.len()
+    }
+    fn num_private_witnesses(&self) -> usize {
+        self.ir.num_witness_cells()
+    }
+    fn witness_schema(&self) -> &'static WitnessSchema {
+        self.witness_schema
+    }
+    fn public_inputs_schema(&self) -> &'static PublicInputsSchema {
+        self.public_inputs_schema
+    }
+
+    fn prove(
+        &self,
+        witness_json: &str,
+        k: u32,
+        cache_dir: &Path,
+    ) -> zerostyl_circuits::Result<ProofArtifact> {
+        let (circuit, public) = self.circuit(witness_json)?;
+        let proof = self
+            .prover(circuit, k, cache_dir)
+            .and_then(|prover| prover.generate_proof(std::slice::from_ref(&public)))
+            .map_err(|e| CircuitError::ProveFailed(e.to_string()))?;
+        let inputs =
+            vec![public.iter().map(|fp| format!("0x{}", hex::encode(fp.to_repr()))).collect()];
+        let public_inputs_json = serde_json::to_string_pretty(&PublicInputsJson { inputs })?;
+        Ok(ProofArtifact::new(proof, public_inputs_json))
+    }
+
+    fn verify(
+        &self,
+        proof: &[u8],
+        public_inputs_json: &str,
+        k: u32,
+        cache_dir: &Path,
+    ) -> zerostyl_circuits::Result<bool> {
+        let parsed: PublicInputsJson = serde_json::from_str(public_inputs_json)?;
+        let public_inputs = parsed
+            .inputs
+            .iter()
+            .map(|row| row.iter().map(|s| string_to_field(s)).collect())
+            .collect::<anyhow::Result<Vec<Vec<Fp>>>>()
+            .map_err(|e| CircuitError::VerifyFailed(e.to_string()))?;
+        let circuit = CircuitBuilder::new(self.ir.clone()).build::<Fp>();
+        self.prover(circuit, k, cache_dir)
+            .and_then(|prover| prover.verify_proof(proof, &public_inputs))
+            .map_err(|e| CircuitError::VerifyFailed(e.to_string()))
+    }
+
+    fn mock_prove(
+        &self,
+        witness_json: &str,
+        k: u32,
+    ) -> zerostyl_circuits::Result<MockProverReport> {
+        let (circuit, public) = self.circuit(witness_json)?;
+        let prover = MockProver::run(k, &circuit, vec![public])
+            .map_err(|e| CircuitError::Other(format!("MockProver setup failed: {e:?}")))?;
+
+        let (satisfied, failures) = match prover.verify() {
+            Ok(()) => (true, Vec::new()),
+            Err(errs) => (false, errs.iter().map(convert_failure).collect()),
+        };
+
+        Ok(MockProverReport { circuit_name: self.name.to_string(), k, satisfied, failures })
+    }
+
+    fn inspect(&self) -> zerostyl_circuits::Result<CircuitIntrospection> {
+        let mut cs = ConstraintSystem::<Fp>::default();
+        let _ = ZkCircuit::<Fp>::configure(&mut cs);
+        let debug = format!("{:?}", cs.pinned());
+
+        Ok(CircuitIntrospection {
+            circuit_name: self.name.to_string(),
+            k: self.default_k(),
+            num_advice_columns: parse_usize_field(&debug, "num_advice_columns"),
+            num_fixed_columns: parse_usize_field(&debug, "num_fixed_columns"),
+            num_instance_columns: parse_usize_field(&debug, "num_instance_columns"),
+            num_selectors: parse_usize_field(&debug, "num_selectors"),
+            max_constraint_degree: cs.degree(),
+            gates: Vec::new(),
+            columns: Vec::new(),
+        })
+    }
+}
+
+/// Schema type for witnesses of an IR type. Narrow integers share `u64`; the circuit
+/// range-checks them to their own width.
+fn field_type(zk_type: &ZkType) -> FieldType {
+    match zk_type {
+        ZkType::U8 | ZkType::U16 | ZkType::U32 | ZkType::U64 => FieldType::U64,
+        ZkType::U128 => FieldType::U128,
+        ZkType::I64 => FieldType::I64,
+        ZkType::Bool => FieldType::Bool,
+        ZkType::Field => FieldType::Fp,
+        ZkType::Bytes32 => FieldType::Bytes32,
+        ZkType::Address => FieldType::Address,
+        ZkType::Array { element_type, size } => {
+            FieldType::Array { kind: Box::new(field_type(element_type)), len: *size }
+        }
+    }
+}
+
+impl std::fmt::Display for PublicValue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PublicValue::Commitment { value, randomness } => {
+                write!(f, "Poseidon({value}, {randomness})")
+            }
+            PublicValue::Bound(PublicInputSource::Field(name)) => write!(f, "Equal to {name}"),
+            PublicValue::Bound(PublicInputSource::Arithmetic { operation, operands }) => {
+                let op = match operation {
+                    ArithOp::Add => " + ",
+                    ArithOp::Sub => " - ",
+                    ArithOp::Mul => " * ",
+                    ArithOp::Div => " / ",
+                };
+                write!(f, "{}", operands.join(op))
+            }
+        }
+    }
+}
+
+fn public_value(ir: &CircuitIR, name: &str) -> Option<PublicValue> {
+    ir.inter_field_constraints.iter().find_map(|constraint| match constraint {
+        InterFieldConstraint::CommitmentVerification { commitment, value, randomness, .. }
+            if commitment == name =>
+        {
+            Some(PublicValue::Commitment { value: value.clone(), randomness: randomness.clone() })
+        }
+        InterFieldConstraint::PublicInputBinding { public_input, source }
+            if public_input == name =>
+        {
+            Some(PublicValue::Bound(source.clone()))
+        }
+        _ => None,
+    })
+}
+
+/// `lhs op rhs` in the field, as the arithmetic gadget computes it. `None` on division by zero.
+fn apply(op: ArithOp, lhs: Fp, rhs: Fp) -> Option<Fp> {
+    match op {
+        ArithOp::Add => Some(lhs + rhs),
+        ArithOp::Sub => Some(lhs - rhs),
+        ArithOp::Mul => Some(lhs * rhs),
+        ArithOp::Div => Option::<Fp>::from(rhs.invert()).map(|inv| lhs * inv),
+    }
+}
+
+fn convert_failure(f: &VerifyFailure) -> FailureEntry {
+    let details = format!("{f}");
+    match f {
+        VerifyFailure::ConstraintNotSatisfied { constraint, location, .. } => FailureEntry {
+            kind: FailureKind::ConstraintNotSatisfied,
+            gate_name: Some(format!("{constraint}")),
+            region: Some(format!("{location}")),
+            row: None,
+            column: None,
+            details,
+        },
+        VerifyFailure::CellNotAssigned { gate, gate_offset, column, .. } => FailureEntry {
+            kind: FailureKind::ConstraintNotSatisfied,
+            gate_name: Some(format!("{gate}")),
+            region: None,
+            row: Some(*gate_offset),
+            column: Some(format!("{column:?}")),
+            details,
+        },
+        VerifyFailure::InstanceCellNotAssigned { gate, column, row, .. } => FailureEntry {
+            kind: FailureKind::InstanceCellMismatch,
+            gate_name: Some(format!("{gate}")),
+            region: None,
+            row: Some(*row),
+            column: Some(format!("{column:?}")),
+            details,
+        },
+        VerifyFailure::ConstraintPoisoned { constraint } => FailureEntry {
+            kind: FailureKind::ConstraintNotSatisfied,
+            gate_name: Some(format!("{constraint}")),
+            region: None,
+            row: None,
+            column: None,
+            details,
+        },
+        VerifyFailure::Lookup { lookup_index, location } => FailureEntry {
+            kind: FailureKind::Lookup,
+            gate_name: Some(format!("lookup[{lookup_index}]")),
+            region: Some(format!("{location}")),
+            row: None,
+            column: None,
+            details,
+        },
+        VerifyFailure::Permutation { column, location } => FailureEntry {
+            kind: FailureKind::Permutation,
+            gate_name: None,
+            region: Some(format!("{location}")),
+            row: None,
+            column: Some(format!("{column}")),
+            details,
+        },
+    }
+}
+
+// Reads `num_<X>: N` from the `ConstraintSystem` Debug output; the fields are pub(crate)
+// in halo2_proofs 0.3.
+fn parse_usize_field(debug_str: &str, name: &str) -> usize {
+    let needle = format!("{name}: ");
+    if let Some(start) = debug_str.find(&needle) {
+        let after = &debug_str[start + needle.len()..];
+        let end = after.find(|c: char| !c.is_ascii_digit()).unwrap_or(after.len());
+        after[..end].parse().unwrap_or(0)
+    } else {
+        0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{parse_contract, transform_to_ir, IrBuilder};
+    use tempfile::TempDir;
+
+    fn descriptor_for(source: &str) -> IrDescriptor {
+        IrDescriptor::new(transform_to_ir(parse_contract(source).unwrap()).unwrap()).unwrap()
+    }
+
+    const NOTE: &str = r#"
+        struct Note {
+            #[zk_private(commit = "poseidon", randomness = "blinding")]
+            amount: u64,
+            #[zk_private]
+            blinding: Field,
+            #[zk_private]
+            delta: i64,
+        }
+    "#;
+
+    #[test]
+    fn test_schemas_follow_ir() {
+        let d = descriptor_for(NOTE);
+        let witness: Vec<(&str, &FieldType)> =
+            d.witness_schema().fields.iter().map(|f| (f.name.as_str(), &f.kind)).collect();
+        assert_eq!(
+            witness,
+            vec![
+                ("amount", &FieldType::U64),
+                ("blinding", &FieldType::Fp),
+                ("delta", &FieldType::I64)
+            ]
+        );
+        let public = &d.public_inputs_schema().fields;
+        assert_eq!(public.len(), d.num_public_inputs());
+        assert_eq!(public[0].description.as_deref(), Some("Poseidon(amount, blinding)"));
+        assert_eq!(d.name(), "Note");
+        assert_eq!(d.default_k(), d.ir().circuit_config.k());
+    }
+
+    #[test]
+    fn test_mock_prove_derives_commitment() {
+        let d = descriptor_for(NOTE);
+        let k = d.default_k();
+        let report =
+            d.mock_prove(r#"{"amount": "500", "blinding": "77", "delta": "-3"}"#, k).unwrap();
+        assert!(report.satisfied, "{:?}", report.failures);
+
+        let err = d.mock_prove(r#"{"amount": "500", "blinding": "77"}"#, k).unwrap_err();
+        assert!(matches!(err, CircuitError::InvalidWitness(msg) if msg.contains("'delta'")));
+    }
+
+    #[test]
+    fn test_mock_prove_reports_failures() {
+        let d = descriptor_for(
+            r#"
+            struct Small {
+                #[zk_private]
+                level: u8,
+            }
+        "#,
+        );
+        let report = d.mock_prove(r#"{"level": "256"}"#, d.default_k()).unwrap();
+        assert!(!report.satisfied);
+        assert!(!report.failures.is_empty());
+    }
+
+    #[test]
+    fn test_arithmetic_binding() {
+        let ir = IrBuilder::new("Total")
+            .private("a", ZkType::U64)
+            .private("b", ZkType::U64)
+            .public("total")
+            .bind(ArithOp::Mul, &["a", "b"])
+            .build()
+            .unwrap();
+        let d = IrDescriptor::new(ir).unwrap();
+        assert_eq!(d.public_inputs_schema().fields[0].description.as_deref(), Some("a * b"));
+
+        let (cells, public) = d.witnesses(r#"{"a": "6", "b": "7"}"#).unwrap();
+        assert_eq!(cells, vec![Fp::from(6), Fp::from(7)]);
+        assert_eq!(public, vec![Fp::from(42)]);
+        assert!(d.mock_prove(r#"{"a": "6", "b": "7"}"#, d.default_k()).unwrap().satisfied);
+    }
+
+    #[test]
+    fn test_signed_arithmetic_binding() {
+        let ir = IrBuilder::new("Net")
+            .private("gain", ZkType::I64)
+            .private("fee", ZkType::U64)
+            .public("net")
+            .bind(ArithOp::Sub, &["gain", "fee"])
+            .build()
+            .unwrap();
+        let d = IrDescriptor::new(ir).unwrap();
+        let witness = r#"{"gain": "-700", "fee": "50"}"#;
+        let (_, public) = d.witnesses(witness).unwrap();
+        assert_eq!(public, vec![-Fp::from(750)]);
+        assert!(d.mock_prove(witness, d.default_k()).unwrap().satisfied);
+    }
+
+    #[test]
+    fn test_register_and_inspect() {
+        let registry = zerostyl_circuits::Registry::new();
+        let d = registry.register_owned(Box::new(descriptor_for(NOTE))).unwrap();
+        assert!(std::ptr::eq(registry.get("Note").unwrap(), d));
+        assert!(registry.register_owned(Box::new(descriptor_for(NOTE))).is_err());
+
+        let info = d.inspect().unwrap();
+        assert_eq!(info.circuit_name, "Note");
+        assert_eq!(info.num_instance_columns, 1);
+        assert!(info.num_advice_columns > 0);
+    }
+
+    #[test]
+    fn test_apply_division_by_zero() {
+        assert_eq!(apply(ArithOp::Div, Fp::from(6), Fp::from(3)), Some(Fp::from(2)));
+        assert_eq!(apply(ArithOp::Div, Fp::from(6), Fp::ZERO), None);
+    }
+
+    #[test]
+    #[ignore] // Slow: generates params and proving keys
+    fn test_prove_and_verify_roundtrip() {
+        let d = descriptor_for(NOTE);
+        let k = d.default_k();
+        let tmp = TempDir::new().unwrap();
+        let witness = r#"{"amount": "500", "blinding": "77", "delta": "-3"}"#;
+        let artifact = d.prove(witness, k, tmp.path()).unwrap();
+        assert!(d.verify(&artifact.bytes, &artifact.public_inputs_json, k, tmp.path()).unwrap());
+
+        let tampered = artifact.public_inputs_json.replacen("0x", "0x01", 1);
+        assert!(!d.verify(&artifact.bytes, &tampered, k, tmp.path()).unwrap_or(false));
+    }
+}
@@ -0,0 +1,233 @@
+//! Source-located compiler diagnostics
+//!
+//! Errors raised while parsing a contract or lowering it to IR carry the location of the
+//! offending field or annotation as a [`CompilerError::Diagnostic`]. [`Diagnostic::render`]
+//! prints them the way rustc does:
+//!
+//! ```text
+//! error: Unsupported zk type: Type 'String' is not supported for zk-SNARK circuits. ...
+//!  --> contracts/vault.rs:4:5
+//!   |
+//! 4 |     owner: String,
+//!   |     ^^^^^^^^^^^^^
+//!   |
+//!   = help: remove #[zk_private] to keep this field out of the circuit
+//! ```
+//!
+//! ```rust
+//! use zerostyl_compiler::{parse_contract, transform_to_ir};
+//!
+//! let input = "struct Vault {\n    #[zk_private]\n    owner: String,\n}\n";
+//! let err = parse_contract(input).and_then(transform_to_ir).unwrap_err();
+//! let err = err.with_file("contracts/vault.rs");
+//! let diagnostic = err.diagnostic().unwrap();
+//! assert_eq!((diagnostic.span.line, diagnostic.span.column), (3, 5));
+//! eprintln!("{}", diagnostic.render());
+//! ```
+
+use std::fmt;
+
+use crate::error::CompilerError;
+
+/// A region of contract source. Lines and columns are 1-based; columns count characters.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SourceSpan {
+    pub line: usize,
+    pub column: usize,
+    /// Line of the last character in the span.
+    pub end_line: usize,
+    /// Column just past the last character in the span.
+    pub end_column: usize,
+    /// Text of the span's first line, for rendering.
+    pub snippet: String,
+}
+
+impl SourceSpan {
+    /// Locates a `syn`/`proc_macro2` span within `source`, the text it was parsed from.
+    pub(crate) fn from_span(span: proc_macro2::Span, source: &str) -> SourceSpan {
+        let (start, end) = (span.start(), span.end());
+        SourceSpan {
+            line: start.line,
+            column: start.column + 1,
+            end_line: end.line,
+            end_column: end.column + 1,
+            snippet: source.lines().nth(start.line.saturating_sub(1)).unwrap_or("").to_string(),
+        }
+    }
+
+    /// The span from the start of `self` to the end of `other`.
+    pub(crate) fn to(&self, other: &SourceSpan) -> SourceSpan {
+        SourceSpan { end_line: other.end_line, end_column: other.end_column, ..self.clone() }
+    }
+}
+
+/// A compiler error located in contract source (see the [module docs](self)).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Diagnostic {
+    pub message: String,
+    /// Path of the contract file, if the caller supplied one with
+    /// [`CompilerError::with_file`].
+    pub file: Option<String>,
+    pub span: SourceSpan,
+    pub help: Option<String>,
+}
+
+impl Diagnostic {
+    pub fn new(message: impl Into<String>, span: SourceSpan) -> Diagnostic {
+        Diagnostic { message: message.into(), file: None, span, help: None }
+    }
+
+    pub fn with_help(mut self, help: impl Into<String>) -> Diagnostic {
+        self.help = Some(help.into());
+        self
+    }
+
+    /// Renders the diagnostic in rustc's format: message, location, source line with the
+    /// span underlined, then the help text.
+    pub fn render(&self) -> String {
+        let span = &self.span;
+        let gutter = " ".repeat(span.line.to_string().len());
+        let line_chars = span.snippet.chars().count();
+        // Multi-line spans are underlined to the end of their first line
+        let end_column = if span.end_line > span.line { line_chars + 1 } else { span.end_column };
+        let underline = "^".repeat(end_column.saturating_sub(span.column).max(1));
+
+        let mut out = format!("error: {}\n", self.message);
+        out += &format!("{gutter}--> {}:{}:{}\n", self.file_name(), span.line, span.column);
+        out += &format!("{gutter} |\n");
+        out += &format!("{} | {}\n", span.line, span.snippet);
+        out += &format!("{gutter} | {}{}\n", " ".repeat(span.column - 1), underline);
+        if let Some(help) = &self.help {
+            out += &format!("{gutter} |\n{gutter} = help: {help}\n");
+        }
+        out
+    }
+
+    fn file_name(&self) -> &str {
+        self.file.as_deref().unwrap_or("<input>")
+    }
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}:{}: {}", self.file_name(), self.span.line, self.span.column, self.message)
+    }
+}
+
+impl CompilerError {
+    /// Locates the error at `span`. Errors that already carry a location keep it.
+    pub(crate) fn at(self, span: Option<&SourceSpan>) -> CompilerError {
+        match (self, span) {
+            (err @ CompilerError::Diagnostic { .. }, _) | (err, None) => err,
+            (err, Some(span)) => CompilerError::Diagnostic {
+                diagnostic: Box::new(Diagnostic::new(err.to_string(), span.clone())),
+                source: Box::new(err),
+            },
+        }
+    }
+
+    /// Like [`CompilerError::at`], with a help line.
+    pub(crate) fn at_with_help(
+        self,
+        span: Option<&SourceSpan>,
+        help: impl Into<String>,
+    ) -> CompilerError {
+        match self.at(span) {
+            CompilerError::Diagnostic { mut diagnostic, source } if diagnostic.help.is_none() => {
+                diagnostic.help = Some(help.into());
+                CompilerError::Diagnostic { diagnostic, source }
+            }
+            err => err,
+        }
+    }
+
+    /// Prefixes the message with `context`, keeping the source location if there is one
+    /// and the error's variant either way.
+    pub(crate) fn context(self, context: impl fmt::Display) -> CompilerError {
+        match self {
+            CompilerError::Diagnostic { mut diagnostic, source } => {
+                diagnostic.message = format!("{}: {}", context, diagnostic.message);
+                CompilerError::Diagnostic { diagnostic, source }
+            }
+            err => CompilerError::Context { context: context.to_string(), source: Box::new(err) },
+        }
+    }
+
+    /// Records the contract file path on a located error; other errors are unchanged.
+    pub fn with_file(self, file: impl Into<String>) -> CompilerError {
+        match self {
+            CompilerError::Diagnostic { mut diagnostic, source } => {
+                diagnostic.file = Some(file.into());
+                CompilerError::Diagnostic { diagnostic, source }
+            }
+            err => err,
+        }
+    }
+
+    /// The source location of the error, if it has one.
+    pub fn diagnostic(&self) -> Option<&Diagnostic> {
+        match self {
+            CompilerError::Diagnostic { diagnostic, .. } => Some(diagnostic),
+            _ => None,
+        }
+    }
+
+    /// The error without its source location or context, to match on its variant.
+    pub fn kind(&self) -> &CompilerError {
+        match self {
+            CompilerError::Diagnostic { source, .. } | CompilerError::Context { source, .. } => {
+                source.kind()
+            }
+            err => err,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn span(line: usize, column: usize, end_column: usize, snippet: &str) -> SourceSpan {
+        SourceSpan { line, column, end_line: line, end_column, snippet: snippet.to_string() }
+    }
+
+    #[test]
+    fn test_render_underlines_span() {
+        let diagnostic = Diagnostic::new("bad type", span(12, 5, 18, "    owner: String,"))
+            .with_help("use Address");
+        let expected = "\
+error: bad type
+  --> <input>:12:5
+   |
+12 |     owner: String,
+   |     ^^^^^^^^^^^^^
+   |
+   = help: use Address
+";
+        assert_eq!(diagnostic.render(), expected);
+    }
+
+    #[test]
+    fn test_multi_line_span_underlines_rest_of_line() {
+        let mut span = span(3, 9, 2, "    #[zk_private(");
+        span.end_line = 5;
+        let rendered = Diagnostic::new("oops", span).render();
+        assert!(rendered.ends_with("  |         ^^^^^^^^^\n"), "got:\n{rendered}");
+    }
+
+    #[test]
+    fn test_error_helpers_keep_location() {
+        let located = CompilerError::ParseError("x".to_string()).at(Some(&span(1, 1, 2, "x")));
+        let located = located.context("in 'C'").with_file("c.rs");
+        assert_eq!(located.to_string(), "c.rs:1:1: in 'C': Parse error: x");
+        assert!(matches!(located.kind(), CompilerError::ParseError(message) if message == "x"));
+
+        let unlocated = CompilerError::ParseError("x".to_string()).at(None).with_file("c.rs");
+        assert!(unlocated.diagnostic().is_none());
+        assert_eq!(unlocated.to_string(), "Parse error: x");
+
+        let unlocated = unlocated.context("in 'C'");
+        assert_eq!(unlocated.to_string(), "in 'C': Parse error: x");
+        assert!(matches!(unlocated.kind(), CompilerError::ParseError(message) if message == "x"));
+    }
+}
@@ -2,6 +2,9 @@
 
 use thiserror::Error;
 
+use crate::codegen::stylus::StylusViolation;
+use crate::diagnostic::Diagnostic;
+
 #[derive(Debug, Error)]
 pub enum CompilerError {
     #[error("Parse error: {0}")]
@@ -16,16 +19,37 @@ pub enum CompilerError {
     #[error("Invalid annotation: {0}")]
     InvalidAnnotation(String),
 
+    #[error("Invalid IR: {0}")]
+    InvalidIr(String),
+
     #[error("Syntax error: {0}")]
     SynError(#[from] syn::Error),
 
     #[error(transparent)]
     RuntimeError(#[from] zerostyl_runtime::ZeroStylError),
 
+    /// An error located in contract source; see [`Diagnostic::render`]. `source` is the
+    /// error that was located, also returned by [`CompilerError::kind`].
+    #[error("{diagnostic}")]
+    Diagnostic { diagnostic: Box<Diagnostic>, source: Box<CompilerError> },
+
+    /// An unlocated error with the context it was raised in, e.g. the contract being
+    /// transformed. `source` is also returned by [`CompilerError::kind`].
+    #[error("{context}: {source}")]
+    Context { context: String, source: Box<CompilerError> },
+
+    /// Stylus would refuse to activate the WASM; see [`crate::codegen::stylus`].
+    #[error("WASM is not Stylus-compatible: {}", join_violations(.0))]
+    StylusIncompatible(Vec<StylusViolation>),
+
     #[error("{0}")]
     Other(String),
 }
 
+fn join_violations(violations: &[StylusViolation]) -> String {
+    violations.iter().map(ToString::to_string).collect::<Vec<_>>().join("; ")
+}
+
 pub type Result<T> = std::result::Result<T, CompilerError>;
 
 #[cfg(test)]
@@ -56,6 +80,24 @@ mod tests {
         assert_eq!(err.to_string(), "Invalid annotation: missing parameter");
     }
 
+    #[test]
+    fn test_invalid_ir_display() {
+        let err = CompilerError::InvalidIr("unsupported IR format version 2".to_string());
+        assert_eq!(err.to_string(), "Invalid IR: unsupported IR format version 2");
+    }
+
+    #[test]
+    fn test_stylus_incompatible_display() {
+        let err = CompilerError::StylusIncompatible(vec![
+            StylusViolation::MissingEntrypoint,
+            StylusViolation::MissingMemoryExport,
+        ]);
+        assert_eq!(
+            err.to_string(),
+            "WASM is not Stylus-compatible: missing `user_entrypoint` export; missing `memory` export"
+        );
+    }
+
     #[test]
     fn test_other_error_display() {
         let err = CompilerError::Other("custom error message".to_string());
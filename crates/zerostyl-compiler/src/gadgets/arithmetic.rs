@@ -0,0 +1,378 @@
+//! Arithmetic chip for binary relations between field elements.
+//!
+//! Each operation occupies a single row `(lhs, rhs, out, inv)` guarded by its own selector:
+//!
+//! - Add: `lhs + rhs - out = 0`
+//! - Sub: `lhs - rhs - out = 0`
+//! - Mul: `lhs * rhs - out = 0`
+//! - Div: `rhs * out - lhs = 0` and `rhs * inv - 1 = 0`
+//!
+//! Division is expressed as multiplication by the inverse: the second constraint
+//! forces `rhs` to be non-zero by requiring the prover to exhibit its inverse, which
+//! makes `out` the unique quotient. All arithmetic is in Fp, so results wrap modulo `p`;
+//! callers that need integer semantics must range-check the operands and the result.
+
+use crate::ast::ArithOp;
+use halo2_proofs::{
+    arithmetic::Field,
+    circuit::{AssignedCell, Layouter, Value},
+    pasta::Fp,
+    plonk::{Advice, Column, ConstraintSystem, Error, Expression, Selector},
+    poly::Rotation,
+};
+
+/// Configuration for the arithmetic chip.
+#[derive(Debug, Clone)]
+pub struct ArithmeticConfig {
+    lhs_col: Column<Advice>,
+    rhs_col: Column<Advice>,
+    out_col: Column<Advice>,
+    inv_col: Column<Advice>,
+    add_selector: Selector,
+    sub_selector: Selector,
+    mul_selector: Selector,
+    div_selector: Selector,
+}
+
+/// Arithmetic chip: constrains `out = lhs ∘ rhs` for `∘ ∈ {+, -, *, /}`.
+pub struct ArithmeticChip {
+    config: ArithmeticConfig,
+}
+
+impl ArithmeticChip {
+    /// Configures the arithmetic chip.
+    ///
+    /// Allocates 4 advice columns (operands, result, and the divisor inverse)
+    /// and one selector per operation.
+    pub fn configure(meta: &mut ConstraintSystem<Fp>) -> ArithmeticConfig {
+        let lhs_col = meta.advice_column();
+        let rhs_col = meta.advice_column();
+        let out_col = meta.advice_column();
+        let inv_col = meta.advice_column();
+        meta.enable_equality(lhs_col);
+        meta.enable_equality(rhs_col);
+        meta.enable_equality(out_col);
+
+        let add_selector = meta.selector();
+        let sub_selector = meta.selector();
+        let mul_selector = meta.selector();
+        let div_selector = meta.selector();
+
+        meta.create_gate("arithmetic add", |meta| {
+            let s = meta.query_selector(add_selector);
+            let lhs = meta.query_advice(lhs_col, Rotation::cur());
+            let rhs = meta.query_advice(rhs_col, Rotation::cur());
+            let out = meta.query_advice(out_col, Rotation::cur());
+            vec![s * (lhs + rhs - out)]
+        });
+
+        meta.create_gate("arithmetic sub", |meta| {
+            let s = meta.query_selector(sub_selector);
+            let lhs = meta.query_advice(lhs_col, Rotation::cur());
+            let rhs = meta.query_advice(rhs_col, Rotation::cur());
+            let out = meta.query_advice(out_col, Rotation::cur());
+            vec![s * (lhs - rhs - out)]
+        });
+
+        meta.create_gate("arithmetic mul", |meta| {
+            let s = meta.query_selector(mul_selector);
+            let lhs = meta.query_advice(lhs_col, Rotation::cur());
+            let rhs = meta.query_advice(rhs_col, Rotation::cur());
+            let out = meta.query_advice(out_col, Rotation::cur());
+            vec![s * (lhs * rhs - out)]
+        });
+
+        // out = lhs / rhs, with rhs != 0 witnessed by its inverse
+        meta.create_gate("arithmetic div", |meta| {
+            let s = meta.query_selector(div_selector);
+            let lhs = meta.query_advice(lhs_col, Rotation::cur());
+            let rhs = meta.query_advice(rhs_col, Rotation::cur());
+            let out = meta.query_advice(out_col, Rotation::cur());
+            let inv = meta.query_advice(inv_col, Rotation::cur());
+            vec![
+                s.clone() * (rhs.clone() * out - lhs),
+                s * (rhs * inv - Expression::Constant(Fp::one())),
+            ]
+        });
+
+        ArithmeticConfig {
+            lhs_col,
+            rhs_col,
+            out_col,
+            inv_col,
+            add_selector,
+            sub_selector,
+            mul_selector,
+            div_selector,
+        }
+    }
+
+    /// Constructs the chip from configuration.
+    #[must_use]
+    pub fn construct(config: ArithmeticConfig) -> Self {
+        Self { config }
+    }
+
+    /// Computes `lhs ∘ rhs` and returns the constrained result cell.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error`] if synthesis fails.
+    pub fn apply(
+        &self,
+        layouter: impl Layouter<Fp>,
+        op: ArithOp,
+        lhs: &AssignedCell<Fp, Fp>,
+        rhs: &AssignedCell<Fp, Fp>,
+    ) -> Result<AssignedCell<Fp, Fp>, Error> {
+        self.assign(layouter, op, lhs, rhs, None)
+    }
+
+    /// Proves that `out = lhs ∘ rhs` for an already-assigned `out`.
+    ///
+    /// For [`ArithOp::Div`] this also proves that `rhs` is non-zero.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error`] if synthesis fails.
+    pub fn constrain(
+        &self,
+        layouter: impl Layouter<Fp>,
+        op: ArithOp,
+        lhs: &AssignedCell<Fp, Fp>,
+        rhs: &AssignedCell<Fp, Fp>,
+        out: &AssignedCell<Fp, Fp>,
+    ) -> Result<(), Error> {
+        self.assign(layouter, op, lhs, rhs, Some(out)).map(|_| ())
+    }
+
+    fn assign(
+        &self,
+        mut layouter: impl Layouter<Fp>,
+        op: ArithOp,
+        lhs: &AssignedCell<Fp, Fp>,
+        rhs: &AssignedCell<Fp, Fp>,
+        out: Option<&AssignedCell<Fp, Fp>>,
+    ) -> Result<AssignedCell<Fp, Fp>, Error> {
+        layouter.assign_region(
+            || format!("arithmetic {:?}", op),
+            |mut region| {
+                let selector = match op {
+                    ArithOp::Add => self.config.add_selector,
+                    ArithOp::Sub => self.config.sub_selector,
+                    ArithOp::Mul => self.config.mul_selector,
+                    ArithOp::Div => self.config.div_selector,
+                };
+                selector.enable(&mut region, 0)?;
+
+                let lhs = lhs.copy_advice(|| "lhs", &mut region, self.config.lhs_col, 0)?;
+                let rhs = rhs.copy_advice(|| "rhs", &mut region, self.config.rhs_col, 0)?;
+
+                // A zero divisor has no inverse; assigning zero leaves the div gate unsatisfied.
+                let rhs_inv = rhs.value().map(|r| Option::from(r.invert()).unwrap_or(Fp::zero()));
+                if op == ArithOp::Div {
+                    region.assign_advice(|| "rhs inverse", self.config.inv_col, 0, || rhs_inv)?;
+                }
+
+                match out {
+                    Some(out) => out.copy_advice(|| "out", &mut region, self.config.out_col, 0),
+                    None => {
+                        let out_val =
+                            lhs.value().copied().zip(rhs.value().copied()).zip(rhs_inv).map(
+                                |((l, r), r_inv)| match op {
+                                    ArithOp::Add => l + r,
+                                    ArithOp::Sub => l - r,
+                                    ArithOp::Mul => l * r,
+                                    ArithOp::Div => l * r_inv,
+                                },
+                            );
+                        region.assign_advice(|| "out", self.config.out_col, 0, || out_val)
+                    }
+                }
+            },
+        )
+    }
+
+    /// Loads a value into an advice cell.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error`] if the assignment fails.
+    pub fn load_value(
+        &self,
+        mut layouter: impl Layouter<Fp>,
+        value: Value<Fp>,
+    ) -> Result<AssignedCell<Fp, Fp>, Error> {
+        layouter.assign_region(
+            || "load arithmetic value",
+            |mut region| {
+                region.assign_advice(|| "arithmetic value", self.config.lhs_col, 0, || value)
+            },
+        )
+    }
+
+    /// Loads a constant into an advice cell, fixed by a copy constraint.
+    ///
+    /// The circuit must have enabled a constants column
+    /// ([`ConstraintSystem::enable_constant`]).
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error`] if the assignment fails.
+    pub fn load_constant(
+        &self,
+        mut layouter: impl Layouter<Fp>,
+        value: Fp,
+    ) -> Result<AssignedCell<Fp, Fp>, Error> {
+        layouter.assign_region(
+            || "load arithmetic constant",
+            |mut region| {
+                region.assign_advice_from_constant(
+                    || "arithmetic constant",
+                    self.config.lhs_col,
+                    0,
+                    value,
+                )
+            },
+        )
+    }
+
+    /// Returns a reference to the chip configuration.
+    #[must_use]
+    pub fn config(&self) -> &ArithmeticConfig {
+        &self.config
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use halo2_proofs::{circuit::SimpleFloorPlanner, dev::MockProver, plonk::Circuit};
+
+    #[derive(Clone)]
+    struct ArithmeticTestCircuit {
+        lhs: Value<Fp>,
+        rhs: Value<Fp>,
+        out: Value<Fp>,
+        op: ArithOp,
+    }
+
+    impl Circuit<Fp> for ArithmeticTestCircuit {
+        type Config = ArithmeticConfig;
+        type FloorPlanner = SimpleFloorPlanner;
+
+        fn without_witnesses(&self) -> Self {
+            Self {
+                lhs: Value::unknown(),
+                rhs: Value::unknown(),
+                out: Value::unknown(),
+                op: self.op,
+            }
+        }
+
+        fn configure(meta: &mut ConstraintSystem<Fp>) -> ArithmeticConfig {
+            ArithmeticChip::configure(meta)
+        }
+
+        fn synthesize(
+            &self,
+            config: ArithmeticConfig,
+            mut layouter: impl Layouter<Fp>,
+        ) -> Result<(), Error> {
+            let chip = ArithmeticChip::construct(config);
+            let lhs = chip.load_value(layouter.namespace(|| "load lhs"), self.lhs)?;
+            let rhs = chip.load_value(layouter.namespace(|| "load rhs"), self.rhs)?;
+            let out = chip.load_value(layouter.namespace(|| "load out"), self.out)?;
+            chip.constrain(layouter.namespace(|| "relation"), self.op, &lhs, &rhs, &out)
+        }
+    }
+
+    fn run_arithmetic(lhs: Fp, rhs: Fp, out: Fp, op: ArithOp) -> bool {
+        let circuit = ArithmeticTestCircuit {
+            lhs: Value::known(lhs),
+            rhs: Value::known(rhs),
+            out: Value::known(out),
+            op,
+        };
+        let prover = MockProver::run(5, &circuit, vec![]).unwrap();
+        prover.verify().is_ok()
+    }
+
+    #[test]
+    fn test_add() {
+        assert!(run_arithmetic(Fp::from(2), Fp::from(3), Fp::from(5), ArithOp::Add));
+        assert!(!run_arithmetic(Fp::from(2), Fp::from(3), Fp::from(6), ArithOp::Add));
+    }
+
+    #[test]
+    fn test_sub() {
+        assert!(run_arithmetic(Fp::from(10), Fp::from(4), Fp::from(6), ArithOp::Sub));
+        assert!(!run_arithmetic(Fp::from(10), Fp::from(4), Fp::from(14), ArithOp::Sub));
+    }
+
+    #[test]
+    fn test_sub_wraps_modulo_p() {
+        assert!(run_arithmetic(Fp::from(1), Fp::from(2), -Fp::one(), ArithOp::Sub));
+    }
+
+    #[test]
+    fn test_mul() {
+        assert!(run_arithmetic(Fp::from(6), Fp::from(7), Fp::from(42), ArithOp::Mul));
+        assert!(!run_arithmetic(Fp::from(6), Fp::from(7), Fp::from(43), ArithOp::Mul));
+    }
+
+    #[test]
+    fn test_div() {
+        assert!(run_arithmetic(Fp::from(42), Fp::from(7), Fp::from(6), ArithOp::Div));
+        assert!(!run_arithmetic(Fp::from(42), Fp::from(7), Fp::from(5), ArithOp::Div));
+    }
+
+    #[test]
+    fn test_div_by_zero_rejected() {
+        assert!(!run_arithmetic(Fp::from(0), Fp::from(0), Fp::from(0), ArithOp::Div));
+        assert!(!run_arithmetic(Fp::from(5), Fp::from(0), Fp::from(0), ArithOp::Div));
+    }
+
+    #[test]
+    fn test_apply_computes_result() {
+        #[derive(Clone)]
+        struct ApplyCircuit;
+
+        impl Circuit<Fp> for ApplyCircuit {
+            type Config = ArithmeticConfig;
+            type FloorPlanner = SimpleFloorPlanner;
+
+            fn without_witnesses(&self) -> Self {
+                Self
+            }
+
+            fn configure(meta: &mut ConstraintSystem<Fp>) -> ArithmeticConfig {
+                ArithmeticChip::configure(meta)
+            }
+
+            fn synthesize(
+                &self,
+                config: ArithmeticConfig,
+                mut layouter: impl Layouter<Fp>,
+            ) -> Result<(), Error> {
+                let chip = ArithmeticChip::construct(config);
+                let a = chip.load_value(layouter.namespace(|| "a"), Value::known(Fp::from(9)))?;
+                let b = chip.load_value(layouter.namespace(|| "b"), Value::known(Fp::from(3)))?;
+                for op in [ArithOp::Add, ArithOp::Sub, ArithOp::Mul, ArithOp::Div] {
+                    let out = chip.apply(layouter.namespace(|| "apply"), op, &a, &b)?;
+                    let expected = match op {
+                        ArithOp::Add => Fp::from(12),
+                        ArithOp::Sub => Fp::from(6),
+                        ArithOp::Mul => Fp::from(27),
+                        ArithOp::Div => Fp::from(3),
+                    };
+                    out.value().assert_if_known(|v| **v == expected);
+                }
+                Ok(())
+            }
+        }
+
+        let prover = MockProver::run(5, &ApplyCircuit, vec![]).unwrap();
+        prover.verify().unwrap();
+    }
+}
@@ -9,19 +9,29 @@
 //! - `a <= b  ⟺  b >= a`
 //!
 //! The difference is constrained by a custom gate, then range-checked
-//! using [`RangeProofChip`].
+//! using [`RangeProofChip`], or a shared [`LookupRangeChip`] when configured with
+//! [`ComparisonChip::configure_with_lookup`].
+//!
+//! Equality needs no range check: `a == b` is a copy constraint, and `a != b`
+//! is proven by witnessing `inv = (a - b)^-1` and constraining `(a - b) * inv = 1`,
+//! which has no solution when `a - b = 0`.
 //!
 //! # Preconditions
 //!
-//! Both operands **must** be pre-range-checked to `[0, 2^num_bits)` before
-//! calling any comparison method. Without this guarantee, field arithmetic
-//! wraps modulo `p` and the comparison is **unsound**. For example, if `a`
+//! For the ordering comparisons, both operands **must** be pre-range-checked to
+//! `[0, 2^num_bits)` before calling any comparison method. Without this guarantee,
+//! field arithmetic wraps modulo `p` and the comparison is **unsound**. For example, if `a`
 //! is actually `p - 1` (a valid field element), `a - b` overflows into a
 //! small positive value, making the range check pass even though `a < b`
 //! in the integer sense.
+//!
+//! `num_bits` may exceed 64 (e.g. 128 for `u128` operands); wide differences are
+//! checked limb by limb, up to [`RangeProofChip::MAX_BOUNDED_BITS`].
 
+use super::lookup_range::{LookupRangeChip, LookupRangeConfig};
 use super::range::{RangeProofChip, RangeProofConfig};
 use halo2_proofs::{
+    arithmetic::Field,
     circuit::{AssignedCell, Layouter, Value},
     pasta::Fp,
     plonk::{Advice, Column, ConstraintSystem, Error, Expression, Selector},
@@ -31,17 +41,54 @@ use halo2_proofs::{
 /// Configuration for the comparison chip.
 #[derive(Debug, Clone)]
 pub struct ComparisonConfig {
-    range_config: RangeProofConfig,
+    range_config: DiffRangeConfig,
     left_col: Column<Advice>,
     right_col: Column<Advice>,
     diff_col: Column<Advice>,
     gte_selector: Selector,
     gt_selector: Selector,
+    neq_selector: Selector,
+}
+
+impl ComparisonConfig {
+    /// Returns the same gates with differences range-checked by an existing
+    /// [`LookupRangeChip`] instead, for circuits that pick their range chip at synthesis time.
+    ///
+    /// The caller loads the table once with [`LookupRangeChip::load_table`].
+    pub(crate) fn with_lookup(&self, lookup_config: LookupRangeConfig) -> Self {
+        Self { range_config: DiffRangeConfig::Lookup(lookup_config), ..self.clone() }
+    }
+}
+
+/// Range check applied to ordering differences.
+#[derive(Debug, Clone)]
+enum DiffRangeConfig {
+    Bits(RangeProofConfig),
+    Lookup(LookupRangeConfig),
+}
+
+impl DiffRangeConfig {
+    fn check_range(
+        &self,
+        layouter: impl Layouter<Fp>,
+        value: AssignedCell<Fp, Fp>,
+        num_bits: usize,
+    ) -> Result<(), Error> {
+        match self {
+            DiffRangeConfig::Bits(config) => {
+                RangeProofChip::construct(config.clone()).check_range(layouter, value, num_bits)
+            }
+            DiffRangeConfig::Lookup(config) => {
+                LookupRangeChip::construct(config.clone()).check_range(layouter, value, num_bits)
+            }
+        }
+    }
 }
 
 /// Comparison chip: proves ordering relationships between field elements.
 ///
-/// Uses [`RangeProofChip`] internally to range-check differences.
+/// Uses [`RangeProofChip`] internally to range-check differences, or [`LookupRangeChip`]
+/// when configured with [`Self::configure_with_lookup`].
 pub struct ComparisonChip {
     config: ComparisonConfig,
 }
@@ -49,11 +96,28 @@ pub struct ComparisonChip {
 impl ComparisonChip {
     /// Configures the comparison chip.
     ///
-    /// Allocates 3 advice columns for difference computation, 2 selectors
-    /// for the `>=` and `>` gates, plus the columns required by [`RangeProofChip`].
+    /// Allocates 3 advice columns for difference computation, 3 selectors
+    /// for the `>=`, `>` and `!=` gates, plus the columns required by [`RangeProofChip`].
     pub fn configure(meta: &mut ConstraintSystem<Fp>) -> ComparisonConfig {
-        let range_config = RangeProofChip::configure(meta);
+        let range_config = DiffRangeConfig::Bits(RangeProofChip::configure(meta));
+        Self::configure_gates(meta, range_config)
+    }
+
+    /// Configures the comparison chip to range-check differences with an existing
+    /// [`LookupRangeChip`], sharing its columns and table.
+    ///
+    /// The caller loads the table once with [`LookupRangeChip::load_table`].
+    pub fn configure_with_lookup(
+        meta: &mut ConstraintSystem<Fp>,
+        lookup_config: LookupRangeConfig,
+    ) -> ComparisonConfig {
+        Self::configure_gates(meta, DiffRangeConfig::Lookup(lookup_config))
+    }
 
+    fn configure_gates(
+        meta: &mut ConstraintSystem<Fp>,
+        range_config: DiffRangeConfig,
+    ) -> ComparisonConfig {
         let left_col = meta.advice_column();
         let right_col = meta.advice_column();
         let diff_col = meta.advice_column();
@@ -82,7 +146,26 @@ impl ComparisonChip {
             vec![s * (left - right - Expression::Constant(Fp::one()) - diff)]
         });
 
-        ComparisonConfig { range_config, left_col, right_col, diff_col, gte_selector, gt_selector }
+        let neq_selector = meta.selector();
+
+        // (left - right) * inv = 1, with inv witnessed in diff_col (for !=)
+        meta.create_gate("comparison neq inverse", |meta| {
+            let s = meta.query_selector(neq_selector);
+            let left = meta.query_advice(left_col, Rotation::cur());
+            let right = meta.query_advice(right_col, Rotation::cur());
+            let inv = meta.query_advice(diff_col, Rotation::cur());
+            vec![s * ((left - right) * inv - Expression::Constant(Fp::one()))]
+        });
+
+        ComparisonConfig {
+            range_config,
+            left_col,
+            right_col,
+            diff_col,
+            gte_selector,
+            gt_selector,
+            neq_selector,
+        }
     }
 
     /// Constructs the chip from configuration.
@@ -111,6 +194,9 @@ impl ComparisonChip {
         right: AssignedCell<Fp, Fp>,
         num_bits: usize,
     ) -> Result<(), Error> {
+        if num_bits > RangeProofChip::MAX_BOUNDED_BITS {
+            return Err(Error::Synthesis);
+        }
         let diff = layouter.assign_region(
             || "compute left - right - 1",
             |mut region| {
@@ -126,8 +212,11 @@ impl ComparisonChip {
             },
         )?;
 
-        let range_chip = RangeProofChip::construct(self.config.range_config.clone());
-        range_chip.check_range(layouter.namespace(|| "range check gt diff"), diff, num_bits)
+        self.config.range_config.check_range(
+            layouter.namespace(|| "range check gt diff"),
+            diff,
+            num_bits,
+        )
     }
 
     /// Proves that `left >= right`.
@@ -150,6 +239,9 @@ impl ComparisonChip {
         right: AssignedCell<Fp, Fp>,
         num_bits: usize,
     ) -> Result<(), Error> {
+        if num_bits > RangeProofChip::MAX_BOUNDED_BITS {
+            return Err(Error::Synthesis);
+        }
         let diff = layouter.assign_region(
             || "compute left - right",
             |mut region| {
@@ -162,8 +254,11 @@ impl ComparisonChip {
             },
         )?;
 
-        let range_chip = RangeProofChip::construct(self.config.range_config.clone());
-        range_chip.check_range(layouter.namespace(|| "range check gte diff"), diff, num_bits)
+        self.config.range_config.check_range(
+            layouter.namespace(|| "range check gte diff"),
+            diff,
+            num_bits,
+        )
     }
 
     /// Proves that `left < right`.
@@ -210,6 +305,57 @@ impl ComparisonChip {
         self.assert_gte(layouter, right, left, num_bits)
     }
 
+    /// Proves that `left == right` with a copy constraint.
+    ///
+    /// Unlike the ordering comparisons, no range check is needed.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error`] if synthesis fails.
+    pub fn assert_eq(
+        &self,
+        mut layouter: impl Layouter<Fp>,
+        left: AssignedCell<Fp, Fp>,
+        right: AssignedCell<Fp, Fp>,
+    ) -> Result<(), Error> {
+        layouter.assign_region(
+            || "constrain left == right",
+            |mut region| region.constrain_equal(left.cell(), right.cell()),
+        )
+    }
+
+    /// Proves that `left != right`.
+    ///
+    /// Witnesses `inv = (left - right)^-1` and constrains `(left - right) * inv = 1`.
+    /// Unlike the ordering comparisons, no range check is needed.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error`] if synthesis fails.
+    pub fn assert_neq(
+        &self,
+        mut layouter: impl Layouter<Fp>,
+        left: AssignedCell<Fp, Fp>,
+        right: AssignedCell<Fp, Fp>,
+    ) -> Result<(), Error> {
+        layouter.assign_region(
+            || "prove left != right",
+            |mut region| {
+                self.config.neq_selector.enable(&mut region, 0)?;
+                left.copy_advice(|| "left", &mut region, self.config.left_col, 0)?;
+                right.copy_advice(|| "right", &mut region, self.config.right_col, 0)?;
+                // Equal operands have no inverse; assigning zero leaves the gate unsatisfied.
+                let inv_val = left
+                    .value()
+                    .copied()
+                    .zip(right.value().copied())
+                    .map(|(l, r)| Option::from((l - r).invert()).unwrap_or(Fp::zero()));
+                region.assign_advice(|| "inverse", self.config.diff_col, 0, || inv_val)?;
+                Ok(())
+            },
+        )
+    }
+
     /// Loads a value into an advice cell.
     ///
     /// # Errors
@@ -239,6 +385,7 @@ impl ComparisonChip {
 mod tests {
     use super::*;
     use halo2_proofs::{circuit::SimpleFloorPlanner, dev::MockProver, plonk::Circuit};
+    use halo2curves::ff::PrimeField;
 
     #[derive(Clone)]
     enum ComparisonOp {
@@ -246,6 +393,8 @@ mod tests {
         Gte,
         Lt,
         Lte,
+        Eq,
+        Neq,
     }
 
     #[derive(Clone)]
@@ -306,6 +455,12 @@ mod tests {
                     right_cell,
                     self.num_bits,
                 ),
+                ComparisonOp::Eq => {
+                    chip.assert_eq(layouter.namespace(|| "eq"), left_cell, right_cell)
+                }
+                ComparisonOp::Neq => {
+                    chip.assert_neq(layouter.namespace(|| "neq"), left_cell, right_cell)
+                }
             }
         }
     }
@@ -322,6 +477,17 @@ mod tests {
         prover.verify().is_ok()
     }
 
+    fn run_wide_comparison(left: u128, right: u128, op: ComparisonOp) -> bool {
+        let circuit = ComparisonTestCircuit {
+            left: Value::known(Fp::from_u128(left)),
+            right: Value::known(Fp::from_u128(right)),
+            num_bits: 128,
+            op,
+        };
+        let prover = MockProver::run(10, &circuit, vec![]).unwrap();
+        prover.verify().is_ok()
+    }
+
     // --- Greater than ---
 
     #[test]
@@ -404,4 +570,107 @@ mod tests {
     fn test_lte_greater_rejected() {
         assert!(!run_comparison(100, 50, 64, ComparisonOp::Lte));
     }
+
+    // --- Equal ---
+
+    #[test]
+    fn test_eq_valid() {
+        assert!(run_comparison(100, 100, 64, ComparisonOp::Eq));
+    }
+
+    #[test]
+    fn test_eq_different_rejected() {
+        assert!(!run_comparison(100, 101, 64, ComparisonOp::Eq));
+    }
+
+    // --- Not equal ---
+
+    #[test]
+    fn test_neq_valid() {
+        assert!(run_comparison(100, 101, 64, ComparisonOp::Neq));
+        assert!(run_comparison(101, 100, 64, ComparisonOp::Neq));
+    }
+
+    #[test]
+    fn test_neq_equal_rejected() {
+        assert!(!run_comparison(100, 100, 64, ComparisonOp::Neq));
+        assert!(!run_comparison(0, 0, 64, ComparisonOp::Neq));
+    }
+
+    // --- 128-bit operands ---
+
+    #[test]
+    fn test_gt_u128_operands() {
+        assert!(run_wide_comparison(u128::MAX, u128::MAX - 1, ComparisonOp::Gt));
+        assert!(!run_wide_comparison(u128::MAX - 1, u128::MAX, ComparisonOp::Gt));
+        assert!(run_wide_comparison(1u128 << 64, u128::from(u64::MAX), ComparisonOp::Gt));
+    }
+
+    #[test]
+    fn test_lte_u128_operands() {
+        assert!(run_wide_comparison(1u128 << 100, 1u128 << 100, ComparisonOp::Lte));
+        assert!(!run_wide_comparison((1u128 << 100) + 1, 1u128 << 100, ComparisonOp::Lte));
+    }
+
+    #[test]
+    fn test_ordering_rejects_unsound_width() {
+        let circuit = ComparisonTestCircuit {
+            left: Value::known(Fp::from(2u64)),
+            right: Value::known(Fp::from(1u64)),
+            num_bits: RangeProofChip::MAX_BITS,
+            op: ComparisonOp::Gt,
+        };
+        assert!(MockProver::run(10, &circuit, vec![]).is_err());
+    }
+
+    // --- Lookup range backend ---
+
+    #[derive(Clone)]
+    struct LookupGtCircuit {
+        left: Value<Fp>,
+        right: Value<Fp>,
+    }
+
+    impl Circuit<Fp> for LookupGtCircuit {
+        type Config = (LookupRangeConfig, ComparisonConfig);
+        type FloorPlanner = SimpleFloorPlanner;
+
+        fn without_witnesses(&self) -> Self {
+            Self { left: Value::unknown(), right: Value::unknown() }
+        }
+
+        fn configure(meta: &mut ConstraintSystem<Fp>) -> Self::Config {
+            let lookup_config = LookupRangeChip::configure(meta);
+            let comparison_config =
+                ComparisonChip::configure_with_lookup(meta, lookup_config.clone());
+            (lookup_config, comparison_config)
+        }
+
+        fn synthesize(
+            &self,
+            (lookup_config, config): Self::Config,
+            mut layouter: impl Layouter<Fp>,
+        ) -> Result<(), Error> {
+            LookupRangeChip::construct(lookup_config).load_table(layouter.namespace(|| "table"))?;
+            let chip = ComparisonChip::construct(config);
+            let left_cell = chip.load_value(layouter.namespace(|| "load left"), self.left)?;
+            let right_cell = chip.load_value(layouter.namespace(|| "load right"), self.right)?;
+            chip.assert_gt(layouter.namespace(|| "gt"), left_cell, right_cell, 64)
+        }
+    }
+
+    #[test]
+    fn test_gt_with_lookup_range() {
+        let run = |left: u64, right: u64| {
+            let circuit = LookupGtCircuit {
+                left: Value::known(Fp::from(left)),
+                right: Value::known(Fp::from(right)),
+            };
+            MockProver::run(9, &circuit, vec![]).unwrap().verify().is_ok()
+        };
+        assert!(run(101, 100));
+        assert!(run(u64::MAX, 0));
+        assert!(!run(100, 100));
+        assert!(!run(0, u64::MAX));
+    }
 }
@@ -0,0 +1,495 @@
+//! Range proof chip via 8-bit limb lookups.
+//!
+//! Same interface as [`RangeProofChip`](super::RangeProofChip), but each row checks a whole
+//! 8-bit limb against a fixed table of `0..256` instead of a single bit, so a `u64` costs 8
+//! rows instead of 65.
+//!
+//! # Choosing a range chip
+//!
+//! [`ZkCircuit`](crate::ZkCircuit) proves field ranges with
+//! [`RangeProofChip`](super::RangeProofChip) unless its IR selects this chip with
+//! [`CircuitConfig::with_range_chip`](crate::CircuitConfig::with_range_chip). The table
+//! occupies 256 rows of its own column, so the circuit is at least 256 rows tall; the lookup
+//! chip only lowers `k` once the bit-decomposition regions stack well past that.
+//! [`measure_rows`](crate::measure_rows) reports the height of any circuit either way; for
+//! the example circuits (`tests/circuit_tests.rs` asserts these figures):
+//!
+//! | circuit     | range rows, bits | range rows, lookup | height, bits  | height, lookup |
+//! |-------------|------------------|--------------------|---------------|----------------|
+//! | `TxPrivacy` | 715              | 88                 | 727 (k = 10)  | 256 (k = 9)    |
+//! | `StateMask` | 455              | 56                 | 461 (k = 9)   | 256 (k = 9)    |
+//!
+//! # How it works
+//!
+//! For a value `v` with `num_bits = N`, split into `L = ceil(N / 8)` limbs, the region holds
+//! the running sum `z_0 = v`, `z_{i+1} = (z_i - l_i) / 256` one per row, and looks up
+//! - `l_i = z_i - 256 * z_{i+1}` for every row but the last,
+//! - `l_{L-1} = z_{L-1}` on the last row, which forces `z_L = 0`,
+//! - `l_{L-1} * 2^(8 - t)` when the top limb only has `t = N - 8 (L - 1) < 8` bits, which is
+//!   in the table only if `l_{L-1} < 2^t`.
+//!
+//! So `v = sum(l_i * 256^i) < 2^N`, and since `2^254 < p` no other value can alias it. A value
+//! that does not fit leaves a limb outside the table, reported as a lookup failure.
+//!
+//! Bounded ranges `[min, max]` are proven exactly like
+//! [`RangeProofChip::check_range_bounded`](super::RangeProofChip::check_range_bounded).
+//!
+//! The table must be loaded once per circuit with [`LookupRangeChip::load_table`].
+
+use halo2_proofs::{
+    circuit::{AssignedCell, Layouter, Value},
+    pasta::Fp,
+    plonk::{Advice, Column, ConstraintSystem, Error, Expression, Fixed, Selector, TableColumn},
+    poly::Rotation,
+};
+use halo2curves::ff::PrimeField;
+
+/// Configuration for the lookup range chip.
+#[derive(Debug, Clone)]
+pub struct LookupRangeConfig {
+    value_col: Column<Advice>,
+    diff_col: Column<Advice>,
+    table: TableColumn,
+    running_selector: Selector,
+    last_selector: Selector,
+    short_selector: Selector,
+    bounded_diff_selector: Selector,
+    bounded_diff_reverse_selector: Selector,
+    /// Top-limb shift `2^(8 - t)` in range regions, bound constants in bounded regions.
+    fixed_col: Column<Fixed>,
+}
+
+/// Lookup range chip: proves `value ∈ [0, 2^num_bits)` one 8-bit limb per row.
+pub struct LookupRangeChip {
+    config: LookupRangeConfig,
+}
+
+impl LookupRangeChip {
+    /// Width of a limb, and of the lookup table (`0..2^LIMB_BITS`).
+    pub const LIMB_BITS: usize = 8;
+
+    /// Largest width accepted by [`Self::check_range`] (`2^254 < p` for the Pasta base field).
+    pub const MAX_BITS: usize = 254;
+
+    /// Largest width accepted by [`Self::check_range_bounded`] and ordering comparisons.
+    pub const MAX_BOUNDED_BITS: usize = 253;
+
+    /// Configures the lookup range chip.
+    ///
+    /// Allocates 2 advice columns, 1 fixed column, the table column, two lookup arguments and
+    /// selectors for the limb lookups and bounded difference gates.
+    pub fn configure(meta: &mut ConstraintSystem<Fp>) -> LookupRangeConfig {
+        let value_col = meta.advice_column();
+        let diff_col = meta.advice_column();
+        meta.enable_equality(value_col);
+        meta.enable_equality(diff_col);
+
+        let table = meta.lookup_table_column();
+        let running_selector = meta.complex_selector();
+        let last_selector = meta.complex_selector();
+        let short_selector = meta.complex_selector();
+        let bounded_diff_selector = meta.selector();
+        let bounded_diff_reverse_selector = meta.selector();
+        let fixed_col = meta.fixed_column();
+
+        let limb_base = Expression::Constant(Fp::from(1u64 << Self::LIMB_BITS));
+
+        // Limb lookup: z_cur - 2^8 * z_next on running rows, z_cur on the last row
+        meta.lookup(|meta| {
+            let running = meta.query_selector(running_selector);
+            let last = meta.query_selector(last_selector);
+            let z_cur = meta.query_advice(value_col, Rotation::cur());
+            let z_next = meta.query_advice(value_col, Rotation::next());
+            let limb = running * (z_cur.clone() - limb_base * z_next) + last * z_cur;
+            vec![(limb, table)]
+        });
+
+        // Short top limb: l * 2^(8 - t) must also be a limb
+        meta.lookup(|meta| {
+            let s = meta.query_selector(short_selector);
+            let top = meta.query_advice(value_col, Rotation::cur());
+            let shift = meta.query_fixed(fixed_col);
+            vec![(s * top * shift, table)]
+        });
+
+        // Bounded diff constraint: value - constant - diff == 0 (diff = value - min)
+        meta.create_gate("lookup bounded diff", |meta| {
+            let s = meta.query_selector(bounded_diff_selector);
+            let value = meta.query_advice(value_col, Rotation::cur());
+            let constant = meta.query_fixed(fixed_col);
+            let diff = meta.query_advice(diff_col, Rotation::cur());
+            vec![s * (value - constant - diff)]
+        });
+
+        // Bounded diff reverse constraint: constant - value - diff == 0 (diff = max - value)
+        meta.create_gate("lookup bounded diff reverse", |meta| {
+            let s = meta.query_selector(bounded_diff_reverse_selector);
+            let value = meta.query_advice(value_col, Rotation::cur());
+            let constant = meta.query_fixed(fixed_col);
+            let diff = meta.query_advice(diff_col, Rotation::cur());
+            vec![s * (constant - value - diff)]
+        });
+
+        LookupRangeConfig {
+            value_col,
+            diff_col,
+            table,
+            running_selector,
+            last_selector,
+            short_selector,
+            bounded_diff_selector,
+            bounded_diff_reverse_selector,
+            fixed_col,
+        }
+    }
+
+    /// Constructs the chip from configuration.
+    #[must_use]
+    pub fn construct(config: LookupRangeConfig) -> Self {
+        Self { config }
+    }
+
+    /// Fills the limb table with `0..2^LIMB_BITS`.
+    ///
+    /// Must be called exactly once per circuit, including when the config is shared with a
+    /// [`ComparisonChip`](super::ComparisonChip).
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error`] if the table assignment fails.
+    pub fn load_table(&self, mut layouter: impl Layouter<Fp>) -> Result<(), Error> {
+        layouter.assign_table(
+            || "range limb table",
+            |mut table| {
+                for limb in 0..(1u64 << Self::LIMB_BITS) {
+                    table.assign_cell(
+                        || format!("limb {}", limb),
+                        self.config.table,
+                        limb as usize,
+                        || Value::known(Fp::from(limb)),
+                    )?;
+                }
+                Ok(())
+            },
+        )
+    }
+
+    /// Proves that `value ∈ [0, 2^num_bits)`.
+    ///
+    /// Splits the value into 8-bit limbs (least significant first) and looks each one up in
+    /// the table, narrowing the top limb to the bits left over.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` — The value to range-check (already assigned)
+    /// * `num_bits` — Number of bits, from 1 to [`Self::MAX_BITS`]
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Synthesis`] if `num_bits` is out of bounds, or [`Error`] if
+    /// synthesis fails.
+    pub fn check_range(
+        &self,
+        mut layouter: impl Layouter<Fp>,
+        value: AssignedCell<Fp, Fp>,
+        num_bits: usize,
+    ) -> Result<(), Error> {
+        if num_bits == 0 || num_bits > Self::MAX_BITS {
+            return Err(Error::Synthesis);
+        }
+        let num_limbs = num_bits.div_ceil(Self::LIMB_BITS);
+        let top_bits = num_bits - Self::LIMB_BITS * (num_limbs - 1);
+
+        layouter.assign_region(
+            || format!("lookup range check {} bits", num_bits),
+            |mut region| {
+                let value_fp = value.value().copied();
+                value.copy_advice(|| "z 0", &mut region, self.config.value_col, 0)?;
+
+                // z_i = v >> 8i, read from the little-endian canonical repr
+                for row in 1..num_limbs {
+                    let z = value_fp.map(|v| {
+                        let repr = v.to_repr();
+                        let mut shifted = [0u8; 32];
+                        shifted[..32 - row].copy_from_slice(&repr.as_ref()[row..]);
+                        Fp::from_repr(shifted).unwrap()
+                    });
+                    region.assign_advice(
+                        || format!("z {}", row),
+                        self.config.value_col,
+                        row,
+                        || z,
+                    )?;
+                }
+
+                for row in 0..num_limbs - 1 {
+                    self.config.running_selector.enable(&mut region, row)?;
+                }
+                let last = num_limbs - 1;
+                self.config.last_selector.enable(&mut region, last)?;
+                if top_bits < Self::LIMB_BITS {
+                    self.config.short_selector.enable(&mut region, last)?;
+                    region.assign_fixed(
+                        || "top limb shift",
+                        self.config.fixed_col,
+                        last,
+                        || Value::known(Fp::from(1u64 << (Self::LIMB_BITS - top_bits))),
+                    )?;
+                }
+                Ok(())
+            },
+        )
+    }
+
+    /// Proves that `value ∈ [min, max]`.
+    ///
+    /// Works by proving:
+    /// - `value - min ∈ [0, 2^num_bits)` (value >= min)
+    /// - `max - value ∈ [0, 2^num_bits)` (value <= max)
+    ///
+    /// The caller must ensure that `max - min < 2^num_bits`.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` — The value to bound-check (already assigned)
+    /// * `min` — Minimum bound (inclusive)
+    /// * `max` — Maximum bound (inclusive)
+    /// * `num_bits` — Number of bits for the range check, at most [`Self::MAX_BOUNDED_BITS`]
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Synthesis`] if `num_bits` is out of bounds, or [`Error`] if
+    /// synthesis fails.
+    pub fn check_range_bounded(
+        &self,
+        mut layouter: impl Layouter<Fp>,
+        value: AssignedCell<Fp, Fp>,
+        min: Fp,
+        max: Fp,
+        num_bits: usize,
+    ) -> Result<(), Error> {
+        if num_bits == 0 || num_bits > Self::MAX_BOUNDED_BITS {
+            return Err(Error::Synthesis);
+        }
+        // value_minus_min = value - min (constrained by "lookup bounded diff" gate)
+        let value_minus_min = layouter.assign_region(
+            || "compute value - min",
+            |mut region| {
+                self.config.bounded_diff_selector.enable(&mut region, 0)?;
+                let v = value.copy_advice(|| "value", &mut region, self.config.value_col, 0)?;
+                region.assign_fixed(
+                    || "min constant",
+                    self.config.fixed_col,
+                    0,
+                    || Value::known(min),
+                )?;
+                let diff_val = v.value().copied().map(|v| v - min);
+                region.assign_advice(|| "value - min", self.config.diff_col, 0, || diff_val)
+            },
+        )?;
+
+        // max_minus_value = max - value (constrained by "lookup bounded diff reverse" gate)
+        let max_minus_value = layouter.assign_region(
+            || "compute max - value",
+            |mut region| {
+                self.config.bounded_diff_reverse_selector.enable(&mut region, 0)?;
+                let v = value.copy_advice(|| "value", &mut region, self.config.value_col, 0)?;
+                region.assign_fixed(
+                    || "max constant",
+                    self.config.fixed_col,
+                    0,
+                    || Value::known(max),
+                )?;
+                let diff_val = v.value().copied().map(|v| max - v);
+                region.assign_advice(|| "max - value", self.config.diff_col, 0, || diff_val)
+            },
+        )?;
+
+        self.check_range(
+            layouter.namespace(|| "range check value - min"),
+            value_minus_min,
+            num_bits,
+        )?;
+
+        self.check_range(
+            layouter.namespace(|| "range check max - value"),
+            max_minus_value,
+            num_bits,
+        )
+    }
+
+    /// Loads a value into an advice cell.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error`] if the assignment fails.
+    pub fn load_value(
+        &self,
+        mut layouter: impl Layouter<Fp>,
+        value: Value<Fp>,
+    ) -> Result<AssignedCell<Fp, Fp>, Error> {
+        layouter.assign_region(
+            || "load range value",
+            |mut region| region.assign_advice(|| "range value", self.config.value_col, 0, || value),
+        )
+    }
+
+    /// Returns a reference to the chip configuration.
+    #[must_use]
+    pub fn config(&self) -> &LookupRangeConfig {
+        &self.config
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use halo2_proofs::{
+        arithmetic::Field,
+        circuit::SimpleFloorPlanner,
+        dev::{MockProver, VerifyFailure},
+        plonk::Circuit,
+    };
+
+    #[derive(Clone)]
+    struct LookupRangeTestCircuit {
+        value: Value<Fp>,
+        num_bits: usize,
+    }
+
+    impl Circuit<Fp> for LookupRangeTestCircuit {
+        type Config = LookupRangeConfig;
+        type FloorPlanner = SimpleFloorPlanner;
+
+        fn without_witnesses(&self) -> Self {
+            Self { value: Value::unknown(), num_bits: self.num_bits }
+        }
+
+        fn configure(meta: &mut ConstraintSystem<Fp>) -> LookupRangeConfig {
+            LookupRangeChip::configure(meta)
+        }
+
+        fn synthesize(
+            &self,
+            config: LookupRangeConfig,
+            mut layouter: impl Layouter<Fp>,
+        ) -> Result<(), Error> {
+            let chip = LookupRangeChip::construct(config);
+            chip.load_table(layouter.namespace(|| "load table"))?;
+            let value_cell = chip.load_value(layouter.namespace(|| "load value"), self.value)?;
+            chip.check_range(layouter.namespace(|| "range check"), value_cell, self.num_bits)
+        }
+    }
+
+    #[derive(Clone)]
+    struct BoundedLookupRangeTestCircuit {
+        value: Value<Fp>,
+        min: Fp,
+        max: Fp,
+        num_bits: usize,
+    }
+
+    impl Circuit<Fp> for BoundedLookupRangeTestCircuit {
+        type Config = LookupRangeConfig;
+        type FloorPlanner = SimpleFloorPlanner;
+
+        fn without_witnesses(&self) -> Self {
+            Self { value: Value::unknown(), min: self.min, max: self.max, num_bits: self.num_bits }
+        }
+
+        fn configure(meta: &mut ConstraintSystem<Fp>) -> LookupRangeConfig {
+            LookupRangeChip::configure(meta)
+        }
+
+        fn synthesize(
+            &self,
+            config: LookupRangeConfig,
+            mut layouter: impl Layouter<Fp>,
+        ) -> Result<(), Error> {
+            let chip = LookupRangeChip::construct(config);
+            chip.load_table(layouter.namespace(|| "load table"))?;
+            let value_cell = chip.load_value(layouter.namespace(|| "load value"), self.value)?;
+            chip.check_range_bounded(
+                layouter.namespace(|| "bounded range check"),
+                value_cell,
+                self.min,
+                self.max,
+                self.num_bits,
+            )
+        }
+    }
+
+    fn verify(value: Fp, num_bits: usize) -> Result<(), Vec<VerifyFailure>> {
+        let circuit = LookupRangeTestCircuit { value: Value::known(value), num_bits };
+        MockProver::run(9, &circuit, vec![]).unwrap().verify()
+    }
+
+    fn verify_bounded(value: u64, min: u64, max: u64) -> Result<(), Vec<VerifyFailure>> {
+        let circuit = BoundedLookupRangeTestCircuit {
+            value: Value::known(Fp::from(value)),
+            min: Fp::from(min),
+            max: Fp::from(max),
+            num_bits: 16,
+        };
+        MockProver::run(9, &circuit, vec![]).unwrap().verify()
+    }
+
+    #[test]
+    fn test_lookup_range_full_limbs() {
+        assert_eq!(verify(Fp::from(255u64), 8), Ok(()));
+        assert_eq!(verify(Fp::zero(), 8), Ok(()));
+        assert_eq!(verify(Fp::from(u64::MAX), 64), Ok(()));
+        assert_eq!(verify(Fp::from_u128(u128::MAX), 128), Ok(()));
+    }
+
+    #[test]
+    fn test_lookup_range_overflow_is_lookup_failure() {
+        let failures = verify(Fp::from(256u64), 8).unwrap_err();
+        assert!(failures.iter().all(|f| matches!(f, VerifyFailure::Lookup { .. })));
+
+        let failures = verify(Fp::from_u128(u128::from(u64::MAX) + 1), 64).unwrap_err();
+        assert!(failures.iter().all(|f| matches!(f, VerifyFailure::Lookup { .. })));
+    }
+
+    #[test]
+    fn test_lookup_range_short_top_limb() {
+        assert_eq!(verify(Fp::from(1u64), 1), Ok(()));
+        assert!(verify(Fp::from(2u64), 1).is_err());
+        assert_eq!(verify(Fp::from((1u64 << 20) - 1), 20), Ok(()));
+        assert!(verify(Fp::from(1u64 << 20), 20).is_err());
+    }
+
+    #[test]
+    fn test_lookup_range_254bit() {
+        let value = Fp::from(2u64).pow_vartime([253]) + Fp::from(5u64);
+        assert_eq!(verify(value, 254), Ok(()));
+        // p - 1 = -1 needs 255 bits
+        assert!(verify(-Fp::one(), 254).is_err());
+    }
+
+    #[test]
+    fn test_lookup_range_rejects_bad_widths() {
+        for num_bits in [0, LookupRangeChip::MAX_BITS + 1] {
+            let circuit = LookupRangeTestCircuit { value: Value::known(Fp::from(1u64)), num_bits };
+            assert!(MockProver::run(9, &circuit, vec![]).is_err());
+        }
+    }
+
+    #[test]
+    fn test_lookup_bounded_range() {
+        assert_eq!(verify_bounded(150, 150, 300), Ok(()));
+        assert_eq!(verify_bounded(200, 150, 300), Ok(()));
+        assert_eq!(verify_bounded(300, 150, 300), Ok(()));
+        assert!(verify_bounded(149, 150, 300).is_err());
+        assert!(verify_bounded(301, 150, 300).is_err());
+    }
+
+    #[test]
+    fn test_rows_per_check() {
+        let circuit = LookupRangeTestCircuit { value: Value::unknown(), num_bits: 64 };
+        let report = crate::measure_circuit_rows("lookup range", &circuit, |_| vec![]).unwrap();
+        assert!(report.rows_by_region().contains(&("lookup range check 64 bits".to_string(), 8)));
+        // The table sets the height
+        assert_eq!(report.used_rows, 1 << LookupRangeChip::LIMB_BITS);
+    }
+}
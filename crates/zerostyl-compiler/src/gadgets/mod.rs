@@ -4,18 +4,29 @@
 //!
 //! - [`PoseidonCommitmentChip`] — Poseidon hash commitment: `commitment = Poseidon(value, randomness)`
 //! - [`MerkleTreeChip`] — Poseidon-based Merkle tree membership verification (depth up to 64)
-//! - [`RangeProofChip`] — Bit-decomposition range proof (8/16/32/64 bits)
-//! - [`ComparisonChip`] — Ordering proofs (`>`, `>=`, `<`, `<=`) via range-checked differences
+//! - [`RangeProofChip`] — Bit-decomposition range proof (up to 254 bits, in 64-bit limbs)
+//! - [`LookupRangeChip`] — Same range proofs with 8-bit limbs looked up in a fixed table
+//! - [`ComparisonChip`] — Ordering proofs (`>`, `>=`, `<`, `<=`) via range-checked differences,
+//!   plus `==` / `!=`
+//! - [`ArithmeticChip`] — Binary relations `out = lhs ∘ rhs` for `+`, `-`, `*`, `/`
+//!   (non-zero divisor)
+//! - [`SignedChip`] — Offset encoding (`v + 2^63`) for signed `i64` values
 //!
 //! All gadgets use the Pasta Fp field and the P128Pow5T3 Poseidon specification
 //! (128-bit security, x^5 S-box, width=3, rate=2).
 
+pub mod arithmetic;
 pub mod comparison;
+pub mod lookup_range;
 pub mod merkle;
 pub mod poseidon_commitment;
 pub mod range;
+pub mod signed;
 
+pub use arithmetic::{ArithmeticChip, ArithmeticConfig};
 pub use comparison::{ComparisonChip, ComparisonConfig};
+pub use lookup_range::{LookupRangeChip, LookupRangeConfig};
 pub use merkle::{MerkleTreeChip, MerkleTreeConfig};
 pub use poseidon_commitment::{PoseidonCommitmentChip, PoseidonCommitmentConfig};
 pub use range::{RangeProofChip, RangeProofConfig};
+pub use signed::{SignedChip, SignedConfig};
@@ -4,6 +4,10 @@
 //! from `halo2_gadgets`. This is the standard approach used by Zcash Orchard, Semaphore,
 //! and Tornado Cash Nova for privacy-preserving commitments in ZK circuits.
 //!
+//! Commitments that bind more data (e.g. owner, asset id, amount and randomness) use
+//! [`PoseidonCommitmentChip::hash_n`], which hashes any number of cells with the same
+//! sponge, encoding the input length in the capacity element for domain separation.
+//!
 //! # Column requirements
 //!
 //! The Poseidon P128Pow5T3 chip requires:
@@ -21,17 +25,26 @@
 //! let value = Fp::from(100u64);
 //! let randomness = Fp::from(42u64);
 //! let commitment = PoseidonCommitmentChip::hash_outside_circuit(value, randomness);
+//!
+//! // Two-input hash_n is the same hash
+//! assert_eq!(PoseidonCommitmentChip::hash_n_outside_circuit(&[value, randomness]), commitment);
 //! ```
 
 use halo2_gadgets::poseidon::{
-    primitives::{self as poseidon, ConstantLength, P128Pow5T3},
-    Hash, Pow5Chip, Pow5Config,
+    primitives::{self as poseidon, Absorbing, ConstantLength, Domain, Mds, P128Pow5T3, Spec},
+    Hash, PaddedWord, PoseidonInstructions, PoseidonSpongeInstructions, Pow5Chip, Pow5Config,
+    StateWord,
 };
 use halo2_proofs::{
     circuit::{AssignedCell, Layouter, Value},
     pasta::Fp,
     plonk::{Advice, Column, ConstraintSystem, Error, Fixed},
 };
+use halo2curves::ff::PrimeField;
+
+/// Sponge width and rate of P128Pow5T3.
+const WIDTH: usize = 3;
+const RATE: usize = 2;
 
 /// Configuration for the Poseidon commitment chip.
 ///
@@ -41,6 +54,7 @@ use halo2_proofs::{
 pub struct PoseidonCommitmentConfig {
     pow5_config: Pow5Config<Fp, 3, 2>,
     state: [Column<Advice>; 3],
+    constants: Column<Fixed>,
 }
 
 impl PoseidonCommitmentConfig {
@@ -53,6 +67,12 @@ impl PoseidonCommitmentConfig {
         &self.state
     }
 
+    /// Returns the fixed column enabled for global constants.
+    #[must_use]
+    pub fn constants_column(&self) -> Column<Fixed> {
+        self.constants
+    }
+
     /// Returns a reference to the underlying Pow5 config.
     #[must_use]
     pub fn pow5_config(&self) -> &Pow5Config<Fp, 3, 2> {
@@ -87,7 +107,7 @@ impl PoseidonCommitmentChip {
 
         let pow5_config = Pow5Chip::configure::<P128Pow5T3>(meta, state, partial_sbox, rc_a, rc_b);
 
-        PoseidonCommitmentConfig { pow5_config, state }
+        PoseidonCommitmentConfig { pow5_config, state, constants: rc_b[0] }
     }
 
     /// Constructs the chip from a previously created configuration.
@@ -144,6 +164,75 @@ impl PoseidonCommitmentChip {
         hasher.hash(layouter.namespace(|| "poseidon_hash"), [left, right])
     }
 
+    /// Computes the Poseidon hash of `inputs` inside the circuit.
+    ///
+    /// The inputs are zero-padded to a multiple of the rate and absorbed two at a time,
+    /// starting from a capacity element of `inputs.len() * 2^64`. This is the
+    /// `ConstantLength` domain of the Poseidon paper with the length fixed at synthesis
+    /// time, so hashes of different arities never collide and `hash_n` over two cells
+    /// equals [`hash_two`](Self::hash_two). Use [`Self::hash_n_outside_circuit`] for
+    /// witness generation.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Synthesis`] if `inputs` is empty, or [`Error`] if the Poseidon chip
+    /// fails during synthesis.
+    pub fn hash_n(
+        &self,
+        mut layouter: impl Layouter<Fp>,
+        inputs: &[AssignedCell<Fp, Fp>],
+    ) -> Result<AssignedCell<Fp, Fp>, Error> {
+        if inputs.is_empty() {
+            return Err(Error::Synthesis);
+        }
+        let chip = Pow5Chip::construct(self.config.pow5_config.clone());
+
+        let mut state: [StateWord<Fp>; WIDTH] = layouter.assign_region(
+            || format!("initial state for hash_n({})", inputs.len()),
+            |mut region| {
+                let mut load = |i: usize, value: Fp| {
+                    region
+                        .assign_advice_from_constant(
+                            || format!("state_{}", i),
+                            self.config.state[i],
+                            0,
+                            value,
+                        )
+                        .map(StateWord::from)
+                };
+                Ok([load(0, Fp::zero())?, load(1, Fp::zero())?, load(2, capacity(inputs.len()))?])
+            },
+        )?;
+
+        let padding = (RATE - inputs.len() % RATE) % RATE;
+        let words: Vec<PaddedWord<Fp>> = inputs
+            .iter()
+            .cloned()
+            .map(PaddedWord::Message)
+            .chain(std::iter::repeat_n(PaddedWord::Padding(Fp::zero()), padding))
+            .collect();
+        for (i, block) in words.chunks(RATE).enumerate() {
+            let mut input = Absorbing::init_empty();
+            for word in block {
+                input.absorb(word.clone()).expect("block fits in the rate");
+            }
+            state = PoseidonSpongeInstructions::<Fp, P128Pow5T3, HashN, WIDTH, RATE>::add_input(
+                &chip,
+                &mut layouter.namespace(|| format!("absorb block {}", i)),
+                &state,
+                &input,
+            )?;
+            state = PoseidonInstructions::<Fp, P128Pow5T3, WIDTH, RATE>::permute(
+                &chip,
+                &mut layouter.namespace(|| format!("permute block {}", i)),
+                &state,
+            )?;
+        }
+
+        let [output, _, _] = state;
+        Ok(output.into())
+    }
+
     /// Loads a private value into an advice cell.
     ///
     /// Uses `state[column_idx]` for assignment. `column_idx` must be 0, 1, or 2.
@@ -183,6 +272,81 @@ impl PoseidonCommitmentChip {
     pub fn hash_outside_circuit(value: Fp, randomness: Fp) -> Fp {
         poseidon::Hash::<_, P128Pow5T3, ConstantLength<2>, 3, 2>::init().hash([value, randomness])
     }
+
+    /// Computes [`hash_n`](Self::hash_n) of `inputs` outside the circuit.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `inputs` is empty.
+    #[must_use]
+    pub fn hash_n_outside_circuit(inputs: &[Fp]) -> Fp {
+        assert!(!inputs.is_empty(), "hash_n needs at least one input");
+        let (round_constants, mds, _) = <P128Pow5T3 as Spec<Fp, WIDTH, RATE>>::constants();
+
+        let mut state = [Fp::zero(), Fp::zero(), capacity(inputs.len())];
+        for block in inputs.chunks(RATE) {
+            // Padding words are zero and leave the state unchanged
+            for (word, input) in state.iter_mut().zip(block) {
+                *word += input;
+            }
+            permute(&mut state, &mds, &round_constants);
+        }
+        state[0]
+    }
+}
+
+/// Capacity element encoding an input length, as in Poseidon's `ConstantLength` domain.
+fn capacity(len: usize) -> Fp {
+    Fp::from_u128((len as u128) << 64)
+}
+
+/// Domain of [`PoseidonCommitmentChip::hash_n`] sponges, naming their regions.
+///
+/// The capacity element depends on the input length, so `hash_n` loads the initial state
+/// itself instead of asking the domain for it.
+#[derive(Debug)]
+struct HashN;
+
+impl Domain<Fp, RATE> for HashN {
+    type Padding = std::iter::Empty<Fp>;
+
+    fn name() -> String {
+        "HashN".to_string()
+    }
+
+    fn initial_capacity_element() -> Fp {
+        unreachable!("hash_n loads the length-dependent capacity element itself")
+    }
+
+    fn padding(_input_len: usize) -> Self::Padding {
+        std::iter::empty()
+    }
+}
+
+/// The P128Pow5T3 permutation, as `halo2_gadgets` computes it outside circuits (its own
+/// implementation is private).
+fn permute(state: &mut [Fp; WIDTH], mds: &Mds<Fp, WIDTH>, round_constants: &[[Fp; WIDTH]]) {
+    let half_full_rounds = <P128Pow5T3 as Spec<Fp, WIDTH, RATE>>::full_rounds() / 2;
+    let partial_rounds = <P128Pow5T3 as Spec<Fp, WIDTH, RATE>>::partial_rounds();
+    let sbox = <P128Pow5T3 as Spec<Fp, WIDTH, RATE>>::sbox;
+
+    for (round, rcs) in round_constants.iter().enumerate() {
+        for (word, rc) in state.iter_mut().zip(rcs) {
+            *word += rc;
+        }
+        let partial = (half_full_rounds..half_full_rounds + partial_rounds).contains(&round);
+        if partial {
+            state[0] = sbox(state[0]);
+        } else {
+            state.iter_mut().for_each(|word| *word = sbox(*word));
+        }
+
+        let mut mixed = [Fp::zero(); WIDTH];
+        for (out, row) in mixed.iter_mut().zip(mds) {
+            *out = row.iter().zip(state.iter()).map(|(m, word)| *m * word).sum();
+        }
+        *state = mixed;
+    }
 }
 
 #[cfg(test)]
@@ -368,6 +532,119 @@ mod tests {
         assert!(result.is_err(), "load_private(column_idx=3) must return Err, not panic");
     }
 
+    /// Test circuit that exposes `hash_n(inputs)` as a public input.
+    #[derive(Clone)]
+    struct HashNTestCircuit {
+        inputs: Vec<Value<Fp>>,
+    }
+
+    impl Circuit<Fp> for HashNTestCircuit {
+        type Config = CommitmentTestConfig;
+        type FloorPlanner = SimpleFloorPlanner;
+
+        fn without_witnesses(&self) -> Self {
+            Self { inputs: vec![Value::unknown(); self.inputs.len()] }
+        }
+
+        fn configure(meta: &mut ConstraintSystem<Fp>) -> CommitmentTestConfig {
+            CommitmentTestCircuit::configure(meta)
+        }
+
+        fn synthesize(
+            &self,
+            config: CommitmentTestConfig,
+            mut layouter: impl Layouter<Fp>,
+        ) -> Result<(), Error> {
+            let chip = PoseidonCommitmentChip::construct(config.poseidon);
+            let cells = self
+                .inputs
+                .iter()
+                .enumerate()
+                .map(|(i, value)| {
+                    chip.load_private(layouter.namespace(|| "load input"), *value, i % 3)
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+
+            let hash = chip.hash_n(layouter.namespace(|| "hash_n"), &cells)?;
+            layouter.constrain_instance(hash.cell(), config.instance, 0)
+        }
+    }
+
+    fn fps(values: &[u64]) -> Vec<Fp> {
+        values.iter().map(|v| Fp::from(*v)).collect()
+    }
+
+    fn hash_n_circuit(inputs: &[Fp]) -> HashNTestCircuit {
+        HashNTestCircuit { inputs: inputs.iter().map(|v| Value::known(*v)).collect() }
+    }
+
+    #[test]
+    fn test_hash_n_in_circuit_matches_outside() {
+        // Owner, asset id, amount, randomness; then an odd arity that needs padding
+        for inputs in [fps(&[0xa11ce, 7, 1_000, 42]), fps(&[1, 2, 3])] {
+            let expected = PoseidonCommitmentChip::hash_n_outside_circuit(&inputs);
+            let prover =
+                MockProver::run(8, &hash_n_circuit(&inputs), vec![vec![expected]]).unwrap();
+            prover.assert_satisfied();
+        }
+    }
+
+    #[test]
+    fn test_hash_n_wrong_hash_rejected() {
+        let inputs = fps(&[1, 2, 3, 4]);
+        let wrong = PoseidonCommitmentChip::hash_n_outside_circuit(&fps(&[1, 2, 3, 5]));
+        let prover = MockProver::run(8, &hash_n_circuit(&inputs), vec![vec![wrong]]).unwrap();
+        assert!(prover.verify().is_err());
+    }
+
+    #[test]
+    fn test_hash_n_empty_input_returns_error() {
+        let result = MockProver::run(8, &hash_n_circuit(&[]), vec![vec![]]);
+        assert!(result.is_err(), "hash_n(&[]) must return Err, not panic");
+    }
+
+    #[test]
+    fn test_hash_n_matches_constant_length_hash() {
+        fn constant_length<const L: usize>(inputs: [Fp; L]) -> Fp {
+            poseidon::Hash::<_, P128Pow5T3, ConstantLength<L>, 3, 2>::init().hash(inputs)
+        }
+        let [a, b, c, d, e] = [3, 5, 7, 11, 13].map(Fp::from);
+        let hash_n = PoseidonCommitmentChip::hash_n_outside_circuit;
+
+        assert_eq!(hash_n(&[a]), constant_length([a]));
+        assert_eq!(hash_n(&[a, b]), constant_length([a, b]));
+        assert_eq!(hash_n(&[a, b]), PoseidonCommitmentChip::hash_outside_circuit(a, b));
+        assert_eq!(hash_n(&[a, b, c]), constant_length([a, b, c]));
+        assert_eq!(hash_n(&[a, b, c, d]), constant_length([a, b, c, d]));
+        assert_eq!(hash_n(&[a, b, c, d, e]), constant_length([a, b, c, d, e]));
+    }
+
+    #[test]
+    fn test_hash_n_separates_arities() {
+        let hash_n = PoseidonCommitmentChip::hash_n_outside_circuit;
+        // Zero padding alone would make these absorb the same blocks
+        assert_ne!(hash_n(&fps(&[1])), hash_n(&fps(&[1, 0])));
+        assert_ne!(hash_n(&fps(&[1, 2, 3])), hash_n(&fps(&[1, 2, 3, 0])));
+    }
+
+    #[test]
+    fn test_hash_n_golden_vectors() {
+        // Little-endian field element encodings
+        let vectors: [(&[u64], &str); 4] = [
+            (&[0], "1be5fca308655c973b949d15fe8374a773b5db31f76290d17c2d4f92a3c0a100"),
+            (&[1, 2], "4ce3bd9407dc758983c62390ce00463beb82796eb0d40a0398993cb4eca55535"),
+            (&[1, 2, 3], "eafce114beaa82503698d4acc618788b648cc6409822265f9e3eea1ec642ab18"),
+            (
+                &[1, 2, 3, 4, 5, 6, 7, 8],
+                "8646cfb3c8aac5be6ca96d10d2506c2c9f99d8320b138b7a97e0e2be8dba0e0a",
+            ),
+        ];
+        for (inputs, expected) in vectors {
+            let hash = PoseidonCommitmentChip::hash_n_outside_circuit(&fps(inputs));
+            assert_eq!(hex::encode(hash.to_repr()), expected, "hash_n({:?})", inputs);
+        }
+    }
+
     #[test]
     fn test_hash_two_same_as_commit() {
         let a = Fp::from(10u64);
@@ -1,17 +1,27 @@
 //! Range proof chip via bit decomposition.
 //!
 //! Proves that a value lies within `[0, 2^num_bits)` by decomposing it into individual bits
-//! and constraining each bit to be boolean. Supports configurable bit widths: 8, 16, 32, 64.
+//! and constraining each bit to be boolean. Supports any bit width from 1 to
+//! [`RangeProofChip::MAX_BITS`].
 //!
 //! # How it works
 //!
 //! For a value `v` with `num_bits = N`:
 //! 1. Decompose `v` into bits `b_0, b_1, ..., b_{N-1}`
 //! 2. Constrain each bit: `b_i * (1 - b_i) = 0` (boolean)
-//! 3. Constrain reconstruction: `sum(b_i * 2^i) = v`
+//! 3. Constrain reconstruction: `sum(b_i * 2^i) = v`, accumulated MSB first from a running
+//!    sum whose first row is constrained to zero
+//!
+//! Widths above [`RangeProofChip::LIMB_BITS`] are checked limb by limb: `v` is split into
+//! 64-bit limbs `l_0, ..., l_{L-1}`, each limb is bit-decomposed as above (the top limb only
+//! gets the remaining `N - 64 * (L - 1)` bits), and `sum(l_j * 2^(64 j)) = v` is enforced
+//! the same way. Because `2^254 < p`, a decomposition of up to 254 bits can never alias a
+//! different value.
 //!
 //! For bounded range `[min, max]`:
 //! - Prove `v - min ∈ [0, 2^N)` AND `max - v ∈ [0, 2^N)`
+//! - Sound as long as `max - min < 2^N` and `N <= MAX_BOUNDED_BITS`, so that a value outside
+//!   the range cannot wrap around the field modulus into both windows
 
 use halo2_proofs::{
     circuit::{AssignedCell, Layouter, Value},
@@ -27,7 +37,9 @@ pub struct RangeProofConfig {
     value_col: Column<Advice>,
     bits_col: Column<Advice>,
     bool_selector: Selector,
+    acc_init_selector: Selector,
     recompose_selector: Selector,
+    limb_recompose_selector: Selector,
     bounded_diff_selector: Selector,
     bounded_diff_reverse_selector: Selector,
     fixed_col: Column<Fixed>,
@@ -39,9 +51,22 @@ pub struct RangeProofChip {
 }
 
 impl RangeProofChip {
+    /// Width of a single limb in a multi-limb range check.
+    pub const LIMB_BITS: usize = 64;
+
+    /// Largest width accepted by [`Self::check_range`] (`2^254 < p` for the Pasta base field).
+    pub const MAX_BITS: usize = 254;
+
+    /// Largest width accepted by [`Self::check_range_bounded`] and ordering comparisons.
+    ///
+    /// One bit below [`Self::MAX_BITS`] so that `2^(N+1) <= p`, which keeps the two
+    /// difference checks from being satisfied by a wrapped-around value.
+    pub const MAX_BOUNDED_BITS: usize = 253;
+
     /// Configures the range proof chip.
     ///
-    /// Allocates 2 advice columns and 2 selectors for boolean and recomposition gates.
+    /// Allocates 2 advice columns, 1 fixed column and selectors for the boolean, accumulator
+    /// init, bit and limb recomposition, and bounded difference gates.
     pub fn configure(meta: &mut ConstraintSystem<Fp>) -> RangeProofConfig {
         let value_col = meta.advice_column();
         let bits_col = meta.advice_column();
@@ -49,7 +74,9 @@ impl RangeProofChip {
         meta.enable_equality(bits_col);
 
         let bool_selector = meta.selector();
+        let acc_init_selector = meta.selector();
         let recompose_selector = meta.selector();
+        let limb_recompose_selector = meta.selector();
         let bounded_diff_selector = meta.selector();
         let bounded_diff_reverse_selector = meta.selector();
         let fixed_col = meta.fixed_column();
@@ -61,6 +88,14 @@ impl RangeProofChip {
             vec![s * (bit.clone() * (halo2_proofs::plonk::Expression::Constant(Fp::one()) - bit))]
         });
 
+        // Accumulator init: acc == 0 on the first row of a recomposition.
+        // Without it the prover could start from any value and "recompose" anything.
+        meta.create_gate("range acc init", |meta| {
+            let s = meta.query_selector(acc_init_selector);
+            let acc = meta.query_advice(value_col, Rotation::cur());
+            vec![s * acc]
+        });
+
         // Recomposition constraint: accumulated - value == 0
         // We use value_col[cur] for the running accumulator and bits_col[cur] for the current bit.
         // accumulated_new = accumulated_old * 2 + bit
@@ -77,6 +112,17 @@ impl RangeProofChip {
             ]
         });
 
+        // Limb recomposition: acc_next = acc_prev * 2^64 + limb
+        // Same layout as the bit recomposition, one 64-bit limb per row.
+        meta.create_gate("range limb recompose", |meta| {
+            let s = meta.query_selector(limb_recompose_selector);
+            let acc_prev = meta.query_advice(value_col, Rotation::cur());
+            let acc_next = meta.query_advice(value_col, Rotation::next());
+            let limb = meta.query_advice(bits_col, Rotation::cur());
+            let shift = halo2_proofs::plonk::Expression::Constant(Fp::from_u128(1u128 << 64));
+            vec![s * (acc_next - (acc_prev * shift + limb))]
+        });
+
         // Bounded diff constraint: value - constant - diff == 0
         // Used for: diff = value - min (proving value >= min)
         meta.create_gate("bounded diff", |meta| {
@@ -101,7 +147,9 @@ impl RangeProofChip {
             value_col,
             bits_col,
             bool_selector,
+            acc_init_selector,
             recompose_selector,
+            limb_recompose_selector,
             bounded_diff_selector,
             bounded_diff_reverse_selector,
             fixed_col,
@@ -117,25 +165,30 @@ impl RangeProofChip {
     /// Proves that `value ∈ [0, 2^num_bits)`.
     ///
     /// Decomposes the value into `num_bits` bits (MSB first), constrains each to be boolean,
-    /// and constrains the recomposition to equal the original value.
+    /// and constrains the recomposition to equal the original value. Widths above
+    /// [`Self::LIMB_BITS`] are split into 64-bit limbs that are checked individually.
     ///
     /// # Arguments
     ///
     /// * `value` — The value to range-check (already assigned)
-    /// * `num_bits` — Number of bits (8, 16, 32, or 64)
+    /// * `num_bits` — Number of bits, from 1 to [`Self::MAX_BITS`]
     ///
     /// # Errors
     ///
-    /// Returns [`Error`] if synthesis fails.
+    /// Returns [`Error::Synthesis`] if `num_bits` is out of bounds, or [`Error`] if
+    /// synthesis fails.
     pub fn check_range(
         &self,
         mut layouter: impl Layouter<Fp>,
         value: AssignedCell<Fp, Fp>,
         num_bits: usize,
     ) -> Result<(), Error> {
-        if num_bits == 0 || num_bits > 64 {
+        if num_bits == 0 || num_bits > Self::MAX_BITS {
             return Err(Error::Synthesis);
         }
+        if num_bits > Self::LIMB_BITS {
+            return self.check_range_limbs(layouter, value, num_bits);
+        }
         layouter.assign_region(
             || format!("range check {} bits", num_bits),
             |mut region| {
@@ -143,6 +196,7 @@ impl RangeProofChip {
                 let value_fp = value.value().copied();
 
                 // Row 0: initial accumulator = 0
+                self.config.acc_init_selector.enable(&mut region, 0)?;
                 region.assign_advice(
                     || "acc init",
                     self.config.value_col,
@@ -208,6 +262,90 @@ impl RangeProofChip {
         )
     }
 
+    /// Multi-limb range check for `num_bits > LIMB_BITS`.
+    ///
+    /// Recomposes the value from 64-bit limbs (MSB limb first) and range-checks each limb,
+    /// giving the top limb only the bits left over.
+    fn check_range_limbs(
+        &self,
+        mut layouter: impl Layouter<Fp>,
+        value: AssignedCell<Fp, Fp>,
+        num_bits: usize,
+    ) -> Result<(), Error> {
+        let num_limbs = num_bits.div_ceil(Self::LIMB_BITS);
+        let shift = Fp::from_u128(1u128 << Self::LIMB_BITS);
+
+        let limb_cells = layouter.assign_region(
+            || format!("range limbs {} bits", num_bits),
+            |mut region| {
+                let value_fp = value.value().copied();
+
+                self.config.acc_init_selector.enable(&mut region, 0)?;
+                region.assign_advice(
+                    || "limb acc init",
+                    self.config.value_col,
+                    0,
+                    || Value::known(Fp::zero()),
+                )?;
+
+                // Limbs are read little-endian from the canonical repr and assigned MSB first
+                let limbs: Vec<Value<Fp>> = (0..num_limbs)
+                    .rev()
+                    .map(|j| {
+                        value_fp.map(|v| {
+                            let repr = v.to_repr();
+                            let mut bytes = [0u8; 8];
+                            bytes.copy_from_slice(&repr.as_ref()[j * 8..j * 8 + 8]);
+                            Fp::from(u64::from_le_bytes(bytes))
+                        })
+                    })
+                    .collect();
+
+                let mut acc = Value::known(Fp::zero());
+                let mut last_acc_cell = None;
+                let mut limb_cells = Vec::with_capacity(num_limbs);
+                for (row, limb_val) in limbs.iter().enumerate() {
+                    self.config.limb_recompose_selector.enable(&mut region, row)?;
+
+                    limb_cells.push(region.assign_advice(
+                        || format!("limb {}", row),
+                        self.config.bits_col,
+                        row,
+                        || *limb_val,
+                    )?);
+
+                    acc = acc.zip(*limb_val).map(|(a, l)| a * shift + l);
+
+                    last_acc_cell = Some(region.assign_advice(
+                        || format!("limb acc {}", row + 1),
+                        self.config.value_col,
+                        row + 1,
+                        || acc,
+                    )?);
+                }
+
+                let value_copy = value.copy_advice(
+                    || "original value",
+                    &mut region,
+                    self.config.bits_col,
+                    num_limbs,
+                )?;
+
+                region.constrain_equal(last_acc_cell.unwrap().cell(), value_copy.cell())?;
+
+                Ok(limb_cells)
+            },
+        )?;
+
+        let top_bits = num_bits - Self::LIMB_BITS * (num_limbs - 1);
+        for (i, limb) in limb_cells.into_iter().enumerate() {
+            let bits = if i == 0 { top_bits } else { Self::LIMB_BITS };
+            self.check_range(layouter.namespace(|| format!("limb {} range", i)), limb, bits)?;
+        }
+
+        Ok(())
+    }
+
     /// Proves that `value ∈ [min, max]`.
     ///
     /// Works by proving:
@@ -221,11 +359,12 @@ impl RangeProofChip {
     /// * `value` — The value to bound-check (already assigned)
     /// * `min` — Minimum bound (inclusive)
     /// * `max` — Maximum bound (inclusive)
-    /// * `num_bits` — Number of bits for the range check
+    /// * `num_bits` — Number of bits for the range check, at most [`Self::MAX_BOUNDED_BITS`]
     ///
     /// # Errors
     ///
-    /// Returns [`Error`] if synthesis fails.
+    /// Returns [`Error::Synthesis`] if `num_bits` is out of bounds, or [`Error`] if
+    /// synthesis fails.
     pub fn check_range_bounded(
         &self,
         mut layouter: impl Layouter<Fp>,
@@ -234,6 +373,9 @@ impl RangeProofChip {
         max: Fp,
         num_bits: usize,
     ) -> Result<(), Error> {
+        if num_bits == 0 || num_bits > Self::MAX_BOUNDED_BITS {
+            return Err(Error::Synthesis);
+        }
         // value_minus_min = value - min (constrained by "bounded diff" gate)
         let value_minus_min = layouter.assign_region(
             || "compute value - min",
@@ -310,7 +452,9 @@ impl RangeProofChip {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use halo2_proofs::{circuit::SimpleFloorPlanner, dev::MockProver, plonk::Circuit};
+    use halo2_proofs::{
+        arithmetic::Field, circuit::SimpleFloorPlanner, dev::MockProver, plonk::Circuit,
+    };
 
     #[derive(Clone)]
     struct RangeTestCircuit {
@@ -378,6 +522,142 @@ mod tests {
         }
     }
 
+    /// Lays out a recomposition region like the chip does, but from a prover-chosen
+    /// accumulator start. Digits are bits, or 64-bit limbs when `limbs` is set.
+    #[derive(Clone)]
+    struct TamperedInitCircuit {
+        value: Fp,
+        acc_init: Fp,
+        digits: Vec<u64>,
+        limbs: bool,
+    }
+
+    impl Circuit<Fp> for TamperedInitCircuit {
+        type Config = RangeProofConfig;
+        type FloorPlanner = SimpleFloorPlanner;
+
+        fn without_witnesses(&self) -> Self {
+            self.clone()
+        }
+
+        fn configure(meta: &mut ConstraintSystem<Fp>) -> RangeProofConfig {
+            RangeProofChip::configure(meta)
+        }
+
+        fn synthesize(
+            &self,
+            config: RangeProofConfig,
+            mut layouter: impl Layouter<Fp>,
+        ) -> Result<(), Error> {
+            let chip = RangeProofChip::construct(config.clone());
+            let value =
+                chip.load_value(layouter.namespace(|| "load value"), Value::known(self.value))?;
+            let (base, recompose) = if self.limbs {
+                (Fp::from_u128(1u128 << 64), config.limb_recompose_selector)
+            } else {
+                (Fp::from(2u64), config.recompose_selector)
+            };
+
+            let digit_cells = layouter.assign_region(
+                || "tampered recompose",
+                |mut region| {
+                    config.acc_init_selector.enable(&mut region, 0)?;
+                    region.assign_advice(
+                        || "acc init",
+                        config.value_col,
+                        0,
+                        || Value::known(self.acc_init),
+                    )?;
+
+                    let mut acc = self.acc_init;
+                    let mut last_acc_cell = None;
+                    let mut digit_cells = Vec::new();
+                    for (row, &digit) in self.digits.iter().enumerate() {
+                        recompose.enable(&mut region, row)?;
+                        if !self.limbs {
+                            config.bool_selector.enable(&mut region, row)?;
+                        }
+                        digit_cells.push(region.assign_advice(
+                            || "digit",
+                            config.bits_col,
+                            row,
+                            || Value::known(Fp::from(digit)),
+                        )?);
+                        acc = acc * base + Fp::from(digit);
+                        last_acc_cell = Some(region.assign_advice(
+                            || "acc",
+                            config.value_col,
+                            row + 1,
+                            || Value::known(acc),
+                        )?);
+                    }
+
+                    let value_copy = value.copy_advice(
+                        || "original value",
+                        &mut region,
+                        config.bits_col,
+                        self.digits.len(),
+                    )?;
+                    region.constrain_equal(last_acc_cell.unwrap().cell(), value_copy.cell())?;
+                    Ok(digit_cells)
+                },
+            )?;
+
+            if self.limbs {
+                for limb in digit_cells {
+                    chip.check_range(layouter.namespace(|| "limb range"), limb, 64)?;
+                }
+            }
+            Ok(())
+        }
+    }
+
+    fn assert_acc_init_rejected(circuit: &TamperedInitCircuit) {
+        let failures = MockProver::run(10, circuit, vec![]).unwrap().verify().unwrap_err();
+        assert!(
+            failures.iter().any(|f| f.to_string().contains("range acc init")),
+            "expected the accumulator init gate to fail, got {:?}",
+            failures
+        );
+    }
+
+    #[test]
+    fn test_tampered_acc_init_rejected() {
+        // 5 in 8 bits, laid out honestly
+        let bits = vec![0, 0, 0, 0, 0, 1, 0, 1];
+        let honest = TamperedInitCircuit {
+            value: Fp::from(5u64),
+            acc_init: Fp::zero(),
+            digits: bits,
+            limbs: false,
+        };
+        MockProver::run(10, &honest, vec![]).unwrap().assert_satisfied();
+
+        // 261 = 1 * 2^8 + 5 would pass the 8-bit check if the accumulator could start at 1
+        let tampered =
+            TamperedInitCircuit { value: Fp::from(261u64), acc_init: Fp::one(), ..honest };
+        assert_acc_init_rejected(&tampered);
+    }
+
+    #[test]
+    fn test_tampered_limb_acc_init_rejected() {
+        let honest = TamperedInitCircuit {
+            value: Fp::from(7u64),
+            acc_init: Fp::zero(),
+            digits: vec![0, 7],
+            limbs: true,
+        };
+        MockProver::run(10, &honest, vec![]).unwrap().assert_satisfied();
+
+        // 2^128 + 7 would pass the 128-bit check if the accumulator could start at 1
+        let tampered = TamperedInitCircuit {
+            value: Fp::from_u128(1u128 << 127).double() + Fp::from(7u64),
+            acc_init: Fp::one(),
+            ..honest
+        };
+        assert_acc_init_rejected(&tampered);
+    }
+
     #[test]
     fn test_range_8bit_valid() {
         let circuit = RangeTestCircuit { value: Value::known(Fp::from(255u64)), num_bits: 8 };
@@ -512,9 +792,95 @@ mod tests {
 
     #[test]
     fn test_check_range_too_many_bits_returns_error() {
-        let circuit = RangeTestCircuit { value: Value::known(Fp::from(42u64)), num_bits: 65 };
+        let circuit = RangeTestCircuit { value: Value::known(Fp::from(42u64)), num_bits: 255 };
         let k = 10;
         let result = MockProver::run(k, &circuit, vec![]);
-        assert!(result.is_err(), "check_range(65 bits) must return Err");
+        assert!(result.is_err(), "check_range(255 bits) must return Err");
+    }
+
+    #[test]
+    fn test_range_128bit_valid() {
+        let circuit =
+            RangeTestCircuit { value: Value::known(Fp::from_u128(u128::MAX)), num_bits: 128 };
+        let prover = MockProver::run(10, &circuit, vec![]).unwrap();
+        prover.assert_satisfied();
+    }
+
+    #[test]
+    fn test_range_128bit_overflow_rejected() {
+        let value = Fp::from_u128(u128::MAX) + Fp::one();
+        let circuit = RangeTestCircuit { value: Value::known(value), num_bits: 128 };
+        let prover = MockProver::run(10, &circuit, vec![]).unwrap();
+        assert!(prover.verify().is_err());
+    }
+
+    #[test]
+    fn test_range_uneven_limbs() {
+        let max_100 = Fp::from_u128((1u128 << 100) - 1);
+        let circuit = RangeTestCircuit { value: Value::known(max_100), num_bits: 100 };
+        let prover = MockProver::run(10, &circuit, vec![]).unwrap();
+        prover.assert_satisfied();
+
+        let circuit =
+            RangeTestCircuit { value: Value::known(Fp::from_u128(1u128 << 100)), num_bits: 100 };
+        let prover = MockProver::run(10, &circuit, vec![]).unwrap();
+        assert!(prover.verify().is_err());
+    }
+
+    #[test]
+    fn test_range_254bit_accepts_field_values_below_bound() {
+        // 2^253 + 5 fits in 254 bits
+        let value = Fp::from(2u64).pow_vartime([253]) + Fp::from(5u64);
+        let circuit = RangeTestCircuit { value: Value::known(value), num_bits: 254 };
+        let prover = MockProver::run(10, &circuit, vec![]).unwrap();
+        prover.assert_satisfied();
+    }
+
+    #[test]
+    fn test_range_254bit_rejects_large_field_element() {
+        // p - 1 = -1 needs 255 bits
+        let circuit = RangeTestCircuit { value: Value::known(-Fp::one()), num_bits: 254 };
+        let prover = MockProver::run(10, &circuit, vec![]).unwrap();
+        assert!(prover.verify().is_err());
+    }
+
+    #[test]
+    fn test_bounded_range_u128() {
+        let min = Fp::from_u128(u128::from(u64::MAX));
+        let max = Fp::from_u128(u128::MAX - 1);
+        let inside = BoundedRangeTestCircuit {
+            value: Value::known(Fp::from_u128(1u128 << 100)),
+            min,
+            max,
+            num_bits: 128,
+        };
+        MockProver::run(11, &inside, vec![]).unwrap().assert_satisfied();
+
+        let above = BoundedRangeTestCircuit {
+            value: Value::known(Fp::from_u128(u128::MAX)),
+            min,
+            max,
+            num_bits: 128,
+        };
+        assert!(MockProver::run(11, &above, vec![]).unwrap().verify().is_err());
+
+        let below = BoundedRangeTestCircuit {
+            value: Value::known(Fp::from(7u64)),
+            min,
+            max,
+            num_bits: 128,
+        };
+        assert!(MockProver::run(11, &below, vec![]).unwrap().verify().is_err());
+    }
+
+    #[test]
+    fn test_bounded_range_rejects_unsound_width() {
+        let circuit = BoundedRangeTestCircuit {
+            value: Value::known(Fp::from(1u64)),
+            min: Fp::zero(),
+            max: Fp::from(2u64),
+            num_bits: RangeProofChip::MAX_BITS,
+        };
+        assert!(MockProver::run(10, &circuit, vec![]).is_err());
     }
 }
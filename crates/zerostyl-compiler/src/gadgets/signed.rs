@@ -0,0 +1,206 @@
+//! Signed integer chip using offset encoding.
+//!
+//! A signed `i64` value `v` is stored in a witness cell as the unsigned encoding
+//! `e = v + 2^63 ∈ [0, 2^64)`. The encoding keeps the existing unsigned gadgets usable:
+//!
+//! - Range: `e ∈ [0, 2^64)` (a 64-bit [`RangeProofChip`] check) ⟺ `v ∈ [i64::MIN, i64::MAX]`
+//! - Comparison: the encoding is monotonic, so [`ComparisonChip`] ordering proofs on
+//!   encoded cells give signed ordering
+//! - Arithmetic: [`SignedChip::decode`] maps `e` to the field-native value `v mod p`
+//!   (`p - |v|` for negative `v`), on which [`ArithmeticChip`] relations hold with
+//!   integer semantics as long as intermediate results stay far below `p`
+//!
+//! Decoding is a single row `(encoded, value)` with the gate `encoded - value - 2^63 = 0`.
+//!
+//! [`RangeProofChip`]: super::RangeProofChip
+//! [`ComparisonChip`]: super::ComparisonChip
+//! [`ArithmeticChip`]: super::ArithmeticChip
+
+use crate::ast::encode_i64;
+use halo2_proofs::{
+    circuit::{AssignedCell, Layouter},
+    pasta::Fp,
+    plonk::{Advice, Column, ConstraintSystem, Error, Expression, Selector},
+    poly::Rotation,
+};
+
+/// Configuration for the signed integer chip.
+#[derive(Debug, Clone)]
+pub struct SignedConfig {
+    encoded_col: Column<Advice>,
+    value_col: Column<Advice>,
+    offset_selector: Selector,
+}
+
+/// Signed integer chip: converts between offset-encoded `i64` cells and field-native values.
+pub struct SignedChip {
+    config: SignedConfig,
+}
+
+impl SignedChip {
+    /// Offset added to a signed value to obtain its unsigned encoding.
+    pub const OFFSET: u64 = 1 << 63;
+
+    /// Configures the signed integer chip.
+    ///
+    /// Allocates 2 advice columns (encoded and decoded value) and one selector.
+    pub fn configure(meta: &mut ConstraintSystem<Fp>) -> SignedConfig {
+        let encoded_col = meta.advice_column();
+        let value_col = meta.advice_column();
+        meta.enable_equality(encoded_col);
+        meta.enable_equality(value_col);
+
+        let offset_selector = meta.selector();
+
+        meta.create_gate("signed offset", |meta| {
+            let s = meta.query_selector(offset_selector);
+            let encoded = meta.query_advice(encoded_col, Rotation::cur());
+            let value = meta.query_advice(value_col, Rotation::cur());
+            vec![s * (encoded - value - Expression::Constant(Fp::from(Self::OFFSET)))]
+        });
+
+        SignedConfig { encoded_col, value_col, offset_selector }
+    }
+
+    /// Constructs the chip from configuration.
+    #[must_use]
+    pub fn construct(config: SignedConfig) -> Self {
+        Self { config }
+    }
+
+    /// Encodes a signed value as its offset representation `v + 2^63`.
+    #[must_use]
+    pub fn encode(value: i64) -> Fp {
+        Fp::from(encode_i64(value))
+    }
+
+    /// Field-native representation of a signed value (`p - |v|` for negative `v`).
+    #[must_use]
+    pub fn to_field(value: i64) -> Fp {
+        if value < 0 {
+            -Fp::from(value.unsigned_abs())
+        } else {
+            Fp::from(value as u64)
+        }
+    }
+
+    /// Decodes an offset-encoded cell into a constrained field-native signed value.
+    ///
+    /// The caller is responsible for range-checking `encoded` to 64 bits.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error`] if synthesis fails.
+    pub fn decode(
+        &self,
+        mut layouter: impl Layouter<Fp>,
+        encoded: &AssignedCell<Fp, Fp>,
+    ) -> Result<AssignedCell<Fp, Fp>, Error> {
+        layouter.assign_region(
+            || "signed decode",
+            |mut region| {
+                self.config.offset_selector.enable(&mut region, 0)?;
+                let e =
+                    encoded.copy_advice(|| "encoded", &mut region, self.config.encoded_col, 0)?;
+                let value = e.value().map(|e| *e - Fp::from(Self::OFFSET));
+                region.assign_advice(|| "value", self.config.value_col, 0, || value)
+            },
+        )
+    }
+
+    /// Returns a reference to the chip configuration.
+    #[must_use]
+    pub fn config(&self) -> &SignedConfig {
+        &self.config
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use halo2_proofs::{
+        circuit::{SimpleFloorPlanner, Value},
+        dev::MockProver,
+        plonk::Circuit,
+    };
+
+    #[derive(Clone)]
+    struct DecodeTestCircuit {
+        encoded: Value<Fp>,
+        expected: Fp,
+    }
+
+    impl Circuit<Fp> for DecodeTestCircuit {
+        type Config = (SignedConfig, Column<Advice>);
+        type FloorPlanner = SimpleFloorPlanner;
+
+        fn without_witnesses(&self) -> Self {
+            Self { encoded: Value::unknown(), expected: self.expected }
+        }
+
+        fn configure(meta: &mut ConstraintSystem<Fp>) -> Self::Config {
+            let input = meta.advice_column();
+            meta.enable_equality(input);
+            let constants = meta.fixed_column();
+            meta.enable_constant(constants);
+            (SignedChip::configure(meta), input)
+        }
+
+        fn synthesize(
+            &self,
+            (config, input): Self::Config,
+            mut layouter: impl Layouter<Fp>,
+        ) -> Result<(), Error> {
+            let chip = SignedChip::construct(config);
+            let encoded = layouter.assign_region(
+                || "load encoded",
+                |mut region| region.assign_advice(|| "encoded", input, 0, || self.encoded),
+            )?;
+            let decoded = chip.decode(layouter.namespace(|| "decode"), &encoded)?;
+            layouter.assign_region(
+                || "expected",
+                |mut region| {
+                    let expected = region.assign_advice_from_constant(
+                        || "expected",
+                        input,
+                        0,
+                        self.expected,
+                    )?;
+                    region.constrain_equal(decoded.cell(), expected.cell())
+                },
+            )
+        }
+    }
+
+    fn decodes_to(encoded: Fp, expected: Fp) -> bool {
+        let circuit = DecodeTestCircuit { encoded: Value::known(encoded), expected };
+        MockProver::run(6, &circuit, vec![]).unwrap().verify().is_ok()
+    }
+
+    #[test]
+    fn test_encode_is_offset_and_monotonic() {
+        assert_eq!(SignedChip::encode(0), Fp::from(1u64 << 63));
+        assert_eq!(SignedChip::encode(i64::MIN), Fp::zero());
+        assert_eq!(SignedChip::encode(i64::MAX), Fp::from(u64::MAX));
+        assert_eq!(SignedChip::encode(-1), Fp::from((1u64 << 63) - 1));
+    }
+
+    #[test]
+    fn test_to_field_negates_negative_values() {
+        assert_eq!(SignedChip::to_field(-5) + Fp::from(5u64), Fp::zero());
+        assert_eq!(SignedChip::to_field(i64::MIN), -Fp::from(1u64 << 63));
+        assert_eq!(SignedChip::to_field(7), Fp::from(7u64));
+    }
+
+    #[test]
+    fn test_decode_roundtrip() {
+        for v in [0, 1, -1, 42, -1_000_000, i64::MIN, i64::MAX] {
+            assert!(decodes_to(SignedChip::encode(v), SignedChip::to_field(v)), "v = {v}");
+        }
+    }
+
+    #[test]
+    fn test_decode_rejects_wrong_value() {
+        assert!(!decodes_to(SignedChip::encode(-5), SignedChip::to_field(5)));
+    }
+}
@@ -0,0 +1,551 @@
+//! Fluent programmatic construction of [`CircuitIR`]
+//!
+//! [`IrBuilder`] builds the same IR as `parse_contract` → `transform_to_ir` without going
+//! through Rust source strings:
+//!
+//! ```rust
+//! use zerostyl_compiler::{ComparisonOp, IrBuilder, ZkType};
+//!
+//! let ir = IrBuilder::new("Note")
+//!     .private("balance", ZkType::U64)
+//!     .range(0..1000)
+//!     .private("minimum", ZkType::U64)
+//!     .compare(ComparisonOp::LessThanOrEqual, 500)
+//!     .compare_field(ComparisonOp::LessThanOrEqual, "balance")
+//!     .private("blinding", ZkType::Field)
+//!     .public("commitment")
+//!     .commit_poseidon("balance", "blinding")
+//!     .build()
+//!     .unwrap();
+//! assert_eq!(ir.public_inputs[0].name, "commitment");
+//! ```
+//!
+//! [`IrBuilder::private`] and [`IrBuilder::public`] declare a field and make it the target
+//! of the per-field methods that follow. Names are checked as they are declared and
+//! referenced — every reference must name an already-declared field. The first error is
+//! kept and returned by [`IrBuilder::build`]; later calls are ignored.
+
+use std::ops::{Bound, RangeBounds};
+
+use zerostyl_runtime::CircuitConfig;
+
+use crate::ast::{
+    default_constraints, encode_i64, ArithExpr, ArithOp, CircuitIR, ComparisonOp, Constraint,
+    HashType, InterFieldConstraint, PublicInputSource, ZkField, ZkType,
+};
+use crate::circuit::{validate_circuit_ir, validate_zk_type};
+use crate::error::{CompilerError, Result};
+
+/// Field targeted by per-field builder methods.
+#[derive(Debug, Clone, Copy)]
+enum Cursor {
+    None,
+    Private(usize),
+    Public(usize),
+}
+
+/// Fluent builder for [`CircuitIR`] (see the [module docs](self)).
+#[derive(Debug)]
+pub struct IrBuilder {
+    name: String,
+    public_inputs: Vec<ZkField>,
+    private_witnesses: Vec<ZkField>,
+    inter_field_constraints: Vec<InterFieldConstraint>,
+    cursor: Cursor,
+    error: Option<CompilerError>,
+}
+
+impl IrBuilder {
+    /// Starts an empty circuit named `name`.
+    pub fn new(name: impl Into<String>) -> Self {
+        let name = name.into();
+        let mut builder = Self {
+            name: name.clone(),
+            public_inputs: Vec::new(),
+            private_witnesses: Vec::new(),
+            inter_field_constraints: Vec::new(),
+            cursor: Cursor::None,
+            error: None,
+        };
+        if !is_identifier(&name) {
+            builder.error = Some(invalid(format!("circuit name '{}' is not an identifier", name)));
+        }
+        builder
+    }
+
+    /// Declares a private witness with the default constraints of its type.
+    pub fn private(self, name: &str, field_type: ZkType) -> Self {
+        self.step(|b| {
+            b.check_new_name(name)?;
+            validate_zk_type(&field_type)?;
+            let constraints = default_constraints(&field_type);
+            b.private_witnesses.push(ZkField { name: name.to_string(), field_type, constraints });
+            b.cursor = Cursor::Private(b.private_witnesses.len() - 1);
+            Ok(())
+        })
+    }
+
+    /// Declares a public input. It must then be bound with [`IrBuilder::commit_poseidon`],
+    /// [`IrBuilder::bind_field`] or [`IrBuilder::bind`].
+    pub fn public(self, name: &str) -> Self {
+        self.step(|b| {
+            b.check_new_name(name)?;
+            b.public_inputs.push(ZkField {
+                name: name.to_string(),
+                field_type: ZkType::Field,
+                constraints: vec![],
+            });
+            b.cursor = Cursor::Public(b.public_inputs.len() - 1);
+            Ok(())
+        })
+    }
+
+    /// Bounds the current unsigned private field to `range`.
+    ///
+    /// Open ends default to the bounds of the field's type.
+    pub fn range(self, range: impl RangeBounds<u128>) -> Self {
+        self.step(|b| {
+            let field = b.current_private("range")?;
+            if field.field_type.is_signed() {
+                return Err(invalid(format!(
+                    "field '{}' is signed; use signed_range instead of range",
+                    field.name
+                )));
+            }
+            let type_max = type_max(field)?;
+            let (min, max) =
+                inclusive_bounds(&range, 0, type_max, |v, d| v.checked_add_signed(d.into()))
+                    .ok_or_else(|| invalid(format!("range on '{}' is empty", field.name)))?;
+            if max > type_max {
+                return Err(invalid(format!(
+                    "range on '{}' exceeds its type maximum {}",
+                    field.name, type_max
+                )));
+            }
+            field.constraints.push(Constraint::RangeProof { min, max });
+            Ok(())
+        })
+    }
+
+    /// Bounds the current `i64` private field to `range`.
+    pub fn signed_range(self, range: impl RangeBounds<i64>) -> Self {
+        self.step(|b| {
+            let field = b.current_signed("signed_range")?;
+            let (min, max) =
+                inclusive_bounds(&range, i64::MIN, i64::MAX, |v, d| v.checked_add(d.into()))
+                    .ok_or_else(|| invalid(format!("range on '{}' is empty", field.name)))?;
+            field.constraints.push(Constraint::RangeProof {
+                min: encode_i64(min).into(),
+                max: encode_i64(max).into(),
+            });
+            Ok(())
+        })
+    }
+
+    /// Compares the current unsigned private field against a constant: `field <op> value`.
+    pub fn compare(self, operator: ComparisonOp, value: u128) -> Self {
+        self.step(|b| {
+            let field = b.current_private("compare")?;
+            if field.field_type.is_signed() {
+                return Err(invalid(format!(
+                    "field '{}' is signed; use signed_compare instead of compare",
+                    field.name
+                )));
+            }
+            field.constraints.push(Constraint::Comparison { operator, value });
+            Ok(())
+        })
+    }
+
+    /// Compares the current `i64` private field against a constant: `field <op> value`.
+    pub fn signed_compare(self, operator: ComparisonOp, value: i64) -> Self {
+        self.step(|b| {
+            let field = b.current_signed("signed_compare")?;
+            field
+                .constraints
+                .push(Constraint::Comparison { operator, value: encode_i64(value).into() });
+            Ok(())
+        })
+    }
+
+    /// Compares the current private field against another private field: `field <op> other`.
+    pub fn compare_field(self, op: ComparisonOp, other: &str) -> Self {
+        self.step(|b| {
+            b.check_private(other)?;
+            let left_field = b.current_private("compare_field")?.name.clone();
+            b.inter_field_constraints.push(InterFieldConstraint::ComparisonCheck {
+                left_field,
+                right_field: other.to_string(),
+                op,
+            });
+            Ok(())
+        })
+    }
+
+    /// Proves the current private field is a leaf of the Merkle tree with root `root`.
+    ///
+    /// `siblings` and `indices` are array fields of the tree depth holding the sibling
+    /// hashes and left/right direction bits.
+    pub fn merkle_member(self, root: &str, siblings: &str, indices: &str) -> Self {
+        self.step(|b| {
+            for name in [root, siblings, indices] {
+                b.check_private(name)?;
+            }
+            let tree_depth = b.private_field(siblings)?.field_type.num_elements();
+            let field = b.current_private("merkle_member")?;
+            field.constraints.push(Constraint::MerkleProof {
+                leaf_field: field.name.clone(),
+                path_field: siblings.to_string(),
+                root_field: root.to_string(),
+                indices_field: indices.to_string(),
+                tree_depth,
+            });
+            Ok(())
+        })
+    }
+
+    /// Binds the current public input to `Poseidon(value, randomness)` of two private fields.
+    pub fn commit_poseidon(self, value: &str, randomness: &str) -> Self {
+        self.step(|b| {
+            b.check_private(randomness)?;
+            let commitment = b.current_public("commit_poseidon")?.name.clone();
+            let hash_type = HashType::Poseidon;
+            b.private_field(value)?
+                .constraints
+                .push(Constraint::Commitment { hash_type: hash_type.clone() });
+            b.inter_field_constraints.push(InterFieldConstraint::CommitmentVerification {
+                commitment,
+                value: value.to_string(),
+                randomness: randomness.to_string(),
+                hash_type,
+            });
+            Ok(())
+        })
+    }
+
+    /// Binds the current public input to the value of a scalar private field.
+    pub fn bind_field(self, source: &str) -> Self {
+        self.step(|b| {
+            b.check_private(source)?;
+            b.bind_current(PublicInputSource::Field(source.to_string()))
+        })
+    }
+
+    /// Binds the current public input to `operands[0] ∘ operands[1] ∘ …` with `∘ = operation`.
+    pub fn bind(self, operation: ArithOp, operands: &[&str]) -> Self {
+        self.step(|b| {
+            for name in operands {
+                b.check_private(name)?;
+            }
+            let operands = operands.iter().map(|s| s.to_string()).collect();
+            b.bind_current(PublicInputSource::Arithmetic { operation, operands })
+        })
+    }
+
+    /// Adds `result = operands[0] ∘ operands[1] ∘ …` with `∘ = operation` over private fields.
+    pub fn relation(self, result: &str, operation: ArithOp, operands: &[&str]) -> Self {
+        self.step(|b| {
+            for name in std::iter::once(&result).chain(operands) {
+                b.check_private(name)?;
+            }
+            b.inter_field_constraints.push(InterFieldConstraint::ArithmeticRelation {
+                result_field: result.to_string(),
+                operation,
+                operands: operands.iter().map(|s| s.to_string()).collect(),
+            });
+            Ok(())
+        })
+    }
+
+    /// Adds the expression check `lhs <op> rhs` over private fields.
+    pub fn check(self, lhs: ArithExpr, op: ComparisonOp, rhs: ArithExpr) -> Self {
+        self.step(|b| {
+            for name in lhs.fields().into_iter().chain(rhs.fields()) {
+                b.check_private(name)?;
+            }
+            b.inter_field_constraints.push(InterFieldConstraint::ExpressionCheck { lhs, op, rhs });
+            Ok(())
+        })
+    }
+
+    /// Finishes the circuit: computes `k` from the estimated rows and validates the IR.
+    ///
+    /// # Errors
+    ///
+    /// Returns the first error recorded while building, or the validation error if the
+    /// assembled IR is not a well-formed circuit (e.g. an unbound public input).
+    pub fn build(self) -> Result<CircuitIR> {
+        if let Some(err) = self.error {
+            return Err(err);
+        }
+        let mut ir = CircuitIR {
+            name: self.name,
+            public_inputs: self.public_inputs,
+            private_witnesses: self.private_witnesses,
+            inter_field_constraints: self.inter_field_constraints,
+            circuit_config: CircuitConfig::minimal(CircuitConfig::MIN_K)?,
+        };
+        ir.recompute_config()?;
+        validate_circuit_ir(&ir)?;
+        Ok(ir)
+    }
+
+    /// Runs `f` unless an earlier step failed, recording its error.
+    fn step(mut self, f: impl FnOnce(&mut Self) -> Result<()>) -> Self {
+        if self.error.is_none() {
+            if let Err(err) = f(&mut self) {
+                self.error = Some(err);
+            }
+        }
+        self
+    }
+
+    fn check_new_name(&self, name: &str) -> Result<()> {
+        if !is_identifier(name) {
+            return Err(invalid(format!("field name '{}' is not an identifier", name)));
+        }
+        if self.private_witnesses.iter().chain(&self.public_inputs).any(|f| f.name == name) {
+            return Err(invalid(format!("field '{}' is declared twice", name)));
+        }
+        Ok(())
+    }
+
+    fn check_private(&self, name: &str) -> Result<()> {
+        if self.private_witnesses.iter().any(|f| f.name == name) {
+            return Ok(());
+        }
+        Err(invalid(format!("'{}' is not a declared private field", name)))
+    }
+
+    fn private_field(&mut self, name: &str) -> Result<&mut ZkField> {
+        self.private_witnesses
+            .iter_mut()
+            .find(|f| f.name == name)
+            .ok_or_else(|| invalid(format!("'{}' is not a declared private field", name)))
+    }
+
+    fn current_private(&mut self, method: &str) -> Result<&mut ZkField> {
+        match self.cursor {
+            Cursor::Private(idx) => Ok(&mut self.private_witnesses[idx]),
+            _ => Err(invalid(format!("{} must follow a private(...) declaration", method))),
+        }
+    }
+
+    fn current_signed(&mut self, method: &str) -> Result<&mut ZkField> {
+        let field = self.current_private(method)?;
+        if !field.field_type.is_signed() {
+            return Err(invalid(format!(
+                "{} requires an i64 field, '{}' is not",
+                method, field.name
+            )));
+        }
+        Ok(field)
+    }
+
+    fn current_public(&mut self, method: &str) -> Result<&mut ZkField> {
+        match self.cursor {
+            Cursor::Public(idx) => Ok(&mut self.public_inputs[idx]),
+            _ => Err(invalid(format!("{} must follow a public(...) declaration", method))),
+        }
+    }
+
+    fn bind_current(&mut self, source: PublicInputSource) -> Result<()> {
+        let public_input = self.current_public("bind")?.name.clone();
+        self.inter_field_constraints
+            .push(InterFieldConstraint::PublicInputBinding { public_input, source });
+        Ok(())
+    }
+}
+
+fn invalid(message: String) -> CompilerError {
+    CompilerError::InvalidIr(message)
+}
+
+fn is_identifier(name: &str) -> bool {
+    let mut chars = name.chars();
+    matches!(chars.next(), Some(c) if c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+/// Largest value a range-checked unsigned field can hold.
+fn type_max(field: &ZkField) -> Result<u128> {
+    match field.field_type.scalar_type().range_bits() {
+        Some(bits) if bits >= 128 => Ok(u128::MAX),
+        Some(bits) => Ok((1u128 << bits) - 1),
+        None => Err(invalid(format!(
+            "range on '{}' requires a range-checked integer type, got {:?}",
+            field.name, field.field_type
+        ))),
+    }
+}
+
+/// Converts `range` to inclusive `(min, max)`, or `None` if it is empty or overflows.
+fn inclusive_bounds<T: Copy + PartialOrd>(
+    range: &impl RangeBounds<T>,
+    lowest: T,
+    highest: T,
+    offset: impl Fn(T, i8) -> Option<T>,
+) -> Option<(T, T)> {
+    let min = match range.start_bound() {
+        Bound::Included(v) => *v,
+        Bound::Excluded(v) => offset(*v, 1)?,
+        Bound::Unbounded => lowest,
+    };
+    let max = match range.end_bound() {
+        Bound::Included(v) => *v,
+        Bound::Excluded(v) => offset(*v, -1)?,
+        Bound::Unbounded => highest,
+    };
+    (min <= max).then_some((min, max))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{parse_contract, transform_to_ir};
+
+    #[test]
+    fn test_builder_matches_parsed_contract() {
+        let source = r#"
+            struct Note {
+                #[zk_private(range = "0..1000", commit = "poseidon", randomness = "blinding")]
+                amount: u64,
+                #[zk_private]
+                blinding: Field,
+            }
+        "#;
+        let parsed = transform_to_ir(parse_contract(source).unwrap()).unwrap();
+
+        let built = IrBuilder::new("Note")
+            .private("amount", ZkType::U64)
+            .range(0..1000)
+            .private("blinding", ZkType::Field)
+            .public("amount_commitment")
+            .commit_poseidon("amount", "blinding")
+            .build()
+            .unwrap();
+        assert_eq!(built, parsed);
+    }
+
+    #[test]
+    fn test_merkle_member_matches_parsed_contract() {
+        let source = r#"
+            struct Membership {
+                #[zk_private]
+                root: Field,
+                #[zk_private]
+                siblings: [Field; 4],
+                #[zk_private]
+                indices: [bool; 4],
+                #[zk_private(constraint = "merkle_member(value, root, siblings, indices)")]
+                leaf: Field,
+            }
+        "#;
+        let parsed = transform_to_ir(parse_contract(source).unwrap()).unwrap();
+
+        let siblings = ZkType::Array { element_type: Box::new(ZkType::Field), size: 4 };
+        let indices = ZkType::Array { element_type: Box::new(ZkType::Bool), size: 4 };
+        let built = IrBuilder::new("Membership")
+            .private("root", ZkType::Field)
+            .private("siblings", siblings)
+            .private("indices", indices)
+            .private("leaf", ZkType::Field)
+            .merkle_member("root", "siblings", "indices")
+            .build()
+            .unwrap();
+        assert_eq!(built, parsed);
+    }
+
+    #[test]
+    fn test_range_bounds() {
+        let range_of = |builder: IrBuilder| {
+            builder.build().unwrap().private_witnesses[0].constraints.last().cloned().unwrap()
+        };
+        let b = IrBuilder::new("R").private("x", ZkType::U8).range(10..);
+        assert_eq!(range_of(b), Constraint::RangeProof { min: 10, max: 255 });
+        let b = IrBuilder::new("R").private("x", ZkType::I64).signed_range(-5..=5);
+        assert_eq!(
+            range_of(b),
+            Constraint::RangeProof { min: encode_i64(-5).into(), max: encode_i64(5).into() }
+        );
+        let b = IrBuilder::new("R").private("x", ZkType::U8).range(0..=256);
+        assert!(b.build().unwrap_err().to_string().contains("exceeds its type maximum 255"));
+        let b = IrBuilder::new("R").private("x", ZkType::U8).range(5..5);
+        assert!(b.build().unwrap_err().to_string().contains("is empty"));
+    }
+
+    #[test]
+    fn test_rejects_bad_names_and_references() {
+        let err = |b: IrBuilder| b.build().unwrap_err().to_string();
+        assert!(err(IrBuilder::new("my circuit")).contains("not an identifier"));
+        assert!(err(IrBuilder::new("C").private("x", ZkType::U8).private("x", ZkType::U8))
+            .contains("declared twice"));
+        assert!(err(IrBuilder::new("C")
+            .private("x", ZkType::U8)
+            .compare_field(ComparisonOp::GreaterThan, "y"))
+        .contains("'y' is not a declared private field"));
+        assert!(err(IrBuilder::new("C").public("p").range(0..1)).contains("must follow a private"));
+        assert!(err(IrBuilder::new("C").private("x", ZkType::U8).bind_field("x"))
+            .contains("must follow a public"));
+        assert!(err(IrBuilder::new("C").private("x", ZkType::U8).signed_range(0..1))
+            .contains("requires an i64 field"));
+    }
+
+    #[test]
+    fn test_first_error_wins() {
+        let err = IrBuilder::new("C")
+            .private("x", ZkType::U8)
+            .compare_field(ComparisonOp::GreaterThan, "missing")
+            .private("x", ZkType::U8)
+            .build()
+            .unwrap_err();
+        assert!(err.to_string().contains("'missing'"), "got: {err}");
+    }
+
+    #[test]
+    fn test_unbound_public_input_fails_validation() {
+        let err = IrBuilder::new("C").private("x", ZkType::U8).public("p").build().unwrap_err();
+        assert!(!matches!(err, CompilerError::InvalidIr(_)), "got: {err}");
+    }
+
+    #[test]
+    fn test_bind_and_relation() {
+        let ir = IrBuilder::new("Transfer")
+            .private("balance_old", ZkType::U64)
+            .private("amount", ZkType::U64)
+            .private("balance_new", ZkType::U64)
+            .relation("balance_new", ArithOp::Sub, &["balance_old", "amount"])
+            .public("new_balance")
+            .bind_field("balance_new")
+            .public("total")
+            .bind(ArithOp::Add, &["balance_new", "amount"])
+            .build()
+            .unwrap();
+        assert_eq!(ir.public_inputs.len(), 2);
+        assert_eq!(ir.inter_field_constraints.len(), 3);
+        assert_eq!(ir.circuit_config.k(), crate::measure_rows(&ir).unwrap().min_k);
+    }
+
+    #[test]
+    fn test_check_matches_parsed_contract() {
+        let ir = IrBuilder::new("Loan")
+            .private("collateral", ZkType::U64)
+            .private("debt", ZkType::U64)
+            .check(
+                ArithExpr::field("collateral") * ArithExpr::constant(100),
+                ComparisonOp::GreaterThanOrEqual,
+                ArithExpr::field("debt") * ArithExpr::constant(150),
+            )
+            .build()
+            .unwrap();
+        let source = r#"
+            struct Loan {
+                #[zk_private(constraint = "value * 100 >= debt * 150")]
+                collateral: u64,
+                #[zk_private]
+                debt: u64,
+            }
+        "#;
+        assert_eq!(ir, transform_to_ir(parse_contract(source).unwrap()).unwrap());
+    }
+}
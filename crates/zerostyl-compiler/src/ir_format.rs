@@ -0,0 +1,432 @@
+//! Persistent forms of the circuit IR
+//!
+//! [`CircuitIR`] has two serialized forms:
+//!
+//! - **JSON** ([`CircuitIR::to_json`] / [`CircuitIR::from_json`]): the stable, versioned
+//!   exchange format for checked-in snapshots, hand-edited IR, and IR produced by other tools.
+//!   The document wraps the IR with a format version:
+//!
+//!   ```text
+//!   { "version": 1, "circuit": { "name": …, "public_inputs": […], … } }
+//!   ```
+//!
+//!   Enums are externally tagged with snake_case names (`"u64"`, `{"range": {"num_bits": 64}}`).
+//!   `circuit_config` may be omitted, in which case it is recomputed from the fields. Its
+//!   `range_chip` (`"bits"` or `"lookup"`) picks the range chip and defaults to `"bits"`.
+//!
+//! - **Text** ([`CircuitIR::to_text`], also the [`std::fmt::Display`] output): a compact,
+//!   line-oriented rendering meant for reading and diffing in code review. It is not parsed
+//!   back.
+//!
+//!   ```text
+//!   circuit Note (k=10)
+//!     public  amount_commitment: field
+//!     private amount: u64 range(64) commit(poseidon)
+//!     private blinding: field
+//!     commitment amount_commitment = poseidon(amount, blinding)
+//!   ```
+
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+use zerostyl_runtime::CircuitConfig;
+
+use crate::ast::{
+    ArithExpr, ArithOp, CircuitIR, ComparisonOp, Constraint, HashType, InterFieldConstraint,
+    PublicInputSource, ZkField, ZkType,
+};
+use crate::circuit::validate_circuit_ir;
+use crate::error::{CompilerError, Result};
+
+/// Version of the JSON IR format written by [`CircuitIR::to_json`].
+///
+/// Bumped on any change that older loaders would misread.
+pub const IR_FORMAT_VERSION: u32 = 1;
+
+#[derive(Serialize)]
+struct IrDocumentRef<'a> {
+    version: u32,
+    circuit: &'a CircuitIR,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct IrDocument {
+    version: u32,
+    circuit: IrBody,
+}
+
+/// [`CircuitIR`] with an optional config, so hand-written IR can leave `k` to the compiler.
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct IrBody {
+    name: String,
+    #[serde(default)]
+    public_inputs: Vec<ZkField>,
+    #[serde(default)]
+    private_witnesses: Vec<ZkField>,
+    #[serde(default)]
+    inter_field_constraints: Vec<InterFieldConstraint>,
+    circuit_config: Option<CircuitConfig>,
+}
+
+impl CircuitIR {
+    /// Serializes the IR as pretty-printed, versioned JSON.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`CompilerError::InvalidIr`] if serialization fails.
+    pub fn to_json(&self) -> Result<String> {
+        serde_json::to_string_pretty(&IrDocumentRef { version: IR_FORMAT_VERSION, circuit: self })
+            .map_err(|e| CompilerError::InvalidIr(e.to_string()))
+    }
+
+    /// Loads IR from the versioned JSON produced by [`CircuitIR::to_json`].
+    ///
+    /// A missing `circuit_config` is recomputed from the fields and constraints. The loaded
+    /// IR is validated with [`validate_circuit_ir`], so it can be handed straight to
+    /// [`CircuitBuilder`](crate::CircuitBuilder).
+    ///
+    /// # Errors
+    ///
+    /// Returns [`CompilerError::InvalidIr`] if the JSON is malformed or has an unsupported
+    /// version, and the validation error if the IR is not a well-formed circuit.
+    pub fn from_json(json: &str) -> Result<CircuitIR> {
+        let doc: IrDocument =
+            serde_json::from_str(json).map_err(|e| CompilerError::InvalidIr(e.to_string()))?;
+        if doc.version != IR_FORMAT_VERSION {
+            return Err(CompilerError::InvalidIr(format!(
+                "unsupported IR format version {} (expected {})",
+                doc.version, IR_FORMAT_VERSION
+            )));
+        }
+
+        let body = doc.circuit;
+        let mut ir = CircuitIR {
+            name: body.name,
+            public_inputs: body.public_inputs,
+            private_witnesses: body.private_witnesses,
+            inter_field_constraints: body.inter_field_constraints,
+            circuit_config: CircuitConfig::minimal(CircuitConfig::MIN_K)?,
+        };
+        match body.circuit_config {
+            // Deserialization bypasses the config constructor, so re-check `k`
+            Some(config) => {
+                ir.circuit_config = CircuitConfig::new(
+                    config.k(),
+                    config.num_advice_columns(),
+                    config.num_instance_columns(),
+                    config.num_fixed_columns(),
+                )?
+                .with_range_chip(config.range_chip());
+            }
+            None => ir.recompute_config()?,
+        }
+
+        validate_circuit_ir(&ir)?;
+        Ok(ir)
+    }
+
+    /// Renders the compact text form of the IR (see the [module docs](self)).
+    pub fn to_text(&self) -> String {
+        self.to_string()
+    }
+}
+
+impl fmt::Display for CircuitIR {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "circuit {} (k={})", self.name, self.circuit_config.k())?;
+        for field in &self.public_inputs {
+            writeln!(f, "  public  {field}")?;
+        }
+        for field in &self.private_witnesses {
+            writeln!(f, "  private {field}")?;
+        }
+        for constraint in &self.inter_field_constraints {
+            writeln!(f, "  {constraint}")?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for ZkField {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.name, self.field_type)?;
+        // Signed fields store bounds offset-encoded (see `encode_i64`); show the values
+        // the contract wrote instead.
+        let signed = self.field_type.is_signed();
+        let decode = |v: u128| v as i128 - (1i128 << 63);
+        for constraint in &self.constraints {
+            match constraint {
+                Constraint::RangeProof { min, max } if signed => {
+                    write!(f, " in({}..={})", decode(*min), decode(*max))?;
+                }
+                Constraint::Comparison { operator, value } if signed => {
+                    write!(f, " {operator} {}", decode(*value))?;
+                }
+                constraint => write!(f, " {constraint}")?,
+            }
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for ZkType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            ZkType::U8 => "u8",
+            ZkType::U16 => "u16",
+            ZkType::U32 => "u32",
+            ZkType::U64 => "u64",
+            ZkType::U128 => "u128",
+            ZkType::I64 => "i64",
+            ZkType::Bool => "bool",
+            ZkType::Field => "field",
+            ZkType::Bytes32 => "bytes32",
+            ZkType::Address => "address",
+            ZkType::Array { element_type, size } => {
+                return write!(f, "[{element_type}; {size}]");
+            }
+        };
+        f.write_str(name)
+    }
+}
+
+impl fmt::Display for Constraint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Constraint::Range { num_bits } => write!(f, "range({num_bits})"),
+            Constraint::Boolean => f.write_str("boolean"),
+            Constraint::RangeProof { min, max } => write!(f, "in({min}..={max})"),
+            Constraint::Comparison { operator, value } => write!(f, "{operator} {value}"),
+            Constraint::Commitment { hash_type } => write!(f, "commit({hash_type})"),
+            Constraint::ArithmeticRelation { lhs_field, rhs_fields, .. } => {
+                write!(f, "relation({lhs_field} =")?;
+                for (i, (name, op)) in rhs_fields.iter().enumerate() {
+                    if i == 0 {
+                        write!(f, " {name}")?;
+                    } else {
+                        write!(f, " {op} {name}")?;
+                    }
+                }
+                f.write_str(")")
+            }
+            Constraint::MerkleProof {
+                leaf_field,
+                path_field,
+                root_field,
+                indices_field,
+                tree_depth,
+            } => write!(
+                f,
+                "merkle({leaf_field}, {path_field}, {indices_field} -> {root_field}; depth {tree_depth})"
+            ),
+        }
+    }
+}
+
+impl fmt::Display for InterFieldConstraint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            InterFieldConstraint::ArithmeticRelation { result_field, operation, operands } => {
+                write!(f, "relation {result_field} = ")?;
+                write_fold(f, *operation, operands)
+            }
+            InterFieldConstraint::MerkleVerification { leaf, path, root, indices } => {
+                write!(f, "merkle {root} = root({leaf}, {path}, {indices})")
+            }
+            InterFieldConstraint::CommitmentVerification {
+                commitment,
+                value,
+                randomness,
+                hash_type,
+            } => write!(f, "commitment {commitment} = {hash_type}({value}, {randomness})"),
+            InterFieldConstraint::ComparisonCheck { left_field, right_field, op } => {
+                write!(f, "compare {left_field} {op} {right_field}")
+            }
+            InterFieldConstraint::PublicInputBinding { public_input, source } => {
+                write!(f, "bind {public_input} = {source}")
+            }
+            InterFieldConstraint::ExpressionCheck { lhs, op, rhs } => {
+                write!(f, "check {lhs} {op} {rhs}")
+            }
+        }
+    }
+}
+
+impl fmt::Display for PublicInputSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PublicInputSource::Field(name) => f.write_str(name),
+            PublicInputSource::Arithmetic { operation, operands } => {
+                write_fold(f, *operation, operands)
+            }
+        }
+    }
+}
+
+impl fmt::Display for ArithExpr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ArithExpr::Field(name) => f.write_str(name),
+            ArithExpr::Constant(value) => write!(f, "{value}"),
+            ArithExpr::Binary { op, lhs, rhs } => {
+                // Parenthesize operands that bind looser than `op`, and right operands that
+                // bind equally, so `a - (b - c)` keeps its grouping.
+                let prec = precedence(*op);
+                write_operand(f, lhs, |p| p < prec)?;
+                write!(f, " {op} ")?;
+                write_operand(f, rhs, |p| p <= prec)
+            }
+        }
+    }
+}
+
+fn precedence(op: ArithOp) -> u8 {
+    match op {
+        ArithOp::Add | ArithOp::Sub => 1,
+        ArithOp::Mul | ArithOp::Div => 2,
+    }
+}
+
+fn write_operand(
+    f: &mut fmt::Formatter<'_>,
+    operand: &ArithExpr,
+    needs_parens: impl Fn(u8) -> bool,
+) -> fmt::Result {
+    match operand {
+        ArithExpr::Binary { op, .. } if needs_parens(precedence(*op)) => write!(f, "({operand})"),
+        _ => write!(f, "{operand}"),
+    }
+}
+
+/// Writes `operands[0] ∘ operands[1] ∘ …`.
+fn write_fold(f: &mut fmt::Formatter<'_>, op: ArithOp, operands: &[String]) -> fmt::Result {
+    for (i, operand) in operands.iter().enumerate() {
+        if i > 0 {
+            write!(f, " {op} ")?;
+        }
+        f.write_str(operand)?;
+    }
+    Ok(())
+}
+
+impl fmt::Display for ComparisonOp {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            ComparisonOp::GreaterThan => ">",
+            ComparisonOp::GreaterThanOrEqual => ">=",
+            ComparisonOp::LessThan => "<",
+            ComparisonOp::LessThanOrEqual => "<=",
+            ComparisonOp::Equal => "==",
+            ComparisonOp::NotEqual => "!=",
+        })
+    }
+}
+
+impl fmt::Display for ArithOp {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            ArithOp::Add => "+",
+            ArithOp::Sub => "-",
+            ArithOp::Mul => "*",
+            ArithOp::Div => "/",
+        })
+    }
+}
+
+impl fmt::Display for HashType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            HashType::Pedersen => "pedersen",
+            HashType::Poseidon => "poseidon",
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{parse_contract, transform_to_ir};
+
+    fn note_ir() -> CircuitIR {
+        let input = r#"
+            struct Note {
+                #[zk_private(commit = "poseidon", randomness = "blinding")]
+                amount: u64,
+                #[zk_private]
+                blinding: Field,
+            }
+        "#;
+        transform_to_ir(parse_contract(input).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn test_json_roundtrip() {
+        let ir = note_ir();
+        let json = ir.to_json().unwrap();
+        assert!(json.contains("\"version\": 1"));
+        assert_eq!(CircuitIR::from_json(&json).unwrap(), ir);
+    }
+
+    #[test]
+    fn test_from_json_rejects_unknown_version() {
+        let json = note_ir().to_json().unwrap().replace("\"version\": 1", "\"version\": 99");
+        let err = CircuitIR::from_json(&json).unwrap_err().to_string();
+        assert!(err.contains("unsupported IR format version 99"), "got: {err}");
+    }
+
+    #[test]
+    fn test_from_json_recomputes_missing_config() {
+        let json = r#"{
+            "version": 1,
+            "circuit": {
+                "name": "Tiny",
+                "private_witnesses": [
+                    { "name": "x", "field_type": "u8", "constraints": [{ "range": { "num_bits": 8 } }] }
+                ]
+            }
+        }"#;
+        let ir = CircuitIR::from_json(json).unwrap();
+        assert_eq!(ir.circuit_config.k(), crate::measure_rows(&ir).unwrap().min_k);
+    }
+
+    #[test]
+    fn test_from_json_revalidates_config_k() {
+        let json = note_ir().to_json().unwrap();
+        let k = format!("\"k\": {}", note_ir().circuit_config.k());
+        let err = CircuitIR::from_json(&json.replace(&k, "\"k\": 2")).unwrap_err().to_string();
+        assert!(err.contains("k must be >="), "got: {err}");
+    }
+
+    #[test]
+    fn test_from_json_keeps_range_chip() {
+        let mut ir = note_ir();
+        ir.circuit_config = ir.circuit_config.with_range_chip(crate::RangeChip::Lookup);
+        ir.recompute_config().unwrap();
+        assert!(ir.to_json().unwrap().contains("\"range_chip\": \"lookup\""));
+        assert_eq!(CircuitIR::from_json(&ir.to_json().unwrap()).unwrap(), ir);
+    }
+
+    #[test]
+    fn test_text_form() {
+        let text = note_ir().to_text();
+        let lines: Vec<&str> = text.lines().collect();
+        assert!(lines[0].starts_with("circuit Note (k="));
+        assert_eq!(lines[1], "  public  amount_commitment: field");
+        assert_eq!(lines[2], "  private amount: u64 range(64) commit(poseidon)");
+        assert_eq!(lines[3], "  private blinding: field");
+        assert_eq!(lines[4], "  commitment amount_commitment = poseidon(amount, blinding)");
+    }
+
+    #[test]
+    fn test_expression_text_keeps_grouping() {
+        let (a, b, c) = (ArithExpr::field("a"), ArithExpr::field("b"), ArithExpr::field("c"));
+        let check = InterFieldConstraint::ExpressionCheck {
+            lhs: (a.clone() - (b.clone() - c.clone())) * ArithExpr::constant(100),
+            op: ComparisonOp::GreaterThanOrEqual,
+            rhs: a * b + c,
+        };
+        assert_eq!(check.to_string(), "check (a - (b - c)) * 100 >= a * b + c");
+    }
+}
@@ -0,0 +1,376 @@
+//! Exact row usage of a synthesized circuit
+//!
+//! [`measure_rows`] dry-runs synthesis of the halo2 circuit for a [`CircuitIR`] with
+//! unknown witnesses, recording every cell the floor planner places. The resulting
+//! [`RowReport`] lists the rows taken by each region and the smallest `k` whose usable
+//! rows (all rows minus halo2's blinding rows) fit the circuit. [`measure_circuit_rows`]
+//! does the same for any halo2 circuit, e.g. the hand-written example circuits.
+//!
+//! ```rust
+//! use zerostyl_compiler::{measure_rows, parse_contract, transform_to_ir};
+//!
+//! let input = r#"
+//!     struct MyCircuit {
+//!         #[zk_private]
+//!         secret: u64,
+//!     }
+//! "#;
+//! let ir = transform_to_ir(parse_contract(input).unwrap()).unwrap();
+//! let report = measure_rows(&ir).unwrap();
+//! assert_eq!(report.min_k, ir.circuit_config.k());
+//! for (region, rows) in report.rows_by_region() {
+//!     println!("{region}: {rows} rows");
+//! }
+//! ```
+
+use std::collections::BTreeMap;
+
+use halo2_proofs::{
+    arithmetic::Field as Halo2Field,
+    circuit::Value,
+    pasta::Fp,
+    plonk::{
+        Advice, Any, Assigned, Assignment, Circuit, Column, ConstraintSystem, Error, Fixed,
+        FloorPlanner, Instance, Selector,
+    },
+};
+use zerostyl_runtime::CircuitConfig;
+
+use crate::ast::CircuitIR;
+use crate::circuit::{ZkCircuit, ZkCircuitConfig};
+use crate::error::{CompilerError, Result};
+
+/// Rows occupied by one synthesized region.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RegionRows {
+    /// Namespace path and region name, e.g. `range_0/range check 64 bits`.
+    pub path: String,
+    /// Region name as given by the gadget, e.g. `range check 64 bits`.
+    pub name: String,
+    /// Number of rows between the region's first and last assigned cell.
+    pub rows: usize,
+}
+
+/// Result of a dry-run synthesis (see the [module docs](self)).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RowReport {
+    /// Regions in synthesis order.
+    pub regions: Vec<RegionRows>,
+    /// Rows used in the tallest column, including constants and public inputs.
+    pub used_rows: usize,
+    /// Rows halo2 reserves for blinding at the end of every column.
+    pub blinding_rows: usize,
+    /// Smallest `k` with `2^k - blinding_rows >= used_rows`, at least [`CircuitConfig::MIN_K`].
+    pub min_k: u32,
+}
+
+impl RowReport {
+    /// Total rows per region name (e.g. all `range check 64 bits` regions together), tallest first.
+    pub fn rows_by_region(&self) -> Vec<(String, usize)> {
+        let mut totals: BTreeMap<&str, usize> = BTreeMap::new();
+        for region in &self.regions {
+            *totals.entry(&region.name).or_default() += region.rows;
+        }
+        let mut totals: Vec<(String, usize)> =
+            totals.into_iter().map(|(name, rows)| (name.to_string(), rows)).collect();
+        totals.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        totals
+    }
+}
+
+/// Measures the exact rows the circuit for `ir` uses and the minimal `k` that fits them.
+///
+/// # Errors
+///
+/// Returns [`CompilerError::Other`] if the circuit cannot be synthesized (e.g. the IR
+/// references unknown fields; run [`validate_circuit_ir`](crate::validate_circuit_ir) first
+/// for a precise error) or does not fit in [`CircuitConfig::MAX_K`].
+pub fn measure_rows(ir: &CircuitIR) -> Result<RowReport> {
+    let circuit = ZkCircuit::<Fp> {
+        ir: ir.clone(),
+        witness_values: vec![Value::unknown(); ir.num_witness_cells()],
+        public_values: vec![Value::unknown(); ir.public_inputs.len()],
+    };
+    measure_circuit_rows(&ir.name, &circuit, ZkCircuitConfig::constants)
+}
+
+/// Measures any halo2 circuit, such as a hand-written one, the way [`measure_rows`] measures
+/// compiled circuits.
+///
+/// `constants` returns the fixed columns the circuit enabled for constants (empty if it
+/// enabled none); `name` only appears in error messages. Witnesses are never read, so
+/// `circuit` can be the result of `without_witnesses`.
+///
+/// # Errors
+///
+/// Returns [`CompilerError::Other`] if the circuit cannot be synthesized or does not fit in
+/// [`CircuitConfig::MAX_K`].
+pub fn measure_circuit_rows<C: Circuit<Fp>>(
+    name: &str,
+    circuit: &C,
+    constants: impl FnOnce(&C::Config) -> Vec<Column<Fixed>>,
+) -> Result<RowReport> {
+    let mut cs = ConstraintSystem::default();
+    let config = C::configure(&mut cs);
+    let constants = constants(&config);
+    let mut counter = RowCounter::default();
+    C::FloorPlanner::synthesize(&mut counter, circuit, config, constants).map_err(|e| {
+        CompilerError::Other(format!("Dry-run synthesis of '{}' failed: {}", name, e))
+    })?;
+
+    // MockProver and the prover only use the first `2^k - (blinding_factors + 1)` rows
+    let blinding_rows = cs.blinding_factors() + 1;
+    let min_k = (CircuitConfig::MIN_K..=CircuitConfig::MAX_K)
+        .find(|k| {
+            let n = 1usize << k;
+            n >= cs.minimum_rows() && n - blinding_rows >= counter.used_rows
+        })
+        .ok_or_else(|| {
+            CompilerError::Other(format!(
+                "Circuit '{}' uses {} rows, more than k={} provides",
+                name,
+                counter.used_rows,
+                CircuitConfig::MAX_K
+            ))
+        })?;
+
+    let regions = counter
+        .regions
+        .into_iter()
+        .map(|r| RegionRows {
+            path: r.path,
+            name: r.name,
+            rows: r.span.map_or(0, |(first, last)| last - first + 1),
+        })
+        .collect();
+    Ok(RowReport { regions, used_rows: counter.used_rows, blinding_rows, min_k })
+}
+
+type Halo2Result<T> = std::result::Result<T, Error>;
+
+struct RegionSpan {
+    path: String,
+    name: String,
+    /// First and last row touched by the region.
+    span: Option<(usize, usize)>,
+}
+
+/// [`Assignment`] that only records which rows are touched.
+#[derive(Default)]
+struct RowCounter {
+    namespaces: Vec<String>,
+    regions: Vec<RegionSpan>,
+    in_region: bool,
+    used_rows: usize,
+}
+
+impl RowCounter {
+    fn touch(&mut self, row: usize) {
+        self.used_rows = self.used_rows.max(row + 1);
+        if self.in_region {
+            if let Some(region) = self.regions.last_mut() {
+                let (first, last) = region.span.get_or_insert((row, row));
+                *first = (*first).min(row);
+                *last = (*last).max(row);
+            }
+        }
+    }
+}
+
+impl<F: Halo2Field> Assignment<F> for RowCounter {
+    fn enter_region<NR, N>(&mut self, name_fn: N)
+    where
+        NR: Into<String>,
+        N: FnOnce() -> NR,
+    {
+        let name = name_fn().into();
+        let path =
+            self.namespaces.iter().chain(std::iter::once(&name)).cloned().collect::<Vec<_>>();
+        self.regions.push(RegionSpan { path: path.join("/"), name, span: None });
+        self.in_region = true;
+    }
+
+    fn exit_region(&mut self) {
+        self.in_region = false;
+    }
+
+    fn enable_selector<A, AR>(&mut self, _: A, _: &Selector, row: usize) -> Halo2Result<()>
+    where
+        A: FnOnce() -> AR,
+        AR: Into<String>,
+    {
+        self.touch(row);
+        Ok(())
+    }
+
+    fn query_instance(&self, _: Column<Instance>, _: usize) -> Halo2Result<Value<F>> {
+        Ok(Value::unknown())
+    }
+
+    fn assign_advice<V, VR, A, AR>(
+        &mut self,
+        _: A,
+        _: Column<Advice>,
+        row: usize,
+        _: V,
+    ) -> Halo2Result<()>
+    where
+        V: FnOnce() -> Value<VR>,
+        VR: Into<Assigned<F>>,
+        A: FnOnce() -> AR,
+        AR: Into<String>,
+    {
+        self.touch(row);
+        Ok(())
+    }
+
+    fn assign_fixed<V, VR, A, AR>(
+        &mut self,
+        _: A,
+        _: Column<Fixed>,
+        row: usize,
+        _: V,
+    ) -> Halo2Result<()>
+    where
+        V: FnOnce() -> Value<VR>,
+        VR: Into<Assigned<F>>,
+        A: FnOnce() -> AR,
+        AR: Into<String>,
+    {
+        self.touch(row);
+        Ok(())
+    }
+
+    fn copy(
+        &mut self,
+        left: Column<Any>,
+        left_row: usize,
+        right: Column<Any>,
+        right_row: usize,
+    ) -> Halo2Result<()> {
+        // Public inputs are read from instance rows, which must also be usable rows
+        for (column, row) in [(left, left_row), (right, right_row)] {
+            if matches!(column.column_type(), Any::Instance) {
+                self.used_rows = self.used_rows.max(row + 1);
+            }
+        }
+        Ok(())
+    }
+
+    fn fill_from_row(
+        &mut self,
+        _: Column<Fixed>,
+        _: usize,
+        _: Value<Assigned<F>>,
+    ) -> Halo2Result<()> {
+        Ok(())
+    }
+
+    fn push_namespace<NR, N>(&mut self, name_fn: N)
+    where
+        NR: Into<String>,
+        N: FnOnce() -> NR,
+    {
+        self.namespaces.push(name_fn().into());
+    }
+
+    fn pop_namespace(&mut self, _: Option<String>) {
+        self.namespaces.pop();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{parse_contract, transform_to_ir, CircuitBuilder, RangeChip};
+    use halo2_proofs::dev::MockProver;
+    use halo2curves::ff::PrimeField;
+
+    fn ir_for(source: &str) -> CircuitIR {
+        transform_to_ir(parse_contract(source).unwrap()).unwrap()
+    }
+
+    const RANGE_AND_COMMIT: &str = r#"
+        struct Note {
+            #[zk_private(commit = "poseidon", randomness = "blinding")]
+            amount: u64,
+            #[zk_private]
+            blinding: Field,
+        }
+    "#;
+
+    #[test]
+    fn test_reports_regions_per_gadget() {
+        let report = measure_rows(&ir_for(RANGE_AND_COMMIT)).unwrap();
+        let by_region = report.rows_by_region();
+        let rows_of = |name: &str| {
+            by_region.iter().find(|(n, _)| n == name).map(|(_, rows)| *rows).unwrap_or(0)
+        };
+        // One 64-bit decomposition: a row per bit plus the final accumulator row
+        assert_eq!(rows_of("range check 64 bits"), 65);
+        assert_eq!(rows_of("private_witnesses"), 1);
+        assert!(report.regions.iter().any(|r| r.path.starts_with("range_0/")));
+        assert!(report.used_rows >= report.regions.iter().map(|r| r.rows).max().unwrap());
+    }
+
+    #[test]
+    fn test_min_k_is_minimal() {
+        let ir = ir_for(RANGE_AND_COMMIT);
+        let report = measure_rows(&ir).unwrap();
+        assert!((1usize << report.min_k) - report.blinding_rows >= report.used_rows);
+        assert!((1usize << (report.min_k - 1)) - report.blinding_rows < report.used_rows);
+    }
+
+    #[test]
+    fn test_min_k_satisfies_mock_prover() {
+        let ir = ir_for(
+            r#"
+            struct Wide {
+                #[zk_private]
+                a: u128,
+                #[zk_private]
+                b: u64,
+            }
+        "#,
+        );
+        let k = measure_rows(&ir).unwrap().min_k;
+        let circuit = CircuitBuilder::new(ir)
+            .build::<Fp>()
+            .with_witnesses(vec![Fp::from_u128(u128::MAX), Fp::from(7)])
+            .unwrap();
+        let prover = MockProver::run(k, &circuit, vec![vec![]]).unwrap();
+        assert_eq!(prover.verify(), Ok(()));
+        assert!(MockProver::run(k - 1, &circuit, vec![vec![]]).is_err());
+    }
+
+    #[test]
+    fn test_lookup_range_chip_shortens_tall_circuits() {
+        let bits = ir_for(
+            r#"
+            struct Batch {
+                #[zk_private]
+                amounts: [u64; 8],
+            }
+        "#,
+        );
+        let mut lookup = bits.clone();
+        lookup.circuit_config = lookup.circuit_config.with_range_chip(RangeChip::Lookup);
+        lookup.recompute_config().unwrap();
+        assert_eq!(lookup.circuit_config.range_chip(), RangeChip::Lookup);
+
+        let bits_report = measure_rows(&bits).unwrap();
+        let lookup_report = measure_rows(&lookup).unwrap();
+        // Eight stacked 65-row decompositions, against 8 limbs each beside the 256-row table
+        assert_eq!(bits_report.used_rows, 8 * 65);
+        assert_eq!(lookup_report.used_rows, 256);
+        assert_eq!((bits_report.min_k, lookup_report.min_k), (10, 9));
+        assert_eq!(lookup.circuit_config.k(), 9);
+
+        let circuit = CircuitBuilder::new(lookup)
+            .build::<Fp>()
+            .with_witnesses(vec![Fp::from(u64::MAX); 8])
+            .unwrap();
+        let prover = MockProver::run(9, &circuit, vec![vec![]]).unwrap();
+        assert_eq!(prover.verify(), Ok(()));
+    }
+}
@@ -6,21 +6,34 @@
 pub mod ast;
 pub mod circuit;
 pub mod codegen;
+pub mod descriptor;
+pub mod diagnostic;
 pub mod error;
 pub mod gadgets;
+pub mod ir_builder;
+pub mod ir_format;
+pub mod layout;
+pub mod optimize;
 pub mod parser;
 
 pub use ast::{
-    compute_k, ArithOp, CircuitIR, ComparisonOp, Constraint, HashType, InterFieldConstraint,
-    ZkField, ZkType,
+    compute_k, encode_i64, ArithExpr, ArithOp, CircuitIR, ComparisonOp, Constraint, HashType,
+    InterFieldConstraint, PublicInputSource, ZkField, ZkType, MAX_ARRAY_ELEMENTS,
 };
 pub use circuit::{validate_circuit_ir, CircuitBuilder, ZkCircuit, ZkCircuitConfig};
+pub use codegen::size_report::{SizeComparison, WasmSizeReport};
 pub use codegen::{validate_wasm, CircuitMetadata, CodegenConfig, WasmCodegen};
+pub use descriptor::IrDescriptor;
+pub use diagnostic::{Diagnostic, SourceSpan};
 pub use error::{CompilerError, Result};
-pub use parser::{parse_contract, ParsedContract, PrivateField};
+pub use ir_builder::IrBuilder;
+pub use ir_format::IR_FORMAT_VERSION;
+pub use layout::{measure_circuit_rows, measure_rows, RegionRows, RowReport};
+pub use optimize::{optimize, optimize_with, OptimizationReport, Pass};
+pub use parser::{parse_contract, parse_contracts, ParsedContract, PrivateField};
 
 // Re-export runtime types for convenience
-pub use zerostyl_runtime::CircuitConfig;
+pub use zerostyl_runtime::{CircuitConfig, RangeChip};
 
-// Re-export the main transformation function
-pub use ast::transform_to_ir;
+// Re-export the main transformation functions
+pub use ast::{transform_all_to_ir, transform_to_ir};
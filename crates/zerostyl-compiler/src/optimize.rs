@@ -0,0 +1,450 @@
+//! Optimization passes over [`CircuitIR`]
+//!
+//! Lowering from annotations is literal: a `u64` field with `range = "0..1000"` keeps its
+//! default 64-bit [`Constraint::Range`] next to the [`Constraint::RangeProof`], so synthesis
+//! decomposes the value once for each. The passes here rewrite per-field and inter-field
+//! constraints into cheaper equivalents without changing which witnesses satisfy the
+//! circuit:
+//!
+//! - [`Pass::Dedupe`] — drops constraints that appear twice on the same field, and
+//!   repeated inter-field constraints
+//! - [`Pass::DropRedundantBooleans`] — drops range checks implied by a `Boolean`
+//!   constraint, and `Boolean` constraints on Merkle direction bits, which the Merkle
+//!   swap gate already constrains
+//! - [`Pass::SubsumeRanges`] — keeps the tightest of several bit ranges, intersects
+//!   bounded ranges, drops bit ranges a bounded range implies, and folds literal
+//!   comparisons into a single bounded range when that costs fewer rows
+//!
+//! Synthesis already assigns every witness cell once and copies it into each gadget, so
+//! the passes only remove gadget work. [`optimize`] reports exact row counts
+//! ([`measure_rows`]) before and after, and recomputes `k`.
+//!
+//! ```rust
+//! use zerostyl_compiler::{optimize, parse_contract, transform_to_ir};
+//!
+//! let input = r#"
+//!     struct Bounded {
+//!         #[zk_private(range = "0..1000", constraint = "value >= 10")]
+//!         amount: u64,
+//!     }
+//! "#;
+//! let mut ir = transform_to_ir(parse_contract(input).unwrap()).unwrap();
+//! let report = optimize(&mut ir).unwrap();
+//! assert!(report.rows_after < report.rows_before);
+//! println!("{report}");
+//! ```
+
+use std::fmt;
+
+use crate::ast::{estimate_constraint_rows, CircuitIR, ComparisonOp, Constraint, ZkField};
+use crate::circuit::merkle_index_fields;
+use crate::error::Result;
+use crate::layout::measure_rows;
+
+/// A single rewrite over the IR (see the [module docs](self)).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Pass {
+    Dedupe,
+    DropRedundantBooleans,
+    SubsumeRanges,
+}
+
+impl Pass {
+    /// Every pass, in the order [`optimize`] runs them.
+    pub const ALL: [Pass; 3] = [Pass::Dedupe, Pass::DropRedundantBooleans, Pass::SubsumeRanges];
+
+    /// Short name used in reports.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Pass::Dedupe => "dedupe",
+            Pass::DropRedundantBooleans => "drop-redundant-booleans",
+            Pass::SubsumeRanges => "subsume-ranges",
+        }
+    }
+
+    /// Applies the pass in place, returning the number of constraints it removed.
+    fn run(&self, ir: &mut CircuitIR) -> usize {
+        let before = num_constraints(ir);
+        match self {
+            Pass::Dedupe => dedupe(ir),
+            Pass::DropRedundantBooleans => drop_redundant_booleans(ir),
+            Pass::SubsumeRanges => ir.private_witnesses.iter_mut().for_each(subsume_ranges),
+        }
+        before - num_constraints(ir)
+    }
+}
+
+/// Row counts and per-pass effect of an optimization run.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OptimizationReport {
+    /// Measured rows before the passes ran.
+    pub rows_before: usize,
+    /// Measured rows after the passes ran.
+    pub rows_after: usize,
+    /// Minimal `k` before the passes ran.
+    pub k_before: u32,
+    /// Minimal `k` after the passes ran.
+    pub k_after: u32,
+    /// Constraints removed by each pass, in the order the passes ran.
+    pub removed: Vec<(Pass, usize)>,
+}
+
+impl OptimizationReport {
+    /// Rows saved by the passes.
+    pub fn rows_saved(&self) -> usize {
+        self.rows_before.saturating_sub(self.rows_after)
+    }
+}
+
+impl fmt::Display for OptimizationReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "rows: {} -> {} (k {} -> {})",
+            self.rows_before, self.rows_after, self.k_before, self.k_after
+        )?;
+        for (pass, removed) in &self.removed {
+            writeln!(f, "  {}: {} constraint(s) removed", pass.name(), removed)?;
+        }
+        Ok(())
+    }
+}
+
+/// Runs every [`Pass`] on `ir` and recomputes its `k`.
+///
+/// # Errors
+///
+/// Returns the [`measure_rows`] error if the circuit cannot be synthesized.
+pub fn optimize(ir: &mut CircuitIR) -> Result<OptimizationReport> {
+    optimize_with(ir, &Pass::ALL)
+}
+
+/// Runs `passes` on `ir`, in order, and recomputes its `k`.
+///
+/// # Errors
+///
+/// Returns the [`measure_rows`] error if the circuit cannot be synthesized.
+pub fn optimize_with(ir: &mut CircuitIR, passes: &[Pass]) -> Result<OptimizationReport> {
+    let before = measure_rows(ir)?;
+    let removed = passes.iter().map(|pass| (*pass, pass.run(ir))).collect();
+    ir.recompute_config()?;
+    let after = measure_rows(ir)?;
+    Ok(OptimizationReport {
+        rows_before: before.used_rows,
+        rows_after: after.used_rows,
+        k_before: before.min_k,
+        k_after: after.min_k,
+        removed,
+    })
+}
+
+fn num_constraints(ir: &CircuitIR) -> usize {
+    let per_field: usize = ir.private_witnesses.iter().map(|f| f.constraints.len()).sum();
+    per_field + ir.inter_field_constraints.len()
+}
+
+fn dedupe(ir: &mut CircuitIR) {
+    for field in &mut ir.private_witnesses {
+        dedupe_vec(&mut field.constraints);
+    }
+    dedupe_vec(&mut ir.inter_field_constraints);
+}
+
+/// Removes later duplicates, keeping the first occurrence in place.
+fn dedupe_vec<T: PartialEq>(items: &mut Vec<T>) {
+    let mut kept: Vec<T> = Vec::with_capacity(items.len());
+    for item in items.drain(..) {
+        if !kept.contains(&item) {
+            kept.push(item);
+        }
+    }
+    *items = kept;
+}
+
+fn drop_redundant_booleans(ir: &mut CircuitIR) {
+    let indices: Vec<String> = merkle_index_fields(ir).into_iter().map(str::to_string).collect();
+    for field in &mut ir.private_witnesses {
+        if indices.contains(&field.name) {
+            field.constraints.retain(|c| !matches!(c, Constraint::Boolean));
+        }
+        if field.constraints.contains(&Constraint::Boolean) {
+            // {0, 1} already lies inside any of these
+            field.constraints.retain(|c| match c {
+                Constraint::Range { .. } => false,
+                Constraint::RangeProof { min, max } => !(*min == 0 && *max >= 1),
+                _ => true,
+            });
+        }
+    }
+}
+
+/// Inclusive bounds implied by a set of element-wise constraints.
+#[derive(Clone, Copy)]
+struct Bounds {
+    min: u128,
+    max: u128,
+}
+
+impl Bounds {
+    /// `[0, 2^num_bits)`; `None` past 128 bits, which the bounds cannot represent.
+    fn of_bits(num_bits: usize) -> Option<Bounds> {
+        let max = match num_bits {
+            128 => u128::MAX,
+            0..128 => (1 << num_bits) - 1,
+            _ => return None,
+        };
+        Some(Bounds { min: 0, max })
+    }
+
+    fn intersect(self, other: Bounds) -> Bounds {
+        Bounds { min: self.min.max(other.min), max: self.max.min(other.max) }
+    }
+
+    /// Tightens the bounds with `value <op> threshold`; `None` for ops that are not a bound.
+    fn with_comparison(self, op: ComparisonOp, threshold: u128) -> Option<Bounds> {
+        let bound = match op {
+            ComparisonOp::GreaterThan => Bounds { min: threshold.checked_add(1)?, max: u128::MAX },
+            ComparisonOp::GreaterThanOrEqual => Bounds { min: threshold, max: u128::MAX },
+            ComparisonOp::LessThan => Bounds { min: 0, max: threshold.checked_sub(1)? },
+            ComparisonOp::LessThanOrEqual => Bounds { min: 0, max: threshold },
+            ComparisonOp::Equal => Bounds { min: threshold, max: threshold },
+            ComparisonOp::NotEqual => return None,
+        };
+        Some(self.intersect(bound))
+    }
+
+    fn is_empty(&self) -> bool {
+        self.min > self.max
+    }
+}
+
+fn subsume_ranges(field: &mut ZkField) {
+    // Keep only the narrowest bit range
+    let narrowest = field
+        .constraints
+        .iter()
+        .filter_map(|c| match c {
+            Constraint::Range { num_bits } => Some(*num_bits),
+            _ => None,
+        })
+        .min();
+    if let Some(bits) = narrowest {
+        let mut kept = false;
+        field.constraints.retain(|c| match c {
+            Constraint::Range { num_bits } if *num_bits == bits && !kept => {
+                kept = true;
+                true
+            }
+            Constraint::Range { .. } => false,
+            _ => true,
+        });
+    }
+
+    // Intersect bounded ranges into the first one
+    let ranges: Vec<Bounds> = field
+        .constraints
+        .iter()
+        .filter_map(|c| match c {
+            Constraint::RangeProof { min, max } => Some(Bounds { min: *min, max: *max }),
+            _ => None,
+        })
+        .collect();
+    let bounded = ranges.iter().copied().reduce(Bounds::intersect);
+    if let Some(bounds) = bounded.filter(|b| !b.is_empty()) {
+        if ranges.len() > 1 {
+            replace_first(
+                field,
+                is_range_proof,
+                Constraint::RangeProof { min: bounds.min, max: bounds.max },
+            );
+        }
+        // A bounded range below 2^bits makes the bit range redundant
+        let implied = narrowest.and_then(Bounds::of_bits).is_none_or(|b| bounds.max <= b.max);
+        if narrowest.is_some() && implied {
+            field.constraints.retain(|c| !matches!(c, Constraint::Range { .. }));
+        }
+    }
+
+    fold_comparisons(field);
+}
+
+/// Replaces the bit/bounded range and the literal comparisons of `field` with one bounded
+/// range, if that is estimated to take fewer rows.
+fn fold_comparisons(field: &mut ZkField) {
+    let foldable = |c: &Constraint| match c {
+        Constraint::Range { .. } | Constraint::RangeProof { .. } => true,
+        Constraint::Comparison { operator, .. } => *operator != ComparisonOp::NotEqual,
+        _ => false,
+    };
+
+    // Folding is only sound when the value is already known to be bounded
+    let mut bounds: Option<Bounds> = None;
+    for constraint in &field.constraints {
+        let range = match constraint {
+            Constraint::Range { num_bits } => match Bounds::of_bits(*num_bits) {
+                Some(range) => range,
+                None => return,
+            },
+            Constraint::RangeProof { min, max } => Bounds { min: *min, max: *max },
+            _ => continue,
+        };
+        bounds = Some(bounds.map_or(range, |b| b.intersect(range)));
+    }
+    let Some(mut bounds) = bounds else {
+        return;
+    };
+    let mut comparisons = 0;
+    for constraint in &field.constraints {
+        if let Constraint::Comparison { operator, value } = constraint {
+            if let Some(tightened) = bounds.with_comparison(*operator, *value) {
+                bounds = tightened;
+                comparisons += 1;
+            }
+        }
+    }
+    if comparisons == 0 || bounds.is_empty() {
+        return;
+    }
+
+    let folded = Constraint::RangeProof { min: bounds.min, max: bounds.max };
+    let current_rows: usize =
+        field.constraints.iter().filter(|c| foldable(c)).map(estimate_constraint_rows).sum();
+    if estimate_constraint_rows(&folded) < current_rows {
+        replace_first(field, foldable, folded);
+    }
+}
+
+fn is_range_proof(constraint: &Constraint) -> bool {
+    matches!(constraint, Constraint::RangeProof { .. })
+}
+
+/// Replaces every constraint matching `pred` with `replacement`, placed where the first
+/// match was.
+fn replace_first(field: &mut ZkField, pred: impl Fn(&Constraint) -> bool, replacement: Constraint) {
+    let Some(position) = field.constraints.iter().position(&pred) else {
+        return;
+    };
+    field.constraints.retain(|c| !pred(c));
+    field.constraints.insert(position, replacement);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{parse_contract, transform_to_ir, CircuitBuilder};
+    use halo2_proofs::dev::MockProver;
+    use halo2curves::pasta::Fp;
+
+    fn ir_for(source: &str) -> CircuitIR {
+        transform_to_ir(parse_contract(source).unwrap()).unwrap()
+    }
+
+    fn accepts(ir: &CircuitIR, witnesses: Vec<u64>) -> bool {
+        let circuit = CircuitBuilder::new(ir.clone())
+            .build::<Fp>()
+            .with_witnesses(witnesses.into_iter().map(Fp::from).collect())
+            .unwrap();
+        let prover = MockProver::run(ir.circuit_config.k(), &circuit, vec![vec![]]).unwrap();
+        prover.verify().is_ok()
+    }
+
+    const BOUNDED: &str = r#"
+        struct Bounded {
+            #[zk_private(range = "0..1000", constraint = "value >= 10", constraint = "value != 500")]
+            amount: u64,
+        }
+    "#;
+
+    #[test]
+    fn test_folds_range_and_comparisons() {
+        let mut ir = ir_for(BOUNDED);
+        let report = optimize(&mut ir).unwrap();
+        assert_eq!(
+            ir.private_witnesses[0].constraints,
+            vec![
+                Constraint::RangeProof { min: 10, max: 999 },
+                Constraint::Comparison { operator: ComparisonOp::NotEqual, value: 500 },
+            ]
+        );
+        assert!(report.rows_after < report.rows_before, "{report}");
+        assert_eq!(report.k_after, ir.circuit_config.k());
+    }
+
+    #[test]
+    fn test_optimized_circuit_keeps_semantics() {
+        let original = ir_for(BOUNDED);
+        let mut optimized = original.clone();
+        optimize(&mut optimized).unwrap();
+        for value in [0, 9, 10, 11, 499, 500, 501, 999, 1000, u64::MAX] {
+            assert_eq!(
+                accepts(&original, vec![value]),
+                accepts(&optimized, vec![value]),
+                "value = {value}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_dedupe_and_narrowest_range() {
+        let mut ir = ir_for(
+            r#"
+            struct Twice {
+                #[zk_private(constraint = "value > 3", constraint = "value > 3")]
+                a: u64,
+            }
+        "#,
+        );
+        ir.private_witnesses[0].constraints.push(Constraint::Range { num_bits: 32 });
+        let dup = ir.inter_field_constraints.clone();
+        ir.inter_field_constraints.extend(dup);
+
+        let report = optimize_with(&mut ir, &[Pass::Dedupe, Pass::SubsumeRanges]).unwrap();
+        assert_eq!(report.removed[0], (Pass::Dedupe, 1));
+        // Range{64} is implied by Range{32}, which then absorbs the comparison
+        assert_eq!(
+            ir.private_witnesses[0].constraints,
+            vec![Constraint::RangeProof { min: 4, max: u32::MAX as u128 }]
+        );
+    }
+
+    #[test]
+    fn test_drops_booleans_checked_by_merkle_gate() {
+        let mut ir = ir_for(
+            r#"
+            struct Membership {
+                #[zk_private(constraint = "merkle_member(value, root, siblings, indices)")]
+                leaf: Field,
+                #[zk_private]
+                root: Field,
+                #[zk_private]
+                siblings: [Field; 2],
+                #[zk_private]
+                indices: [bool; 2],
+                #[zk_private]
+                flag: bool,
+            }
+        "#,
+        );
+        ir.private_witnesses[4].constraints.push(Constraint::Range { num_bits: 8 });
+        let report = optimize(&mut ir).unwrap();
+        assert!(ir.private_witnesses[3].constraints.is_empty());
+        assert_eq!(ir.private_witnesses[4].constraints, vec![Constraint::Boolean]);
+        assert_eq!(report.removed[1], (Pass::DropRedundantBooleans, 2));
+    }
+
+    #[test]
+    fn test_leaves_unsatisfiable_bounds_alone() {
+        let mut ir = ir_for(
+            r#"
+            struct Empty {
+                #[zk_private(range = "0..10", constraint = "value > 20")]
+                a: u64,
+            }
+        "#,
+        );
+        let before = ir.private_witnesses[0].constraints.clone();
+        optimize_with(&mut ir, &[Pass::SubsumeRanges]).unwrap();
+        // The 64-bit range is still implied by the bounded range; the comparison stays
+        assert_eq!(ir.private_witnesses[0].constraints, before[1..].to_vec());
+    }
+}
@@ -1,7 +1,54 @@
 //! Parser for extracting `#[zk_private]` annotations from Rust code
+//!
+//! Besides the bare `#[zk_private]` marker, struct fields accept the same argument
+//! grammar as the exporter's function-parameter attribute:
+//!
+//! ```text
+//! #[zk_private(
+//!     commit = "poseidon",
+//!     randomness = "blinding",
+//!     range = "150..=300",
+//!     constraint = "value > 100",
+//!     constraint = "value <= limit",
+//!     constraint = "merkle_member(value, root, siblings, indices)",
+//! )]
+//! ```
+//!
+//! Comparison right-hand sides are either integer literals (lowered to
+//! [`Constraint::Comparison`]) or the name of another `#[zk_private]` field
+//! (lowered to [`InterFieldConstraint::ComparisonCheck`] by `transform_to_ir`).
+//!
+//! Other comparisons are expression checks ([`InterFieldConstraint::ExpressionCheck`]):
+//! both sides are built from field names (`value` is the annotated field), unsigned integer
+//! literals, `+`, `-`, `*` and parentheses, and several comparisons may be joined by `&&`:
+//!
+//! ```text
+//! #[zk_private(constraint = "value * 100 >= debt * 150 && value - debt < limit")]
+//! ```
+//!
+//! On `i64` fields, range bounds and comparison literals may be negative; they are stored
+//! offset-encoded (see [`encode_i64`]) to match the witness encoding of signed values.
+//!
+//! `commit` together with `randomness` (the name of another `#[zk_private]` field) lowers to
+//! [`InterFieldConstraint::CommitmentVerification`] against a public input named
+//! `<field>_commitment`. Without `randomness`, `commit` only records the scheme.
+//!
+//! Errors in a struct or its annotations carry the source location of the offending item
+//! (see [`crate::diagnostic`]); each [`PrivateField`] keeps its own so that `transform_to_ir`
+//! can locate later errors too.
 
+use crate::ast::{
+    encode_i64, field_zk_type, ArithExpr, ArithOp, ComparisonOp, Constraint, HashType,
+    InterFieldConstraint,
+};
+use crate::diagnostic::SourceSpan;
 use crate::error::{CompilerError, Result};
-use syn::{Attribute, Field, Fields, Item, ItemStruct};
+use quote::ToTokens;
+use syn::spanned::Spanned;
+use syn::{
+    Attribute, BinOp, Expr, ExprBinary, ExprCall, ExprRange, Field, Fields, Item, ItemStruct, Lit,
+    Meta, RangeLimits,
+};
 
 /// A field annotated with `#[zk_private]` in the source contract.
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -10,6 +57,14 @@ pub struct PrivateField {
     pub name: String,
     /// Rust type as a string (e.g. `"u64"`, `"[u8; 32]"`).
     pub field_type: String,
+    /// Constraints declared through `#[zk_private(...)]` arguments, in source order.
+    ///
+    /// `transform_to_ir` appends these after the type's default constraints.
+    pub constraints: Vec<Constraint>,
+    /// Field-to-field relations declared through `#[zk_private(...)]` arguments.
+    pub inter_field_constraints: Vec<InterFieldConstraint>,
+    /// Location of the `name: Type` declaration; `None` for fields built by hand.
+    pub span: Option<SourceSpan>,
 }
 
 /// Result of parsing a Rust struct with `#[zk_private]` annotations.
@@ -21,9 +76,12 @@ pub struct ParsedContract {
     pub private_fields: Vec<PrivateField>,
 }
 
-/// Parse a Rust source string and extract the first struct with `#[zk_private]` fields.
+/// Parse a Rust source string and extract its first top-level struct.
+///
+/// The struct is returned even if none of its fields carry `#[zk_private]`. Use
+/// [`parse_contracts`] for files that declare several private state structs.
 pub fn parse_contract(input: &str) -> Result<ParsedContract> {
-    let ast: syn::File = syn::parse_str(input)?;
+    let ast = parse_file(input)?;
 
     let item_struct = ast
         .items
@@ -34,10 +92,82 @@ pub fn parse_contract(input: &str) -> Result<ParsedContract> {
         })
         .ok_or_else(|| CompilerError::ParseError("No struct found in input".to_string()))?;
 
-    parse_struct(item_struct)
+    parse_struct(item_struct, input)
 }
 
-fn parse_struct(item_struct: &ItemStruct) -> Result<ParsedContract> {
+/// Parse a Rust source string and extract every struct with at least one `#[zk_private]` field.
+///
+/// Structs nested in inline `mod` blocks are included. Results follow source order.
+///
+/// # Errors
+///
+/// Returns [`CompilerError::ParseError`] if no struct carries a `#[zk_private]` field (the
+/// message lists the structs that were skipped), or if two annotated structs share a name.
+pub fn parse_contracts(input: &str) -> Result<Vec<ParsedContract>> {
+    let ast = parse_file(input)?;
+
+    let mut structs = Vec::new();
+    collect_structs(&ast.items, "", &mut structs);
+
+    let mut contracts: Vec<ParsedContract> = Vec::new();
+    let mut skipped = Vec::new();
+    for (path, item_struct) in structs {
+        let parsed = parse_struct(item_struct, input)?;
+        if parsed.private_fields.is_empty() {
+            skipped.push(path);
+            continue;
+        }
+        if contracts.iter().any(|c| c.contract_name == parsed.contract_name) {
+            let span = SourceSpan::from_span(item_struct.ident.span(), input);
+            return Err(CompilerError::ParseError(format!(
+                "Duplicate #[zk_private] struct name '{}' (found again at '{}')",
+                parsed.contract_name, path
+            ))
+            .at_with_help(Some(&span), "rename one of the structs or move it out of this file"));
+        }
+        contracts.push(parsed);
+    }
+
+    if contracts.is_empty() {
+        let detail = if skipped.is_empty() {
+            "no structs were found".to_string()
+        } else {
+            format!("skipped structs without #[zk_private] fields: {}", skipped.join(", "))
+        };
+        return Err(CompilerError::ParseError(format!(
+            "No struct with #[zk_private] fields found in input ({})",
+            detail
+        )));
+    }
+
+    Ok(contracts)
+}
+
+fn parse_file(input: &str) -> Result<syn::File> {
+    syn::parse_str(input).map_err(|e| {
+        let span = SourceSpan::from_span(e.span(), input);
+        CompilerError::SynError(e).at(Some(&span))
+    })
+}
+
+/// Collects structs in source order, descending into inline `mod` blocks.
+///
+/// Each struct is paired with its module-qualified path (e.g. `"vault::Position"`).
+fn collect_structs<'a>(items: &'a [Item], prefix: &str, out: &mut Vec<(String, &'a ItemStruct)>) {
+    for item in items {
+        match item {
+            Item::Struct(s) => out.push((format!("{}{}", prefix, s.ident), s)),
+            Item::Mod(m) => {
+                if let Some((_, content)) = &m.content {
+                    collect_structs(content, &format!("{}{}::", prefix, m.ident), out);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+fn parse_struct(item_struct: &ItemStruct, source: &str) -> Result<ParsedContract> {
     let contract_name = item_struct.ident.to_string();
     let mut private_fields = Vec::new();
 
@@ -51,8 +181,23 @@ fn parse_struct(item_struct: &ItemStruct) -> Result<ParsedContract> {
                     .to_string();
 
                 let field_type = extract_type_name(field)?;
+                let span = field.ident.as_ref().map(|ident| {
+                    SourceSpan::from_span(ident.span(), source)
+                        .to(&SourceSpan::from_span(field.ty.span(), source))
+                });
 
-                private_fields.push(PrivateField { name: field_name, field_type });
+                let mut private_field = PrivateField {
+                    name: field_name,
+                    field_type,
+                    constraints: vec![],
+                    inter_field_constraints: vec![],
+                    span,
+                };
+                for attr in field.attrs.iter().filter(|a| is_zk_private(a)) {
+                    parse_zk_private_args(attr, &mut private_field, &fields.named, source)?;
+                }
+
+                private_fields.push(private_field);
             }
         }
     }
@@ -61,17 +206,381 @@ fn parse_struct(item_struct: &ItemStruct) -> Result<ParsedContract> {
 }
 
 fn has_zk_private_attribute(attrs: &[Attribute]) -> bool {
-    attrs.iter().any(|attr| attr.path().segments.iter().any(|seg| seg.ident == "zk_private"))
+    attrs.iter().any(is_zk_private)
 }
 
-fn extract_type_name(field: &Field) -> Result<String> {
-    use quote::ToTokens;
+fn is_zk_private(attr: &Attribute) -> bool {
+    attr.path().segments.iter().any(|seg| seg.ident == "zk_private")
+}
 
+fn extract_type_name(field: &Field) -> Result<String> {
     let type_tokens = field.ty.to_token_stream().to_string();
 
     Ok(type_tokens.replace(' ', ""))
 }
 
+/// Parses the arguments of one `#[zk_private(...)]` attribute into `field`.
+///
+/// A bare `#[zk_private]` carries no arguments and leaves `field` untouched.
+fn parse_zk_private_args<'a>(
+    attr: &Attribute,
+    field: &mut PrivateField,
+    struct_fields: impl IntoIterator<Item = &'a Field> + Clone,
+    source: &str,
+) -> Result<()> {
+    if !matches!(attr.meta, Meta::List(_)) {
+        return Ok(());
+    }
+
+    let field_name = field.name.clone();
+    let signed = field_zk_type(field)?.is_signed();
+    let mut commit_scheme = None;
+    let mut randomness = None;
+    attr.parse_nested_meta(|meta| {
+        let key = meta
+            .path
+            .get_ident()
+            .ok_or_else(|| meta.error("expected identifier key inside #[zk_private(...)]"))?
+            .to_string();
+        let value = meta.value()?;
+        let lit: Lit = value.parse()?;
+        let Lit::Str(s) = lit else {
+            return Err(meta.error("expected string literal value"));
+        };
+        let raw = s.value();
+        // Errors in the argument's value point at the string literal
+        let value_error = |message: String| syn::Error::new(s.span(), message);
+        match key.as_str() {
+            "commit" => {
+                let hash_type = match raw.as_str() {
+                    "poseidon" => HashType::Poseidon,
+                    "pedersen" => HashType::Pedersen,
+                    other => return Err(value_error(format!("unknown commit scheme '{other}'"))),
+                };
+                commit_scheme = Some(hash_type.clone());
+                field.constraints.push(Constraint::Commitment { hash_type });
+            }
+            "randomness" => {
+                randomness = Some(raw);
+            }
+            "range" => {
+                let (min, max) = parse_range(&raw, signed).map_err(value_error)?;
+                field.constraints.push(Constraint::RangeProof { min, max });
+            }
+            "constraint" => {
+                if raw.trim_start().starts_with("merkle_member") {
+                    let constraint = parse_merkle_member(&raw, &field_name, struct_fields.clone())
+                        .map_err(value_error)?;
+                    field.constraints.push(constraint);
+                } else {
+                    let checks =
+                        parse_constraint(&raw, &field_name, signed).map_err(value_error)?;
+                    for check in checks {
+                        match check {
+                            ParsedCheck::Literal(operator, value) => {
+                                field.constraints.push(Constraint::Comparison { operator, value });
+                            }
+                            ParsedCheck::Field(op, right_field) => {
+                                field.inter_field_constraints.push(
+                                    InterFieldConstraint::ComparisonCheck {
+                                        left_field: field_name.clone(),
+                                        right_field,
+                                        op,
+                                    },
+                                );
+                            }
+                            ParsedCheck::Expression(lhs, op, rhs) => {
+                                field.inter_field_constraints.push(
+                                    InterFieldConstraint::ExpressionCheck { lhs, op, rhs },
+                                );
+                            }
+                        }
+                    }
+                }
+            }
+            other => {
+                return Err(meta.error(format!(
+                    "unknown #[zk_private] attribute key '{other}' (expected: commit, randomness, range, constraint)"
+                )));
+            }
+        }
+        Ok(())
+    })
+    .map_err(|e| {
+        let span = SourceSpan::from_span(e.span(), source);
+        CompilerError::InvalidAnnotation(format!("field '{}': {}", field_name, e)).at(Some(&span))
+    })?;
+
+    match (commit_scheme, randomness) {
+        (Some(hash_type), Some(randomness)) => {
+            field.inter_field_constraints.push(InterFieldConstraint::CommitmentVerification {
+                commitment: format!("{}_commitment", field_name),
+                value: field_name,
+                randomness,
+                hash_type,
+            });
+            Ok(())
+        }
+        (None, Some(_)) => Err(CompilerError::InvalidAnnotation(format!(
+            "field '{}': `randomness` requires `commit` in the same #[zk_private(...)] attribute",
+            field_name
+        ))
+        .at_with_help(
+            Some(&SourceSpan::from_span(attr.span(), source)),
+            "add `commit = \"poseidon\"` to this attribute",
+        )),
+        _ => Ok(()),
+    }
+}
+
+/// Parses `"low..high"` / `"low..=high"` into inclusive `(min, max)` bounds.
+///
+/// With `signed`, bounds are `i64` literals and are returned offset-encoded.
+fn parse_range(raw: &str, signed: bool) -> std::result::Result<(u128, u128), String> {
+    let expr: ExprRange = syn::parse_str(raw).map_err(|e| format!("invalid range '{raw}': {e}"))?;
+    let low =
+        expr.start.as_deref().ok_or_else(|| format!("range '{raw}' must have a lower bound"))?;
+    let high =
+        expr.end.as_deref().ok_or_else(|| format!("range '{raw}' must have an upper bound"))?;
+    let min = eval_bound(low, signed).ok_or_else(|| {
+        format!("range lower bound '{}' must be an integer literal", low.to_token_stream())
+    })?;
+    let high_value = eval_bound(high, signed).ok_or_else(|| {
+        format!("range upper bound '{}' must be an integer literal", high.to_token_stream())
+    })?;
+    let max = match expr.limits {
+        RangeLimits::Closed(_) => high_value,
+        RangeLimits::HalfOpen(_) => {
+            high_value.checked_sub(1).ok_or_else(|| format!("range '{raw}' is empty"))?
+        }
+    };
+    if min > max {
+        return Err(format!("range '{raw}' is empty"));
+    }
+    Ok((min, max))
+}
+
+enum ParsedCheck {
+    Literal(ComparisonOp, u128),
+    Field(ComparisonOp, String),
+    Expression(ArithExpr, ComparisonOp, ArithExpr),
+}
+
+/// Parses a `constraint` argument: comparisons joined by `&&`.
+///
+/// `value <op> literal` and `value <op> other_field` keep their dedicated lowerings; any other
+/// comparison becomes an expression check, with `value` standing for the annotated field.
+/// With `signed`, a literal compared directly against `value` is an `i64` and is returned
+/// offset-encoded.
+fn parse_constraint(
+    raw: &str,
+    field_name: &str,
+    signed: bool,
+) -> std::result::Result<Vec<ParsedCheck>, String> {
+    let expr: Expr = syn::parse_str(raw).map_err(|e| format!("invalid constraint '{raw}': {e}"))?;
+    let mut comparisons = Vec::new();
+    split_conjunction(&expr, &mut comparisons);
+    comparisons.into_iter().map(|c| parse_comparison(raw, c, field_name, signed)).collect()
+}
+
+fn split_conjunction<'e>(expr: &'e Expr, out: &mut Vec<&'e Expr>) {
+    match unparen(expr) {
+        Expr::Binary(ExprBinary { left, op: BinOp::And(_), right, .. }) => {
+            split_conjunction(left, out);
+            split_conjunction(right, out);
+        }
+        other => out.push(other),
+    }
+}
+
+fn unparen(mut expr: &Expr) -> &Expr {
+    while let Expr::Paren(p) = expr {
+        expr = &p.expr;
+    }
+    expr
+}
+
+fn parse_comparison(
+    raw: &str,
+    expr: &Expr,
+    field_name: &str,
+    signed: bool,
+) -> std::result::Result<ParsedCheck, String> {
+    let Expr::Binary(ExprBinary { left, op, right, .. }) = expr else {
+        return Err(format!("constraint '{raw}' must be a comparison (LHS op RHS)"));
+    };
+    let operator = match op {
+        BinOp::Ge(_) => ComparisonOp::GreaterThanOrEqual,
+        BinOp::Gt(_) => ComparisonOp::GreaterThan,
+        BinOp::Le(_) => ComparisonOp::LessThanOrEqual,
+        BinOp::Lt(_) => ComparisonOp::LessThan,
+        BinOp::Eq(_) => ComparisonOp::Equal,
+        BinOp::Ne(_) => ComparisonOp::NotEqual,
+        _ => {
+            return Err(format!(
+                "constraint '{raw}': unsupported operator '{}' (use >=, >, <=, <, ==, !=, \
+                 joined by &&)",
+                op.to_token_stream()
+            ))
+        }
+    };
+    if is_value(left) {
+        if let Some(value) = eval_bound(right, signed) {
+            return Ok(ParsedCheck::Literal(operator, value));
+        }
+        if eval_signed_integer(right).is_some() {
+            return Err(format!("constraint '{raw}': bound is out of range for the field's type"));
+        }
+        if let Expr::Path(p) = right.as_ref() {
+            if let Some(ident) = p.path.get_ident().filter(|i| *i != "value") {
+                return Ok(ParsedCheck::Field(operator, ident.to_string()));
+            }
+        }
+    }
+    let lhs = parse_arith(left, field_name).map_err(|e| format!("constraint '{raw}': {e}"))?;
+    let rhs = parse_arith(right, field_name).map_err(|e| format!("constraint '{raw}': {e}"))?;
+    Ok(ParsedCheck::Expression(lhs, operator, rhs))
+}
+
+fn is_value(expr: &Expr) -> bool {
+    matches!(expr, Expr::Path(p) if p.path.is_ident("value"))
+}
+
+/// Parses one side of an expression check: field names (`value` is the annotated field),
+/// unsigned integer literals, `+`, `-`, `*` and parentheses.
+fn parse_arith(expr: &Expr, field_name: &str) -> std::result::Result<ArithExpr, String> {
+    if let Some(value) = eval_integer(expr) {
+        return Ok(ArithExpr::constant(value));
+    }
+    match expr {
+        Expr::Paren(p) => parse_arith(&p.expr, field_name),
+        Expr::Path(p) if p.path.is_ident("value") => Ok(ArithExpr::field(field_name)),
+        Expr::Path(p) if p.path.get_ident().is_some() => {
+            Ok(ArithExpr::field(p.path.to_token_stream().to_string()))
+        }
+        Expr::Binary(ExprBinary { left, op, right, .. }) => {
+            let op = match op {
+                BinOp::Add(_) => ArithOp::Add,
+                BinOp::Sub(_) => ArithOp::Sub,
+                BinOp::Mul(_) => ArithOp::Mul,
+                BinOp::Div(_) => {
+                    return Err(
+                        "division is not supported (field division does not truncate)".to_string()
+                    )
+                }
+                other => {
+                    return Err(format!(
+                        "unsupported operator '{}' in an arithmetic expression (use +, -, *)",
+                        other.to_token_stream()
+                    ))
+                }
+            };
+            let (lhs, rhs) = (parse_arith(left, field_name)?, parse_arith(right, field_name)?);
+            Ok(match op {
+                ArithOp::Add => lhs + rhs,
+                ArithOp::Sub => lhs - rhs,
+                _ => lhs * rhs,
+            })
+        }
+        other => Err(format!(
+            "unsupported term '{}' (expected a field name or an unsigned integer literal)",
+            other.to_token_stream()
+        )),
+    }
+}
+
+/// Parses `"merkle_member(value, root, siblings, indices)"`.
+///
+/// The tree depth is the length of the `siblings` array field declared in the same struct.
+fn parse_merkle_member<'a>(
+    raw: &str,
+    field_name: &str,
+    struct_fields: impl IntoIterator<Item = &'a Field>,
+) -> std::result::Result<Constraint, String> {
+    let expr: ExprCall =
+        syn::parse_str(raw).map_err(|e| format!("invalid merkle_member call '{raw}': {e}"))?;
+    let func_name = expr.func.to_token_stream().to_string();
+    if func_name.trim() != "merkle_member" {
+        return Err(format!("expected 'merkle_member' function, got '{func_name}'"));
+    }
+    if expr.args.len() != 4 {
+        return Err(format!(
+            "merkle_member expects 4 args (value, root, siblings, indices); got {}",
+            expr.args.len()
+        ));
+    }
+    let args: Vec<String> = expr.args.iter().map(|a| a.to_token_stream().to_string()).collect();
+    if args[0].trim() != "value" {
+        return Err(format!("merkle_member first arg must be 'value'; got '{}'", args[0]));
+    }
+
+    let path_field = &args[2];
+    let tree_depth = struct_fields
+        .into_iter()
+        .find(|f| f.ident.as_ref().is_some_and(|i| i == path_field))
+        .and_then(|f| match &f.ty {
+            syn::Type::Array(array) => eval_integer(&array.len),
+            _ => None,
+        })
+        .ok_or_else(|| {
+            format!("merkle_member siblings '{path_field}' must be a fixed-size array field of the struct")
+        })?;
+
+    Ok(Constraint::MerkleProof {
+        leaf_field: field_name.to_string(),
+        path_field: path_field.clone(),
+        root_field: args[1].clone(),
+        indices_field: args[3].clone(),
+        tree_depth: tree_depth as usize,
+    })
+}
+
+/// Evaluates a bound literal; `signed` bounds must fit in `i64` and are offset-encoded.
+fn eval_bound(expr: &Expr, signed: bool) -> Option<u128> {
+    if signed {
+        let value = i64::try_from(eval_signed_integer(expr)?).ok()?;
+        Some(u128::from(encode_i64(value)))
+    } else {
+        eval_integer(expr)
+    }
+}
+
+fn eval_signed_integer(expr: &Expr) -> Option<i128> {
+    match expr {
+        Expr::Unary(unary) if matches!(unary.op, syn::UnOp::Neg(_)) => {
+            eval_signed_integer(&unary.expr)?.checked_neg()
+        }
+        Expr::Path(p) => match p.path.to_token_stream().to_string().replace(' ', "").as_str() {
+            "i64::MIN" => Some(i64::MIN as i128),
+            "i64::MAX" => Some(i64::MAX as i128),
+            _ => eval_integer(expr).and_then(|v| i128::try_from(v).ok()),
+        },
+        Expr::Paren(p) => eval_signed_integer(&p.expr),
+        _ => eval_integer(expr).and_then(|v| i128::try_from(v).ok()),
+    }
+}
+
+/// Evaluates an integer literal (optionally with `_` separators or a type suffix)
+/// or one of the `uN::MAX` constants.
+fn eval_integer(expr: &Expr) -> Option<u128> {
+    match expr {
+        Expr::Lit(lit) => match &lit.lit {
+            Lit::Int(int) => int.base10_parse::<u128>().ok(),
+            _ => None,
+        },
+        Expr::Path(p) => match p.path.to_token_stream().to_string().replace(' ', "").as_str() {
+            "u8::MAX" => Some(u8::MAX as u128),
+            "u16::MAX" => Some(u16::MAX as u128),
+            "u32::MAX" => Some(u32::MAX as u128),
+            "u64::MAX" => Some(u64::MAX as u128),
+            "u128::MAX" => Some(u128::MAX),
+            _ => None,
+        },
+        Expr::Paren(p) => eval_integer(&p.expr),
+        _ => None,
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1,8 +1,9 @@
 //! Tests for the AST transformation module
 
 use zerostyl_compiler::{
-    parse_contract, transform_to_ir, ArithOp, CircuitIR, ComparisonOp, Constraint,
-    InterFieldConstraint, ParsedContract, PrivateField, ZkField, ZkType,
+    parse_contract, parse_contracts, transform_all_to_ir, transform_to_ir, ArithOp, CircuitIR,
+    ComparisonOp, CompilerError, Constraint, HashType, InterFieldConstraint, ParsedContract,
+    PrivateField, ZkField, ZkType,
 };
 use zerostyl_runtime::CircuitConfig;
 
@@ -17,6 +18,9 @@ fn test_transform_simple_u64_field() {
         private_fields: vec![PrivateField {
             name: "balance".to_string(),
             field_type: "u64".to_string(),
+            constraints: vec![],
+            inter_field_constraints: vec![],
+            span: None,
         }],
     };
 
@@ -32,9 +36,27 @@ fn test_transform_multiple_fields() {
     let parsed = ParsedContract {
         contract_name: "MultiFieldContract".to_string(),
         private_fields: vec![
-            PrivateField { name: "amount".to_string(), field_type: "u64".to_string() },
-            PrivateField { name: "total".to_string(), field_type: "u128".to_string() },
-            PrivateField { name: "hash".to_string(), field_type: "[u8;32]".to_string() },
+            PrivateField {
+                name: "amount".to_string(),
+                field_type: "u64".to_string(),
+                constraints: vec![],
+                inter_field_constraints: vec![],
+                span: None,
+            },
+            PrivateField {
+                name: "total".to_string(),
+                field_type: "u128".to_string(),
+                constraints: vec![],
+                inter_field_constraints: vec![],
+                span: None,
+            },
+            PrivateField {
+                name: "hash".to_string(),
+                field_type: "[u8;32]".to_string(),
+                constraints: vec![],
+                inter_field_constraints: vec![],
+                span: None,
+            },
         ],
     };
 
@@ -52,6 +74,49 @@ fn test_transform_multiple_fields() {
     assert!(matches!(ir.private_witnesses[2].field_type, ZkType::Bytes32));
 }
 
+#[test]
+fn test_transform_all_produces_one_ir_per_contract() {
+    let input = r#"
+        struct Deposit {
+            #[zk_private]
+            amount: u64,
+        }
+
+        mod voting {
+            struct Ballot {
+                #[zk_private]
+                choice: bool,
+            }
+        }
+    "#;
+
+    let irs = transform_all_to_ir(parse_contracts(input).unwrap()).unwrap();
+    assert_eq!(irs.len(), 2);
+    assert_eq!(irs[0].name, "Deposit");
+    assert!(matches!(irs[0].private_witnesses[0].field_type, ZkType::U64));
+    assert_eq!(irs[1].name, "Ballot");
+    assert!(matches!(irs[1].private_witnesses[0].field_type, ZkType::Bool));
+}
+
+#[test]
+fn test_transform_all_names_failing_contract() {
+    let input = r#"
+        struct Good {
+            #[zk_private]
+            amount: u64,
+        }
+
+        struct Bad {
+            #[zk_private]
+            label: String,
+        }
+    "#;
+
+    let err = transform_all_to_ir(parse_contracts(input).unwrap()).unwrap_err().to_string();
+    assert!(err.contains("'Bad'"));
+    assert!(err.contains("String"));
+}
+
 // ============================================================================
 // TYPE VALIDATION TESTS
 // ============================================================================
@@ -121,6 +186,9 @@ fn test_unsupported_type() {
         private_fields: vec![PrivateField {
             name: "name".to_string(),
             field_type: "String".to_string(),
+            constraints: vec![],
+            inter_field_constraints: vec![],
+            span: None,
         }],
     };
 
@@ -139,6 +207,9 @@ fn test_unsupported_vec_type() {
         private_fields: vec![PrivateField {
             name: "vec_field".to_string(),
             field_type: "Vec<u64>".to_string(),
+            constraints: vec![],
+            inter_field_constraints: vec![],
+            span: None,
         }],
     };
 
@@ -155,9 +226,27 @@ fn test_default_constraints() {
     let parsed = ParsedContract {
         contract_name: "ConstraintTest".to_string(),
         private_fields: vec![
-            PrivateField { name: "u64_field".to_string(), field_type: "u64".to_string() },
-            PrivateField { name: "u128_field".to_string(), field_type: "u128".to_string() },
-            PrivateField { name: "bytes32_field".to_string(), field_type: "[u8;32]".to_string() },
+            PrivateField {
+                name: "u64_field".to_string(),
+                field_type: "u64".to_string(),
+                constraints: vec![],
+                inter_field_constraints: vec![],
+                span: None,
+            },
+            PrivateField {
+                name: "u128_field".to_string(),
+                field_type: "u128".to_string(),
+                constraints: vec![],
+                inter_field_constraints: vec![],
+                span: None,
+            },
+            PrivateField {
+                name: "bytes32_field".to_string(),
+                field_type: "[u8;32]".to_string(),
+                constraints: vec![],
+                inter_field_constraints: vec![],
+                span: None,
+            },
         ],
     };
 
@@ -170,14 +259,198 @@ fn test_default_constraints() {
     );
 
     assert_eq!(ir.private_witnesses[1].constraints.len(), 1);
-    if let Constraint::RangeProof { min, max } = ir.private_witnesses[1].constraints[0] {
-        assert_eq!(min, 0);
-        assert_eq!(max, u128::MAX);
-    } else {
-        panic!("Expected RangeProof for u128");
-    }
+    assert!(
+        matches!(ir.private_witnesses[1].constraints[0], Constraint::Range { num_bits: 128 }),
+        "Expected Range {{ num_bits: 128 }} for u128"
+    );
+
+    assert_eq!(ir.private_witnesses[2].constraints.len(), 1);
+    assert!(
+        matches!(ir.private_witnesses[2].constraints[0], Constraint::Range { num_bits: 128 }),
+        "Expected Range {{ num_bits: 128 }} per limb for [u8; 32]"
+    );
+}
+
+#[test]
+fn test_annotation_constraints_follow_defaults() {
+    let input = r#"
+        struct Annotated {
+            #[zk_private(range = "150..=300", commit = "poseidon")]
+            collateral: u64,
+            #[zk_private(constraint = "value < collateral")]
+            debt: u64,
+        }
+    "#;
+
+    let parsed = parse_contract(input).unwrap();
+    let ir = transform_to_ir(parsed).unwrap();
+
+    assert_eq!(
+        ir.private_witnesses[0].constraints,
+        vec![
+            Constraint::Range { num_bits: 64 },
+            Constraint::RangeProof { min: 150, max: 300 },
+            Constraint::Commitment { hash_type: HashType::Poseidon },
+        ]
+    );
+    assert_eq!(
+        ir.inter_field_constraints,
+        vec![InterFieldConstraint::ComparisonCheck {
+            left_field: "debt".to_string(),
+            right_field: "collateral".to_string(),
+            op: ComparisonOp::LessThan,
+        }]
+    );
+}
+
+#[test]
+fn test_annotation_referencing_unknown_field_fails() {
+    let input = r#"
+        struct Dangling {
+            #[zk_private(constraint = "value >= threshold")]
+            collateral: u64,
+            threshold: u64,
+        }
+    "#;
+
+    let parsed = parse_contract(input).unwrap();
+    let err = transform_to_ir(parsed).unwrap_err().to_string();
+    assert!(err.contains("threshold"));
+    assert!(err.contains("not a #[zk_private] field"));
+}
+
+#[test]
+fn test_commitment_adds_public_input() {
+    let input = r#"
+        struct Note {
+            #[zk_private(commit = "poseidon", randomness = "blinding")]
+            amount: u64,
+            #[zk_private]
+            blinding: Field,
+        }
+    "#;
+
+    let ir = transform_to_ir(parse_contract(input).unwrap()).unwrap();
+    assert_eq!(
+        ir.public_inputs,
+        vec![ZkField {
+            name: "amount_commitment".to_string(),
+            field_type: ZkType::Field,
+            constraints: vec![],
+        }]
+    );
+    assert_eq!(ir.inter_field_constraints.len(), 1);
+}
+
+#[test]
+fn test_commitment_randomness_must_be_private() {
+    let input = r#"
+        struct Note {
+            #[zk_private(commit = "poseidon", randomness = "blinding")]
+            amount: u64,
+            blinding: Field,
+        }
+    "#;
+
+    let err = transform_to_ir(parse_contract(input).unwrap()).unwrap_err().to_string();
+    assert!(err.contains("references 'blinding'"), "got: {}", err);
+}
+
+#[test]
+fn test_transform_errors_point_at_field() {
+    let input = r#"
+struct Vault {
+    #[zk_private]
+    balance: u64,
+    #[zk_private]
+    owner: String,
+}
+"#;
+
+    let err = transform_to_ir(parse_contract(input).unwrap()).unwrap_err();
+    let diagnostic = err.diagnostic().expect("transform errors carry the field's location");
+    assert_eq!((diagnostic.span.line, diagnostic.span.column), (6, 5));
+    assert_eq!(diagnostic.span.end_column, 18);
+    assert!(diagnostic.render().contains("6 |     owner: String,\n  |     ^^^^^^^^^^^^^\n"));
+    assert!(diagnostic.help.is_some());
+}
+
+#[test]
+fn test_expression_check_type_errors_point_at_field() {
+    let input = r#"
+struct Loan {
+    #[zk_private]
+    debt: u64,
+    #[zk_private(constraint = "value * 2 > debt")]
+    key: Field,
+}
+"#;
+
+    let err = transform_to_ir(parse_contract(input).unwrap()).unwrap_err();
+    let diagnostic = err.diagnostic().unwrap();
+    assert_eq!(diagnostic.span.line, 6);
+    assert!(diagnostic.message.contains("'key * 2 > debt'"), "got: {}", diagnostic.message);
+    assert!(diagnostic.message.contains("range-checked integer fields"));
+}
+
+#[test]
+fn test_expression_check_rejects_unprovable_width() {
+    let input = r#"
+struct Wide {
+    #[zk_private(constraint = "value * value > b")]
+    a: u128,
+    #[zk_private]
+    b: u128,
+}
+"#;
+
+    let err = transform_to_ir(parse_contract(input).unwrap()).unwrap_err().to_string();
+    assert!(err.contains("needs 257 bits"), "got: {}", err);
+
+    let equality = input.replace('>', "==");
+    assert!(transform_to_ir(parse_contract(&equality).unwrap()).is_ok());
+}
+
+#[test]
+fn test_transform_all_keeps_location() {
+    let input = r#"
+struct Good {
+    #[zk_private]
+    a: u64,
+}
+struct Bad {
+    #[zk_private(constraint = "value > limit")]
+    b: u64,
+}
+"#;
+
+    let err = transform_all_to_ir(parse_contracts(input).unwrap()).unwrap_err();
+    let diagnostic = err.diagnostic().unwrap();
+    assert_eq!(diagnostic.span.line, 8);
+    assert!(diagnostic.message.starts_with("Failed to transform contract 'Bad'"));
+    assert_eq!(
+        diagnostic.help.as_deref(),
+        Some("declare 'limit' as a #[zk_private] field of this struct")
+    );
+}
+
+#[test]
+fn test_transform_all_keeps_error_variant() {
+    let parsed = ParsedContract {
+        contract_name: "Bad".to_string(),
+        private_fields: vec![PrivateField {
+            name: "label".to_string(),
+            field_type: "String".to_string(),
+            constraints: vec![],
+            inter_field_constraints: vec![],
+            span: None,
+        }],
+    };
 
-    assert_eq!(ir.private_witnesses[2].constraints.len(), 0);
+    let err = transform_all_to_ir(vec![parsed]).unwrap_err();
+    assert!(err.diagnostic().is_none());
+    assert!(err.to_string().starts_with("Failed to transform contract 'Bad': Unsupported zk type"));
+    assert!(matches!(err.kind(), CompilerError::UnsupportedZkType(_)), "got: {:?}", err);
 }
 
 #[test]
@@ -219,14 +492,22 @@ fn test_merkle_proof_constraint() {
         leaf_field: "commitment".to_string(),
         path_field: "merkle_path".to_string(),
         root_field: "merkle_root".to_string(),
+        indices_field: "merkle_indices".to_string(),
         tree_depth: 32,
     };
 
     match constraint {
-        Constraint::MerkleProof { leaf_field, path_field, root_field, tree_depth } => {
+        Constraint::MerkleProof {
+            leaf_field,
+            path_field,
+            root_field,
+            indices_field,
+            tree_depth,
+        } => {
             assert_eq!(leaf_field, "commitment");
             assert_eq!(path_field, "merkle_path");
             assert_eq!(root_field, "merkle_root");
+            assert_eq!(indices_field, "merkle_indices");
             assert_eq!(tree_depth, 32);
         }
         _ => panic!("Expected MerkleProof"),
@@ -247,6 +528,7 @@ fn test_inter_field_constraints() {
         leaf: "leaf".to_string(),
         path: "path".to_string(),
         root: "root".to_string(),
+        indices: "indices".to_string(),
     };
 
     assert!(matches!(merkle, InterFieldConstraint::MerkleVerification { .. }));
@@ -261,8 +543,20 @@ fn test_circuit_ir_creation() {
     let parsed = ParsedContract {
         contract_name: "PaymentContract".to_string(),
         private_fields: vec![
-            PrivateField { name: "sender_balance".to_string(), field_type: "u64".to_string() },
-            PrivateField { name: "recipient_balance".to_string(), field_type: "u64".to_string() },
+            PrivateField {
+                name: "sender_balance".to_string(),
+                field_type: "u64".to_string(),
+                constraints: vec![],
+                inter_field_constraints: vec![],
+                span: None,
+            },
+            PrivateField {
+                name: "recipient_balance".to_string(),
+                field_type: "u64".to_string(),
+                constraints: vec![],
+                inter_field_constraints: vec![],
+                span: None,
+            },
         ],
     };
 
@@ -318,8 +612,8 @@ fn test_circuit_config_structure() {
     let parsed = parse_contract(input).unwrap();
     let ir = transform_to_ir(parsed).unwrap();
 
-    // k is computed dynamically: 1 u64 → (1 + 66) * 2 = 134 → k=8
-    assert_eq!(ir.circuit_config.k(), 8);
+    // k is measured by dry-run synthesis: one 65-row range check plus blinding rows → k=7
+    assert_eq!(ir.circuit_config.k(), 7);
     assert_eq!(ir.circuit_config.num_advice_columns(), 1);
     assert_eq!(ir.circuit_config.num_instance_columns(), 1);
 }
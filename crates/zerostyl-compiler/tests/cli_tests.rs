@@ -0,0 +1,147 @@
+//! Tests for the `zerostyl-compile` binary
+//!
+//! The WASM build itself needs the wasm32 target, so `compile` is only exercised up
+//! to the MockProver step.
+
+use std::path::Path;
+use std::process::{Command, Output};
+
+use tempfile::TempDir;
+use zerostyl_compiler::{compute_k, measure_rows, parse_contracts, transform_all_to_ir, CircuitIR};
+
+const NOTE: &str = r#"
+    struct Note {
+        #[zk_private(commit = "poseidon", randomness = "blinding", range = "0..1000")]
+        amount: u64,
+        #[zk_private]
+        blinding: Field,
+    }
+"#;
+
+const TWO_CONTRACTS: &str = r#"
+    struct Note {
+        #[zk_private(commit = "poseidon", randomness = "blinding")]
+        amount: u64,
+        #[zk_private]
+        blinding: Field,
+    }
+
+    struct Vote {
+        #[zk_private(commit = "poseidon", randomness = "salt")]
+        choice: bool,
+        #[zk_private]
+        salt: Field,
+    }
+"#;
+
+fn write(dir: &TempDir, name: &str, contents: &str) -> String {
+    let path = dir.path().join(name);
+    std::fs::write(&path, contents).unwrap();
+    path.display().to_string()
+}
+
+fn run(args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_zerostyl-compile")).args(args).output().unwrap()
+}
+
+fn stdout(output: &Output) -> String {
+    assert!(output.status.success(), "failed: {}", String::from_utf8_lossy(&output.stderr));
+    String::from_utf8(output.stdout.clone()).unwrap()
+}
+
+fn stderr(output: &Output) -> String {
+    assert!(!output.status.success(), "expected failure");
+    String::from_utf8(output.stderr.clone()).unwrap()
+}
+
+#[test]
+fn test_ir_text_and_json() {
+    let dir = TempDir::new().unwrap();
+    let source = write(&dir, "note.rs", NOTE);
+
+    let text = stdout(&run(&["ir", &source]));
+    assert!(text.starts_with("circuit Note (k="));
+    assert!(text.contains("commitment amount_commitment = poseidon(amount, blinding)"));
+
+    let json = stdout(&run(&["ir", &source, "--format", "json"]));
+    let ir = CircuitIR::from_json(&json).unwrap();
+    assert_eq!(ir.name, "Note");
+    assert_eq!(ir.public_inputs.len(), 1);
+
+    assert!(stderr(&run(&["ir", &source, "--format", "yaml"])).contains("unknown format"));
+}
+
+#[test]
+fn test_k_reports_estimate_and_measurement() {
+    let dir = TempDir::new().unwrap();
+    let source = write(&dir, "note.rs", NOTE);
+
+    let ir = parse_contracts(NOTE).and_then(transform_all_to_ir).unwrap().remove(0);
+    let estimated = compute_k(ir.estimate_rows());
+    let measured = measure_rows(&ir).unwrap().min_k;
+
+    let out = stdout(&run(&["k", &source]));
+    assert!(out.contains(&format!("estimated k: {}\n", estimated)), "{}", out);
+    assert!(out.contains(&format!("measured k:  {} (", measured)), "{}", out);
+    assert!(out.contains("Rows by region:"));
+}
+
+#[test]
+fn test_crate_prints_generated_source() {
+    let dir = TempDir::new().unwrap();
+    let source = write(&dir, "note.rs", NOTE);
+
+    let out = stdout(&run(&["crate", &source]));
+    assert!(out.contains("// Cargo.toml\n[package]"));
+    assert!(out.contains("// src/lib.rs\n// ZeroStyl Generated Verifier — Circuit: Note"));
+    assert!(out.contains("fn user_entrypoint"));
+}
+
+#[test]
+fn test_contract_selection() {
+    let dir = TempDir::new().unwrap();
+    let source = write(&dir, "contracts.rs", TWO_CONTRACTS);
+
+    let err = stderr(&run(&["ir", &source]));
+    assert!(err.contains("several contracts (Note, Vote)"), "{err}");
+
+    let out = stdout(&run(&["ir", &source, "--contract", "Vote"]));
+    assert!(out.starts_with("circuit Vote"));
+
+    let err = stderr(&run(&["ir", &source, "--contract", "Swap"]));
+    assert!(err.contains("no #[zk_private] struct 'Swap'"), "{err}");
+}
+
+#[test]
+fn test_errors_render_source_location() {
+    let dir = TempDir::new().unwrap();
+    let source = write(
+        &dir,
+        "bad.rs",
+        "struct Bad {\n    #[zk_private(range = \"0..10\")]\n    name: String,\n}\n",
+    );
+
+    let err = stderr(&run(&["ir", &source]));
+    assert!(err.starts_with("error: "), "{err}");
+    assert!(err.contains(&format!("--> {}:3:", source)), "{err}");
+}
+
+#[test]
+fn test_compile_stops_on_failing_witness() {
+    let dir = TempDir::new().unwrap();
+    let source = write(&dir, "note.rs", NOTE);
+    let witness = write(&dir, "witness.json", r#"{"amount": "5000", "blinding": "7"}"#);
+    let output = dir.path().join("note.wasm");
+
+    let err = stderr(&run(&[
+        "compile",
+        &source,
+        "--witness",
+        &witness,
+        "--output",
+        &output.display().to_string(),
+    ]));
+    assert!(err.contains("mock prover:"), "{err}");
+    assert!(err.contains("constraint failure(s)"), "{err}");
+    assert!(!Path::new(&output).exists());
+}
@@ -0,0 +1,95 @@
+//! Snapshot tests for the JSON and text forms of the circuit IR.
+//!
+//! Set `REGEN_IR_SNAPSHOTS=1` to overwrite the committed snapshot files instead of
+//! asserting equality — use after intentional IR or format changes.
+
+use std::fs;
+use std::path::PathBuf;
+
+use zerostyl_compiler::{
+    parse_contract, transform_to_ir, CircuitBuilder, CircuitIR, CompilerError, IR_FORMAT_VERSION,
+};
+
+const VAULT: &str = r#"
+    struct Vault {
+        #[zk_private(commit = "poseidon", randomness = "blinding", constraint = "value >= minimum")]
+        balance: u64,
+        #[zk_private]
+        blinding: Field,
+        #[zk_private(range = "100..=10_000")]
+        minimum: u64,
+        #[zk_private(range = "-1000..=1000", constraint = "value != 0")]
+        pnl: i64,
+        #[zk_private(constraint = "merkle_member(value, root, siblings, indices)")]
+        owner: Field,
+        #[zk_private]
+        root: Field,
+        #[zk_private]
+        siblings: [Field; 2],
+        #[zk_private]
+        indices: [bool; 2],
+        #[zk_private]
+        salt: [u8; 32],
+    }
+"#;
+
+fn snapshots_dir() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/snapshots")
+}
+
+fn vault_ir() -> CircuitIR {
+    transform_to_ir(parse_contract(VAULT).unwrap()).unwrap()
+}
+
+fn check_snapshot(file: &str, actual: &str) {
+    let path = snapshots_dir().join(file);
+    if std::env::var_os("REGEN_IR_SNAPSHOTS").is_some() {
+        fs::write(&path, actual).expect("snapshot writable");
+        return;
+    }
+    let expected = fs::read_to_string(&path)
+        .unwrap_or_else(|_| panic!("missing snapshot {}; run with REGEN_IR_SNAPSHOTS=1", file));
+    assert_eq!(expected.replace("\r\n", "\n"), actual, "snapshot {} is stale", file);
+}
+
+#[test]
+fn vault_ir_json_snapshot() {
+    check_snapshot("vault.ir.json", &format!("{}\n", vault_ir().to_json().unwrap()));
+}
+
+#[test]
+fn vault_ir_text_snapshot() {
+    check_snapshot("vault.ir.txt", &vault_ir().to_text());
+}
+
+#[test]
+fn snapshot_loads_into_circuit_builder() {
+    let json = fs::read_to_string(snapshots_dir().join("vault.ir.json")).unwrap();
+    let ir = CircuitIR::from_json(&json).unwrap();
+    assert_eq!(ir, vault_ir());
+
+    let builder = CircuitBuilder::new(ir);
+    assert_eq!(builder.circuit_ir().name, "Vault");
+    builder.build::<halo2curves::pasta::Fp>();
+}
+
+#[test]
+fn hand_edited_ir_is_validated() {
+    let json =
+        vault_ir().to_json().unwrap().replace("\"name\": \"blinding\"", "\"name\": \"nonce\"");
+    let err = CircuitIR::from_json(&json).unwrap_err();
+    assert!(!matches!(err, CompilerError::InvalidIr(_)), "expected a validation error: {err}");
+}
+
+#[test]
+fn malformed_json_is_invalid_ir() {
+    let err = CircuitIR::from_json("{ \"version\": 1 }").unwrap_err();
+    assert!(matches!(err, CompilerError::InvalidIr(_)));
+
+    let err = CircuitIR::from_json(&format!(
+        "{{ \"version\": {}, \"circuit\": {{ \"name\": \"X\", \"extra\": 1 }} }}",
+        IR_FORMAT_VERSION
+    ))
+    .unwrap_err();
+    assert!(err.to_string().contains("unknown field"), "got: {err}");
+}
@@ -7,8 +7,14 @@
 use halo2_proofs::dev::MockProver;
 use halo2_proofs::plonk::{keygen_vk, Circuit};
 use halo2_proofs::poly::commitment::Params;
+use halo2curves::ff::PrimeField;
 use halo2curves::pasta::{EqAffine, Fp as TestField};
-use zerostyl_compiler::{parse_contract, transform_to_ir, CircuitBuilder};
+use zerostyl_compiler::gadgets::{MerkleTreeChip, PoseidonCommitmentChip};
+use zerostyl_compiler::{
+    encode_i64, parse_contract, transform_to_ir, validate_circuit_ir, ArithOp, CircuitBuilder,
+    CircuitIR, Constraint, HashType, InterFieldConstraint, PublicInputSource, ZkField, ZkType,
+};
+use zerostyl_runtime::encoding::{encode_address, encode_bytes32};
 
 #[test]
 fn test_mock_prover_empty_circuit() {
@@ -176,14 +182,14 @@ fn test_without_witnesses_works_for_keygen() {
 
 #[test]
 fn test_mock_prover_all_supported_integer_types() {
-    // The generic circuit builder supports u8, u16, u32, u64 range checks.
-    // u128 requires the proc-macro DualRange path (not the generic builder).
+    // The generic circuit builder supports u8 through u128 range checks.
     let input = r#"
         struct SupportedIntegers {
             #[zk_private] v_u8: u8,
             #[zk_private] v_u16: u16,
             #[zk_private] v_u32: u32,
             #[zk_private] v_u64: u64,
+            #[zk_private] v_u128: u128,
             #[zk_private] v_i64: i64,
         }
     "#;
@@ -198,26 +204,71 @@ fn test_mock_prover_all_supported_integer_types() {
 }
 
 #[test]
-fn test_circuit_rejects_u128_range_in_builder() {
-    // The generic builder cannot handle u128 RangeProof (bounds exceed u64).
-    // It must return Err(Synthesis), not silently truncate.
-    // u128 is handled correctly by the proc-macro via DualRange decomposition.
+fn test_mock_prover_u128_range_checked_by_limbs() {
     let input = r#"
         struct U128Circuit {
             #[zk_private] v_u128: u128,
         }
     "#;
 
-    let parsed = parse_contract(input).unwrap();
-    let ir = transform_to_ir(parsed).unwrap();
-    let circuit = CircuitBuilder::new(ir).build::<TestField>();
+    let ir = transform_to_ir(parse_contract(input).unwrap()).unwrap();
+    let k = ir.circuit_config.k();
+    let run = |value: TestField| {
+        let circuit = CircuitBuilder::new(ir.clone())
+            .build::<TestField>()
+            .with_witnesses(vec![value])
+            .unwrap();
+        MockProver::run(k, &circuit, vec![vec![]]).unwrap().verify().is_ok()
+    };
 
-    let k = circuit.ir.circuit_config.k();
-    let result = MockProver::run(k, &circuit, vec![vec![]]);
-    assert!(
-        result.is_err(),
-        "Generic builder must reject u128 RangeProof (use proc-macro DualRange instead)"
-    );
+    assert!(run(TestField::from_u128(u128::MAX)));
+    assert!(!run(TestField::from_u128(u128::MAX) + TestField::one()));
+}
+
+#[test]
+fn test_mock_prover_range_proof_with_u128_bounds() {
+    let input = r#"
+        struct Supply {
+            #[zk_private(range = "18_446_744_073_709_551_616..=u128::MAX")]
+            total: u128,
+        }
+    "#;
+
+    let ir = transform_to_ir(parse_contract(input).unwrap()).unwrap();
+    let k = ir.circuit_config.k();
+    let run = |value: u128| {
+        let circuit = CircuitBuilder::new(ir.clone())
+            .build::<TestField>()
+            .with_witnesses(vec![TestField::from_u128(value)])
+            .unwrap();
+        MockProver::run(k, &circuit, vec![vec![]]).unwrap().verify().is_ok()
+    };
+
+    assert!(run(1u128 << 64));
+    assert!(run(u128::MAX));
+    assert!(!run(u64::MAX as u128));
+}
+
+#[test]
+fn test_mock_prover_comparison_with_u128_constant() {
+    let run = |input: &str, value: u128| {
+        let ir = transform_to_ir(parse_contract(input).unwrap()).unwrap();
+        let circuit = CircuitBuilder::new(ir.clone())
+            .build::<TestField>()
+            .with_witnesses(vec![TestField::from_u128(value)])
+            .unwrap();
+        MockProver::run(ir.circuit_config.k(), &circuit, vec![vec![]]).unwrap().verify().is_ok()
+    };
+
+    let above = r#"struct Supply { #[zk_private(constraint = "value > 18446744073709551616")] total: u128 }"#;
+    assert!(run(above, (1u128 << 64) + 1));
+    assert!(!run(above, 1u128 << 64));
+    assert!(!run(above, u64::MAX as u128));
+
+    // The constant is wider than the field: the comparison widens to fit it.
+    let below =
+        r#"struct Fee { #[zk_private(constraint = "value < 36893488147419103232")] fee: u64 }"#;
+    assert!(run(below, u64::MAX as u128));
 }
 
 // ============================================================================
@@ -349,3 +400,859 @@ fn test_mock_prover_boolean_accepts_one() {
     let prover = MockProver::run(k, &circuit, vec![vec![]]).unwrap();
     prover.verify().unwrap();
 }
+
+#[test]
+fn test_mock_prover_u64_array_elements_range_checked() {
+    let input = r#"
+        struct Balances {
+            #[zk_private]
+            balances: [u64; 4],
+        }
+    "#;
+
+    let parsed = parse_contract(input).unwrap();
+    let ir = transform_to_ir(parsed).unwrap();
+    let k = ir.circuit_config.k();
+    let circuit = CircuitBuilder::new(ir)
+        .build::<TestField>()
+        .with_witnesses(vec![
+            TestField::from(1u64),
+            TestField::from(2u64),
+            TestField::from(u64::MAX),
+            TestField::from(0u64),
+        ])
+        .unwrap();
+    assert_eq!(circuit.num_witnesses(), 4);
+
+    let prover = MockProver::run(k, &circuit, vec![vec![]]).unwrap();
+    prover.verify().unwrap();
+}
+
+#[test]
+fn test_mock_prover_array_rejects_out_of_range_element() {
+    let input = r#"
+        struct Flags {
+            #[zk_private]
+            flags: [bool; 3],
+        }
+    "#;
+
+    let parsed = parse_contract(input).unwrap();
+    let ir = transform_to_ir(parsed).unwrap();
+    let k = ir.circuit_config.k();
+    let circuit = CircuitBuilder::new(ir)
+        .build::<TestField>()
+        .with_witnesses(vec![TestField::from(1u64), TestField::from(2u64), TestField::from(0u64)])
+        .unwrap();
+
+    let prover = MockProver::run(k, &circuit, vec![vec![]]).unwrap();
+    assert!(prover.verify().is_err(), "Non-boolean array element must be rejected");
+}
+
+#[test]
+fn test_mock_prover_nested_field_array_flattens() {
+    let input = r#"
+        struct MerklePath {
+            #[zk_private]
+            leaf: Field,
+            #[zk_private]
+            siblings: [[Field; 2]; 3],
+        }
+    "#;
+
+    let parsed = parse_contract(input).unwrap();
+    let ir = transform_to_ir(parsed).unwrap();
+    let k = ir.circuit_config.k();
+    let circuit = CircuitBuilder::new(ir).build::<TestField>();
+    assert_eq!(circuit.num_witnesses(), 7);
+
+    let result = circuit.clone().with_witnesses(vec![TestField::from(1u64); 2]);
+    assert!(result.unwrap_err().to_string().contains("Expected 7 witnesses but got 2"));
+
+    let circuit = circuit.with_witnesses((0..7u64).map(TestField::from).collect()).unwrap();
+    let prover = MockProver::run(k, &circuit, vec![vec![]]).unwrap();
+    prover.verify().unwrap();
+}
+
+// ============================================================================
+// ARITHMETIC RELATION TESTS
+// ============================================================================
+
+/// Parses `input` and attaches `relation` as an inter-field constraint.
+fn ir_with_relation(input: &str, relation: InterFieldConstraint) -> CircuitIR {
+    let mut ir = transform_to_ir(parse_contract(input).unwrap()).unwrap();
+    ir.inter_field_constraints.push(relation);
+    ir.recompute_config().unwrap();
+    ir
+}
+
+fn run_relation(ir: &CircuitIR, witnesses: &[u64]) -> bool {
+    let k = ir.circuit_config.k();
+    let circuit = CircuitBuilder::new(ir.clone())
+        .build::<TestField>()
+        .with_witnesses(witnesses.iter().copied().map(TestField::from).collect())
+        .unwrap();
+    MockProver::run(k, &circuit, vec![vec![]]).unwrap().verify().is_ok()
+}
+
+fn balance_conservation_ir() -> CircuitIR {
+    let input = r#"
+        struct Transfer {
+            #[zk_private]
+            balance_old: u64,
+            #[zk_private]
+            amount: u64,
+            #[zk_private]
+            balance_new: u64,
+        }
+    "#;
+    ir_with_relation(
+        input,
+        InterFieldConstraint::ArithmeticRelation {
+            result_field: "balance_new".to_string(),
+            operation: ArithOp::Sub,
+            operands: vec!["balance_old".to_string(), "amount".to_string()],
+        },
+    )
+}
+
+#[test]
+fn test_mock_prover_balance_conservation_accepts_valid() {
+    let ir = balance_conservation_ir();
+    assert!(run_relation(&ir, &[1000, 300, 700]));
+}
+
+#[test]
+fn test_mock_prover_balance_conservation_rejects_wrong_result() {
+    let ir = balance_conservation_ir();
+    assert!(!run_relation(&ir, &[1000, 300, 701]));
+}
+
+#[test]
+fn test_mock_prover_balance_conservation_rejects_underflow() {
+    // 100 - 300 wraps modulo p, which cannot match any range-checked u64 balance
+    let ir = balance_conservation_ir();
+    assert!(!run_relation(&ir, &[100, 300, 0]));
+}
+
+#[test]
+fn test_mock_prover_mixed_arithmetic_chain() {
+    // result = a + b - c
+    let input = r#"
+        struct Chain {
+            #[zk_private]
+            a: u64,
+            #[zk_private]
+            b: u64,
+            #[zk_private]
+            c: u64,
+            #[zk_private]
+            result: u64,
+        }
+    "#;
+    let mut ir = transform_to_ir(parse_contract(input).unwrap()).unwrap();
+    ir.private_witnesses[3].constraints.push(Constraint::ArithmeticRelation {
+        lhs_field: "result".to_string(),
+        operator: ArithOp::Add,
+        rhs_fields: vec![
+            ("a".to_string(), ArithOp::Add),
+            ("b".to_string(), ArithOp::Add),
+            ("c".to_string(), ArithOp::Sub),
+        ],
+    });
+    ir.recompute_config().unwrap();
+
+    assert!(run_relation(&ir, &[10, 20, 5, 25]));
+    assert!(!run_relation(&ir, &[10, 20, 5, 35]));
+}
+
+#[test]
+fn test_mock_prover_multiplication_relation() {
+    let input = r#"
+        struct Product {
+            #[zk_private]
+            price: u64,
+            #[zk_private]
+            quantity: u64,
+            #[zk_private]
+            total: u64,
+        }
+    "#;
+    let ir = ir_with_relation(
+        input,
+        InterFieldConstraint::ArithmeticRelation {
+            result_field: "total".to_string(),
+            operation: ArithOp::Mul,
+            operands: vec!["price".to_string(), "quantity".to_string()],
+        },
+    );
+
+    assert!(run_relation(&ir, &[25, 4, 100]));
+    assert!(!run_relation(&ir, &[25, 4, 101]));
+}
+
+#[test]
+fn test_mock_prover_division_relation() {
+    let input = r#"
+        struct Share {
+            #[zk_private]
+            pool: u64,
+            #[zk_private]
+            holders: u64,
+            #[zk_private]
+            share: u64,
+        }
+    "#;
+    let ir = ir_with_relation(
+        input,
+        InterFieldConstraint::ArithmeticRelation {
+            result_field: "share".to_string(),
+            operation: ArithOp::Div,
+            operands: vec!["pool".to_string(), "holders".to_string()],
+        },
+    );
+
+    assert!(run_relation(&ir, &[120, 4, 30]));
+    assert!(!run_relation(&ir, &[120, 4, 31]));
+    // A zero divisor has no inverse, even when pool = share * holders holds trivially
+    assert!(!run_relation(&ir, &[0, 0, 0]));
+}
+
+#[test]
+fn test_mock_prover_arithmetic_relation_on_arrays_is_element_wise() {
+    let input = r#"
+        struct Batch {
+            #[zk_private]
+            a: [u64; 3],
+            #[zk_private]
+            b: [u64; 3],
+            #[zk_private]
+            sum: [u64; 3],
+        }
+    "#;
+    let ir = ir_with_relation(
+        input,
+        InterFieldConstraint::ArithmeticRelation {
+            result_field: "sum".to_string(),
+            operation: ArithOp::Add,
+            operands: vec!["a".to_string(), "b".to_string()],
+        },
+    );
+
+    assert!(run_relation(&ir, &[1, 2, 3, 10, 20, 30, 11, 22, 33]));
+    assert!(!run_relation(&ir, &[1, 2, 3, 10, 20, 30, 11, 22, 34]));
+}
+
+// ============================================================================
+// COMMITMENT VERIFICATION TESTS
+// ============================================================================
+
+fn commitment_ir() -> CircuitIR {
+    let input = r#"
+        struct Note {
+            #[zk_private(commit = "poseidon", randomness = "blinding")]
+            amount: u64,
+            #[zk_private]
+            blinding: Field,
+        }
+    "#;
+    transform_to_ir(parse_contract(input).unwrap()).unwrap()
+}
+
+fn run_commitment(amount: u64, blinding: u64, commitment: TestField) -> bool {
+    let ir = commitment_ir();
+    let k = ir.circuit_config.k();
+    let circuit = CircuitBuilder::new(ir)
+        .build::<TestField>()
+        .with_witnesses(vec![TestField::from(amount), TestField::from(blinding)])
+        .unwrap()
+        .with_public_inputs(vec![commitment])
+        .unwrap();
+    MockProver::run(k, &circuit, vec![vec![commitment]]).unwrap().verify().is_ok()
+}
+
+#[test]
+fn test_mock_prover_commitment_accepts_matching_hash() {
+    let commitment =
+        PoseidonCommitmentChip::hash_outside_circuit(TestField::from(500), TestField::from(77));
+    assert!(run_commitment(500, 77, commitment));
+}
+
+#[test]
+fn test_mock_prover_commitment_rejects_wrong_value() {
+    let commitment =
+        PoseidonCommitmentChip::hash_outside_circuit(TestField::from(500), TestField::from(77));
+    assert!(!run_commitment(501, 77, commitment));
+}
+
+#[test]
+fn test_mock_prover_commitment_rejects_wrong_randomness() {
+    let commitment =
+        PoseidonCommitmentChip::hash_outside_circuit(TestField::from(500), TestField::from(77));
+    assert!(!run_commitment(500, 78, commitment));
+}
+
+#[test]
+fn test_validate_rejects_pedersen_commitment_verification() {
+    let mut ir = commitment_ir();
+    ir.inter_field_constraints = vec![InterFieldConstraint::CommitmentVerification {
+        commitment: "amount_commitment".to_string(),
+        value: "amount".to_string(),
+        randomness: "blinding".to_string(),
+        hash_type: HashType::Pedersen,
+    }];
+    let err = validate_circuit_ir(&ir).unwrap_err().to_string();
+    assert!(err.contains("only supports Poseidon"), "got: {}", err);
+}
+
+#[test]
+fn test_validate_rejects_commitment_without_public_input() {
+    let mut ir = commitment_ir();
+    ir.public_inputs.clear();
+    let err = validate_circuit_ir(&ir).unwrap_err().to_string();
+    assert!(err.contains("is not a public input"), "got: {}", err);
+}
+
+// ============================================================================
+// MERKLE MEMBERSHIP TESTS
+// ============================================================================
+
+const MEMBERSHIP: &str = r#"
+    struct Membership {
+        #[zk_private(constraint = "merkle_member(value, root, siblings, indices)")]
+        leaf: Field,
+        #[zk_private]
+        root: Field,
+        #[zk_private]
+        siblings: [Field; 4],
+        #[zk_private]
+        indices: [bool; 4],
+    }
+"#;
+
+/// Witnesses for [`MEMBERSHIP`]: leaf, root, siblings, then direction bits.
+fn membership_witnesses(leaf: u64, root: TestField) -> Vec<TestField> {
+    let siblings: Vec<TestField> = (0..4u64).map(|i| TestField::from(100 + i)).collect();
+    let indices = [false, true, true, false];
+    let mut witnesses = vec![TestField::from(leaf), root];
+    witnesses.extend(&siblings);
+    witnesses.extend(indices.iter().map(|&b| TestField::from(u64::from(b))));
+    witnesses
+}
+
+fn membership_root(leaf: u64) -> TestField {
+    let siblings: Vec<TestField> = (0..4u64).map(|i| TestField::from(100 + i)).collect();
+    MerkleTreeChip::compute_root_outside_circuit(
+        TestField::from(leaf),
+        &siblings,
+        &[false, true, true, false],
+    )
+}
+
+fn run_membership(ir: &CircuitIR, witnesses: Vec<TestField>) -> bool {
+    let k = ir.circuit_config.k();
+    let circuit =
+        CircuitBuilder::new(ir.clone()).build::<TestField>().with_witnesses(witnesses).unwrap();
+    MockProver::run(k, &circuit, vec![vec![]]).unwrap().verify().is_ok()
+}
+
+#[test]
+fn test_mock_prover_merkle_membership_accepts_valid_path() {
+    let ir = transform_to_ir(parse_contract(MEMBERSHIP).unwrap()).unwrap();
+    assert!(run_membership(&ir, membership_witnesses(42, membership_root(42))));
+}
+
+#[test]
+fn test_mock_prover_merkle_membership_rejects_wrong_root() {
+    let ir = transform_to_ir(parse_contract(MEMBERSHIP).unwrap()).unwrap();
+    assert!(!run_membership(&ir, membership_witnesses(42, membership_root(43))));
+}
+
+#[test]
+fn test_mock_prover_merkle_membership_rejects_non_boolean_index() {
+    let ir = transform_to_ir(parse_contract(MEMBERSHIP).unwrap()).unwrap();
+    let mut witnesses = membership_witnesses(42, membership_root(42));
+    witnesses[6] = TestField::from(2);
+    assert!(!run_membership(&ir, witnesses));
+}
+
+#[test]
+fn test_mock_prover_merkle_verification_inter_field() {
+    let input = r#"
+        struct Membership {
+            #[zk_private]
+            leaf: Field,
+            #[zk_private]
+            root: Field,
+            #[zk_private]
+            siblings: [Field; 4],
+            #[zk_private]
+            indices: [bool; 4],
+        }
+    "#;
+    let mut ir = transform_to_ir(parse_contract(input).unwrap()).unwrap();
+    ir.inter_field_constraints.push(InterFieldConstraint::MerkleVerification {
+        leaf: "leaf".to_string(),
+        path: "siblings".to_string(),
+        root: "root".to_string(),
+        indices: "indices".to_string(),
+    });
+    ir.recompute_config().unwrap();
+
+    assert!(run_membership(&ir, membership_witnesses(7, membership_root(7))));
+    assert!(!run_membership(&ir, membership_witnesses(7, membership_root(8))));
+}
+
+#[test]
+fn test_validate_rejects_merkle_indices_length_mismatch() {
+    let input = r#"
+        struct Membership {
+            #[zk_private]
+            leaf: Field,
+            #[zk_private]
+            root: Field,
+            #[zk_private]
+            siblings: [Field; 4],
+            #[zk_private]
+            indices: [bool; 3],
+        }
+    "#;
+    let mut ir = transform_to_ir(parse_contract(input).unwrap()).unwrap();
+    ir.inter_field_constraints.push(InterFieldConstraint::MerkleVerification {
+        leaf: "leaf".to_string(),
+        path: "siblings".to_string(),
+        root: "root".to_string(),
+        indices: "indices".to_string(),
+    });
+    let err = validate_circuit_ir(&ir).unwrap_err().to_string();
+    assert!(err.contains("'indices' has 3 elements, expected depth 4"), "got: {}", err);
+}
+
+// ============================================================================
+// PUBLIC INPUT BINDING TESTS
+// ============================================================================
+
+fn public_field(name: &str) -> ZkField {
+    ZkField { name: name.to_string(), field_type: ZkType::U64, constraints: vec![] }
+}
+
+fn run_public(ir: &CircuitIR, witnesses: &[u64], public: &[u64]) -> bool {
+    let k = ir.circuit_config.k();
+    let public: Vec<TestField> = public.iter().copied().map(TestField::from).collect();
+    let circuit = CircuitBuilder::new(ir.clone())
+        .build::<TestField>()
+        .with_witnesses(witnesses.iter().copied().map(TestField::from).collect())
+        .unwrap()
+        .with_public_inputs(public.clone())
+        .unwrap();
+    MockProver::run(k, &circuit, vec![public]).unwrap().verify().is_ok()
+}
+
+#[test]
+fn test_mock_prover_public_input_bound_to_field() {
+    let input = "struct Reveal { #[zk_private] amount: u64, #[zk_private] salt: u64 }";
+    let mut ir = transform_to_ir(parse_contract(input).unwrap()).unwrap();
+    ir.bind_public_input(public_field("revealed"), PublicInputSource::Field("amount".to_string()));
+    ir.recompute_config().unwrap();
+    validate_circuit_ir(&ir).unwrap();
+
+    assert!(run_public(&ir, &[250, 9], &[250]));
+    assert!(!run_public(&ir, &[250, 9], &[251]));
+}
+
+#[test]
+fn test_mock_prover_public_input_bound_to_expression() {
+    let input = r#"
+        struct Totals {
+            #[zk_private]
+            deposits: u64,
+            #[zk_private]
+            withdrawals: u64,
+        }
+    "#;
+    let mut ir = transform_to_ir(parse_contract(input).unwrap()).unwrap();
+    ir.bind_public_input(
+        public_field("net"),
+        PublicInputSource::Arithmetic {
+            operation: ArithOp::Sub,
+            operands: vec!["deposits".to_string(), "withdrawals".to_string()],
+        },
+    );
+    ir.recompute_config().unwrap();
+    validate_circuit_ir(&ir).unwrap();
+
+    assert!(run_public(&ir, &[900, 400], &[500]));
+    assert!(!run_public(&ir, &[900, 400], &[501]));
+}
+
+#[test]
+fn test_mock_prover_public_input_bound_to_signed_expression() {
+    let input = "struct Pnl { #[zk_private] gain: i64, #[zk_private] loss: i64 }";
+    let mut ir = transform_to_ir(parse_contract(input).unwrap()).unwrap();
+    ir.bind_public_input(
+        public_field("net"),
+        PublicInputSource::Arithmetic {
+            operation: ArithOp::Add,
+            operands: vec!["gain".to_string(), "loss".to_string()],
+        },
+    );
+    ir.recompute_config().unwrap();
+    validate_circuit_ir(&ir).unwrap();
+
+    // The instance holds the integer sum, not the sum of offset encodings
+    let k = ir.circuit_config.k();
+    let witnesses = vec![TestField::from(encode_i64(300)), TestField::from(encode_i64(-800))];
+    let run = |net: TestField| {
+        let circuit = CircuitBuilder::new(ir.clone())
+            .build::<TestField>()
+            .with_witnesses(witnesses.clone())
+            .unwrap()
+            .with_public_inputs(vec![net])
+            .unwrap();
+        MockProver::run(k, &circuit, vec![vec![net]]).unwrap().verify().is_ok()
+    };
+    assert!(run(-TestField::from(500)));
+    assert!(!run(TestField::from(encode_i64(300)) + TestField::from(encode_i64(-800))));
+}
+
+#[test]
+fn test_validate_rejects_unbound_public_input() {
+    let input = "struct Reveal { #[zk_private] amount: u64 }";
+    let mut ir = transform_to_ir(parse_contract(input).unwrap()).unwrap();
+    ir.add_public_input(public_field("revealed"));
+
+    let err = validate_circuit_ir(&ir).unwrap_err().to_string();
+    assert!(err.contains("Public input 'revealed' is not bound"), "got: {}", err);
+}
+
+#[test]
+fn test_validate_rejects_binding_to_unknown_field() {
+    let input = "struct Reveal { #[zk_private] amount: u64 }";
+    let mut ir = transform_to_ir(parse_contract(input).unwrap()).unwrap();
+    ir.bind_public_input(public_field("revealed"), PublicInputSource::Field("missing".to_string()));
+
+    let err = validate_circuit_ir(&ir).unwrap_err().to_string();
+    assert!(err.contains("unknown private field 'missing'"), "got: {}", err);
+}
+
+// ============================================================================
+// EQUALITY AND FIELD-TO-FIELD COMPARISON TESTS
+// ============================================================================
+
+fn run_struct(input: &str, witnesses: &[u64]) -> bool {
+    let ir = transform_to_ir(parse_contract(input).unwrap()).unwrap();
+    validate_circuit_ir(&ir).unwrap();
+    let k = ir.circuit_config.k();
+    let circuit = CircuitBuilder::new(ir)
+        .build::<TestField>()
+        .with_witnesses(witnesses.iter().copied().map(TestField::from).collect())
+        .unwrap();
+    MockProver::run(k, &circuit, vec![vec![]]).unwrap().verify().is_ok()
+}
+
+#[test]
+fn test_mock_prover_equal_to_constant() {
+    let input = r#"struct Pin { #[zk_private(constraint = "value == 42")] pin: u64 }"#;
+    assert!(run_struct(input, &[42]));
+    assert!(!run_struct(input, &[41]));
+}
+
+#[test]
+fn test_mock_prover_not_equal_to_constant() {
+    let input = r#"struct NonZero { #[zk_private(constraint = "value != 0")] divisor: u64 }"#;
+    assert!(run_struct(input, &[5]));
+    assert!(!run_struct(input, &[0]));
+}
+
+#[test]
+fn test_mock_prover_field_to_field_ordering() {
+    let input = r#"
+        struct Loan {
+            #[zk_private(constraint = "value >= threshold")]
+            collateral: u64,
+            #[zk_private]
+            threshold: u64,
+        }
+    "#;
+    assert!(run_struct(input, &[150, 100]));
+    assert!(run_struct(input, &[100, 100]));
+    assert!(!run_struct(input, &[99, 100]));
+}
+
+#[test]
+fn test_mock_prover_field_to_field_not_equal() {
+    let input = r#"
+        struct Transfer {
+            #[zk_private(constraint = "value != receiver")]
+            sender: Field,
+            #[zk_private]
+            receiver: Field,
+        }
+    "#;
+    assert!(run_struct(input, &[7, 8]));
+    assert!(!run_struct(input, &[7, 7]));
+}
+
+#[test]
+fn test_validate_rejects_ordering_on_unbounded_field() {
+    let input = r#"
+        struct Loan {
+            #[zk_private(constraint = "value >= threshold")]
+            collateral: Field,
+            #[zk_private]
+            threshold: u64,
+        }
+    "#;
+    let ir = transform_to_ir(parse_contract(input).unwrap()).unwrap();
+    let err = validate_circuit_ir(&ir).unwrap_err().to_string();
+    assert!(err.contains("require a range-checked integer type"), "got: {}", err);
+}
+
+#[test]
+fn test_mock_prover_u128_field_to_field_ordering() {
+    let input = r#"
+        struct Vault {
+            #[zk_private(constraint = "value > debt")]
+            assets: u128,
+            #[zk_private]
+            debt: u128,
+        }
+    "#;
+    let ir = transform_to_ir(parse_contract(input).unwrap()).unwrap();
+    validate_circuit_ir(&ir).unwrap();
+    let k = ir.circuit_config.k();
+    let run = |assets: u128, debt: u128| {
+        let circuit = CircuitBuilder::new(ir.clone())
+            .build::<TestField>()
+            .with_witnesses(vec![TestField::from_u128(assets), TestField::from_u128(debt)])
+            .unwrap();
+        MockProver::run(k, &circuit, vec![vec![]]).unwrap().verify().is_ok()
+    };
+
+    assert!(run(u128::MAX, u128::MAX - 1));
+    assert!(run(1u128 << 64, u64::MAX as u128));
+    assert!(!run(u128::MAX - 1, u128::MAX));
+}
+
+// ============================================================================
+// SIGNED INTEGER TESTS
+// ============================================================================
+
+fn run_signed(ir: &CircuitIR, values: &[i64]) -> bool {
+    let k = ir.circuit_config.k();
+    let circuit = CircuitBuilder::new(ir.clone())
+        .build::<TestField>()
+        .with_witnesses(values.iter().map(|v| TestField::from(encode_i64(*v))).collect())
+        .unwrap();
+    MockProver::run(k, &circuit, vec![vec![]]).unwrap().verify().is_ok()
+}
+
+fn pnl_ir() -> CircuitIR {
+    let input = r#"
+        struct Position {
+            #[zk_private]
+            entry: i64,
+            #[zk_private]
+            exit: i64,
+            #[zk_private(constraint = "value >= -500")]
+            pnl: i64,
+        }
+    "#;
+    let ir = ir_with_relation(
+        input,
+        InterFieldConstraint::ArithmeticRelation {
+            result_field: "pnl".to_string(),
+            operation: ArithOp::Sub,
+            operands: vec!["exit".to_string(), "entry".to_string()],
+        },
+    );
+    validate_circuit_ir(&ir).unwrap();
+    ir
+}
+
+#[test]
+fn test_mock_prover_signed_pnl_delta() {
+    let ir = pnl_ir();
+    assert!(run_signed(&ir, &[1000, 1200, 200]));
+    assert!(run_signed(&ir, &[1000, 700, -300]));
+    assert!(run_signed(&ir, &[-50, -250, -200]));
+    assert!(!run_signed(&ir, &[1000, 700, 300]), "sign of the delta must be enforced");
+    assert!(!run_signed(&ir, &[1000, 400, -600]), "loss below -500 must be rejected");
+}
+
+#[test]
+fn test_mock_prover_signed_range_and_ordering() {
+    let input = r#"
+        struct Bounds {
+            #[zk_private(range = "-100..=100", constraint = "value < limit")]
+            x: i64,
+            #[zk_private]
+            limit: i64,
+        }
+    "#;
+    let ir = transform_to_ir(parse_contract(input).unwrap()).unwrap();
+    validate_circuit_ir(&ir).unwrap();
+    assert!(run_signed(&ir, &[-100, -99]));
+    assert!(run_signed(&ir, &[100, i64::MAX]));
+    assert!(!run_signed(&ir, &[-101, 0]));
+    assert!(!run_signed(&ir, &[5, -5]));
+}
+
+#[test]
+fn test_mock_prover_signed_rejects_unencoded_field_element() {
+    // -1 as a raw field element (p - 1) is not a valid 64-bit encoding
+    let input = r#"struct S { #[zk_private] x: i64 }"#;
+    let ir = transform_to_ir(parse_contract(input).unwrap()).unwrap();
+    let k = ir.circuit_config.k();
+    let circuit = CircuitBuilder::new(ir)
+        .build::<TestField>()
+        .with_witnesses(vec![-TestField::one()])
+        .unwrap();
+    assert!(MockProver::run(k, &circuit, vec![vec![]]).unwrap().verify().is_err());
+}
+
+#[test]
+fn test_validate_rejects_mixed_signedness() {
+    let input = r#"
+        struct Mixed {
+            #[zk_private(constraint = "value > b")]
+            a: i64,
+            #[zk_private]
+            b: u64,
+        }
+    "#;
+    let ir = transform_to_ir(parse_contract(input).unwrap()).unwrap();
+    let err = validate_circuit_ir(&ir).unwrap_err().to_string();
+    assert!(err.contains("mixes signed and unsigned"), "got: {}", err);
+
+    let input = r#"
+        struct Mixed {
+            #[zk_private]
+            a: i64,
+            #[zk_private]
+            b: u64,
+            #[zk_private]
+            c: i64,
+        }
+    "#;
+    let ir = ir_with_relation(
+        input,
+        InterFieldConstraint::ArithmeticRelation {
+            result_field: "c".to_string(),
+            operation: ArithOp::Add,
+            operands: vec!["a".to_string(), "b".to_string()],
+        },
+    );
+    let err = validate_circuit_ir(&ir).unwrap_err().to_string();
+    assert!(err.contains("mixes signed and unsigned"), "got: {}", err);
+}
+
+// ============================================================================
+// BYTES32 AND ADDRESS LIMB TESTS
+// ============================================================================
+
+fn limb(repr: [u8; 32]) -> TestField {
+    TestField::from_repr(repr).unwrap()
+}
+
+fn run_limbs(input: &str, witnesses: Vec<TestField>) -> bool {
+    let ir = transform_to_ir(parse_contract(input).unwrap()).unwrap();
+    validate_circuit_ir(&ir).unwrap();
+    let k = ir.circuit_config.k();
+    let circuit = CircuitBuilder::new(ir).build::<TestField>().with_witnesses(witnesses).unwrap();
+    MockProver::run(k, &circuit, vec![vec![]]).unwrap().verify().is_ok()
+}
+
+#[test]
+fn test_mock_prover_bytes32_word_as_two_limbs() {
+    let input = r#"struct Note { #[zk_private] salt: [u8; 32] }"#;
+    // Above the Pasta modulus: does not fit in a single field element
+    let word = [0xff_u8; 32];
+    let [hi, lo] = encode_bytes32(&word);
+    assert!(run_limbs(input, vec![limb(hi), limb(lo)]));
+
+    let mut wide = lo;
+    wide[16] = 1; // 2^128
+    assert!(!run_limbs(input, vec![limb(hi), limb(wide)]));
+}
+
+#[test]
+fn test_mock_prover_address_single_limb() {
+    let input = r#"struct Owner { #[zk_private] owner: Address }"#;
+    let address = [0xee_u8; 20];
+    assert!(run_limbs(input, vec![limb(encode_address(&address))]));
+
+    let mut wide = encode_address(&address);
+    wide[20] = 1; // 2^160
+    assert!(!run_limbs(input, vec![limb(wide)]));
+}
+
+// ============================================================================
+// EXPRESSION CHECK TESTS
+// ============================================================================
+
+fn run_expression(input: &str, witnesses: &[u64]) -> bool {
+    let ir = transform_to_ir(parse_contract(input).unwrap()).unwrap();
+    validate_circuit_ir(&ir).unwrap();
+    run_relation(&ir, witnesses)
+}
+
+const COLLATERAL_RATIO: &str = r#"
+    struct Loan {
+        #[zk_private(constraint = "value * 100 >= debt * 150")]
+        collateral: u64,
+        #[zk_private]
+        debt: u64,
+    }
+"#;
+
+#[test]
+fn test_mock_prover_expression_collateral_ratio() {
+    assert!(run_expression(COLLATERAL_RATIO, &[150, 100]));
+    assert!(run_expression(COLLATERAL_RATIO, &[1_000_000, 0]));
+    assert!(!run_expression(COLLATERAL_RATIO, &[149, 100]));
+}
+
+#[test]
+fn test_mock_prover_expression_products_do_not_wrap() {
+    // 100 * max < 150 * max over the integers, even though both products exceed 2^64
+    assert!(!run_expression(COLLATERAL_RATIO, &[u64::MAX, u64::MAX]));
+    assert!(run_expression(COLLATERAL_RATIO, &[u64::MAX, u64::MAX / 3 * 2]));
+}
+
+#[test]
+fn test_mock_prover_expression_conjunction() {
+    let input = r#"
+        struct Sum {
+            #[zk_private(constraint = "a + b == value && value < 1000")]
+            total: u32,
+            #[zk_private]
+            a: u32,
+            #[zk_private]
+            b: u32,
+        }
+    "#;
+    assert!(run_expression(input, &[700, 300, 400]));
+    assert!(!run_expression(input, &[701, 300, 400]));
+    assert!(!run_expression(input, &[1500, 1000, 500]));
+}
+
+#[test]
+fn test_mock_prover_expression_on_signed_fields() {
+    let input = r#"
+        struct Drift {
+            #[zk_private(constraint = "(value - b) < limit")]
+            a: i64,
+            #[zk_private]
+            b: i64,
+            #[zk_private]
+            limit: u64,
+        }
+    "#;
+    let run =
+        |a: i64, b: i64, limit: u64| run_expression(input, &[encode_i64(a), encode_i64(b), limit]);
+    assert!(run(-5, -20, 16));
+    assert!(!run(-5, -20, 15));
+    assert!(run(i64::MIN, i64::MAX, 0));
+    assert!(!run(i64::MAX, i64::MIN, u64::MAX));
+}
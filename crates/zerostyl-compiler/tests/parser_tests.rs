@@ -1,6 +1,9 @@
 //! Tests for the parser module
 
-use zerostyl_compiler::{parse_contract, ParsedContract, PrivateField};
+use zerostyl_compiler::{
+    encode_i64, parse_contract, parse_contracts, ArithExpr, ComparisonOp, Constraint, HashType,
+    InterFieldConstraint, ParsedContract, PrivateField,
+};
 
 // ============================================================================
 // BASIC PARSING TESTS
@@ -114,10 +117,430 @@ fn test_parse_tuple_struct() {
     assert_eq!(result.unwrap().private_fields.len(), 0);
 }
 
+// ============================================================================
+// ATTRIBUTE ARGUMENT TESTS
+// ============================================================================
+
+#[test]
+fn test_parse_commit_range_and_comparison_arguments() {
+    let input = r#"
+        struct Vault {
+            #[zk_private(range = "150..=300", constraint = "value > 100", commit = "poseidon")]
+            collateral: u64,
+        }
+    "#;
+
+    let result = parse_contract(input).unwrap();
+    let field = &result.private_fields[0];
+    assert_eq!(
+        field.constraints,
+        vec![
+            Constraint::RangeProof { min: 150, max: 300 },
+            Constraint::Comparison { operator: ComparisonOp::GreaterThan, value: 100 },
+            Constraint::Commitment { hash_type: HashType::Poseidon },
+        ]
+    );
+    assert!(field.inter_field_constraints.is_empty());
+}
+
+#[test]
+fn test_parse_comparison_with_u128_constant() {
+    let input = r#"
+        struct Supply {
+            #[zk_private(constraint = "value > 18446744073709551616")]
+            total: u128,
+        }
+    "#;
+
+    let result = parse_contract(input).unwrap();
+    assert_eq!(
+        result.private_fields[0].constraints,
+        vec![Constraint::Comparison { operator: ComparisonOp::GreaterThan, value: 1 << 64 }]
+    );
+}
+
+#[test]
+fn test_parse_commit_with_randomness_is_commitment_verification() {
+    let input = r#"
+        struct Note {
+            #[zk_private(commit = "poseidon", randomness = "blinding")]
+            amount: u64,
+            #[zk_private]
+            blinding: Field,
+        }
+    "#;
+
+    let result = parse_contract(input).unwrap();
+    let field = &result.private_fields[0];
+    assert_eq!(field.constraints, vec![Constraint::Commitment { hash_type: HashType::Poseidon }]);
+    assert_eq!(
+        field.inter_field_constraints,
+        vec![InterFieldConstraint::CommitmentVerification {
+            commitment: "amount_commitment".to_string(),
+            value: "amount".to_string(),
+            randomness: "blinding".to_string(),
+            hash_type: HashType::Poseidon,
+        }]
+    );
+}
+
+#[test]
+fn test_parse_randomness_without_commit_fails() {
+    let input = r#"
+        struct Note {
+            #[zk_private(randomness = "blinding")]
+            amount: u64,
+            #[zk_private]
+            blinding: Field,
+        }
+    "#;
+
+    let err = parse_contract(input).unwrap_err().to_string();
+    assert!(err.contains("field 'amount'"), "got: {}", err);
+    assert!(err.contains("`randomness` requires `commit`"), "got: {}", err);
+}
+
+#[test]
+fn test_parse_half_open_range_is_exclusive() {
+    let input = r#"
+        struct Bounded {
+            #[zk_private(range = "0..1_000")]
+            amount: u64,
+        }
+    "#;
+
+    let result = parse_contract(input).unwrap();
+    assert_eq!(
+        result.private_fields[0].constraints,
+        vec![Constraint::RangeProof { min: 0, max: 999 }]
+    );
+}
+
+#[test]
+fn test_parse_comparison_against_field_is_inter_field() {
+    let input = r#"
+        struct Loan {
+            #[zk_private(constraint = "value >= threshold")]
+            collateral: u64,
+            #[zk_private]
+            threshold: u64,
+        }
+    "#;
+
+    let result = parse_contract(input).unwrap();
+    assert!(result.private_fields[0].constraints.is_empty());
+    assert_eq!(
+        result.private_fields[0].inter_field_constraints,
+        vec![InterFieldConstraint::ComparisonCheck {
+            left_field: "collateral".to_string(),
+            right_field: "threshold".to_string(),
+            op: ComparisonOp::GreaterThanOrEqual,
+        }]
+    );
+}
+
+#[test]
+fn test_parse_merkle_member_takes_depth_from_siblings_array() {
+    let input = r#"
+        struct Membership {
+            #[zk_private(constraint = "merkle_member(value, root, siblings, indices)")]
+            leaf: Field,
+            #[zk_private]
+            root: Field,
+            #[zk_private]
+            siblings: [Field; 20],
+            #[zk_private]
+            indices: [bool; 20],
+        }
+    "#;
+
+    let result = parse_contract(input).unwrap();
+    assert_eq!(
+        result.private_fields[0].constraints,
+        vec![Constraint::MerkleProof {
+            leaf_field: "leaf".to_string(),
+            path_field: "siblings".to_string(),
+            root_field: "root".to_string(),
+            indices_field: "indices".to_string(),
+            tree_depth: 20,
+        }]
+    );
+}
+
+#[test]
+fn test_parse_bare_and_empty_attributes_have_no_constraints() {
+    let input = r#"
+        struct Plain {
+            #[zk_private]
+            a: u64,
+            #[zk_private()]
+            b: u64,
+        }
+    "#;
+
+    let result = parse_contract(input).unwrap();
+    assert_eq!(result.private_fields.len(), 2);
+    assert!(result.private_fields.iter().all(|f| f.constraints.is_empty()));
+}
+
+// ============================================================================
+// MULTI-STRUCT TESTS
+// ============================================================================
+
+#[test]
+fn test_parse_contracts_returns_every_annotated_struct() {
+    let input = r#"
+        struct Config {
+            fee: u64,
+        }
+
+        struct Position {
+            #[zk_private]
+            collateral: u64,
+        }
+
+        mod lending {
+            struct Loan {
+                #[zk_private]
+                principal: u64,
+                #[zk_private]
+                rate: u32,
+            }
+
+            mod nested {
+                struct Note {
+                    #[zk_private]
+                    secret: Field,
+                }
+            }
+        }
+    "#;
+
+    let contracts = parse_contracts(input).unwrap();
+    let names: Vec<&str> = contracts.iter().map(|c| c.contract_name.as_str()).collect();
+    assert_eq!(names, vec!["Position", "Loan", "Note"]);
+    assert_eq!(contracts[1].private_fields.len(), 2);
+}
+
+#[test]
+fn test_parse_contracts_without_annotations_lists_skipped_structs() {
+    let input = r#"
+        struct Config {
+            fee: u64,
+        }
+
+        mod inner {
+            struct Settings {
+                owner: Address,
+            }
+        }
+    "#;
+
+    let err = parse_contracts(input).unwrap_err().to_string();
+    assert!(err.contains("No struct with #[zk_private] fields"));
+    assert!(err.contains("Config"));
+    assert!(err.contains("inner::Settings"));
+}
+
+#[test]
+fn test_parse_contracts_rejects_duplicate_names() {
+    let input = r#"
+        struct Note {
+            #[zk_private]
+            a: u64,
+        }
+
+        mod other {
+            struct Note {
+                #[zk_private]
+                b: u64,
+            }
+        }
+    "#;
+
+    let err = parse_contracts(input).unwrap_err().to_string();
+    assert!(err.contains("Duplicate"));
+    assert!(err.contains("other::Note"));
+}
+
 // ============================================================================
 // ERROR HANDLING TESTS
 // ============================================================================
 
+#[test]
+fn test_parse_unknown_attribute_key_fails() {
+    let input = r#"
+        struct Bad {
+            #[zk_private(foobar = "1")]
+            x: u64,
+        }
+    "#;
+
+    let err = parse_contract(input).unwrap_err().to_string();
+    assert!(err.contains("Invalid annotation"));
+    assert!(err.contains("foobar"));
+}
+
+#[test]
+fn test_parse_invalid_range_arguments_fail() {
+    for range in ["1000..", "10..=5", "0..0", "low..=10"] {
+        let input = format!("struct Bad {{ #[zk_private(range = \"{}\")] x: u64 }}", range);
+        assert!(parse_contract(&input).is_err(), "range '{}' should be rejected", range);
+    }
+}
+
+#[test]
+fn test_parse_signed_bounds_are_offset_encoded() {
+    let input = r#"
+        struct Pnl {
+            #[zk_private(range = "-1000..=1000", constraint = "value > -50")]
+            delta: i64,
+        }
+    "#;
+
+    let result = parse_contract(input).unwrap();
+    assert_eq!(
+        result.private_fields[0].constraints,
+        vec![
+            Constraint::RangeProof {
+                min: u128::from(encode_i64(-1000)),
+                max: u128::from(encode_i64(1000)),
+            },
+            Constraint::Comparison {
+                operator: ComparisonOp::GreaterThan,
+                value: encode_i64(-50).into()
+            },
+        ]
+    );
+}
+
+#[test]
+fn test_parse_signed_bounds_accept_i64_limits() {
+    let input = r#"
+        struct Pnl {
+            #[zk_private(range = "i64::MIN..0")]
+            loss: i64,
+        }
+    "#;
+
+    let result = parse_contract(input).unwrap();
+    assert_eq!(
+        result.private_fields[0].constraints,
+        vec![Constraint::RangeProof { min: 0, max: u128::from(encode_i64(-1)) }]
+    );
+}
+
+#[test]
+fn test_parse_signed_bounds_on_i64_arrays() {
+    let input = r#"
+        struct Deltas {
+            #[zk_private(range = "-5..=5")]
+            xs: [i64; 2],
+        }
+    "#;
+
+    let result = parse_contract(input).unwrap();
+    assert_eq!(
+        result.private_fields[0].constraints,
+        vec![Constraint::RangeProof {
+            min: u128::from(encode_i64(-5)),
+            max: u128::from(encode_i64(5)),
+        }]
+    );
+}
+
+#[test]
+fn test_parse_negative_bounds_rejected_for_unsigned_fields() {
+    for attr in [r#"range = "-5..=5""#, r#"constraint = "value > -1""#] {
+        let input = format!("struct Bad {{ #[zk_private({})] x: u64 }}", attr);
+        assert!(parse_contract(&input).is_err(), "'{}' should be rejected on u64", attr);
+    }
+    let input =
+        r#"struct Bad { #[zk_private(constraint = "value > 9223372036854775808")] x: i64 }"#;
+    assert!(parse_contract(input).is_err(), "bound above i64::MAX should be rejected");
+}
+
+#[test]
+fn test_parse_comparison_with_non_value_lhs_is_expression() {
+    let input = r#"
+        struct Limit {
+            #[zk_private(constraint = "threshold >= value")]
+            x: u64,
+            #[zk_private]
+            threshold: u64,
+        }
+    "#;
+
+    let result = parse_contract(input).unwrap();
+    assert_eq!(
+        result.private_fields[0].inter_field_constraints,
+        vec![InterFieldConstraint::ExpressionCheck {
+            lhs: ArithExpr::field("threshold"),
+            op: ComparisonOp::GreaterThanOrEqual,
+            rhs: ArithExpr::field("x"),
+        }]
+    );
+}
+
+#[test]
+fn test_parse_expression_constraint() {
+    let input = r#"
+        struct Loan {
+            #[zk_private(constraint = "value * 100 >= debt * 150 && value > 10")]
+            collateral: u64,
+            #[zk_private]
+            debt: u64,
+        }
+    "#;
+
+    let field = &parse_contract(input).unwrap().private_fields[0];
+    assert_eq!(
+        field.inter_field_constraints,
+        vec![InterFieldConstraint::ExpressionCheck {
+            lhs: ArithExpr::field("collateral") * ArithExpr::constant(100),
+            op: ComparisonOp::GreaterThanOrEqual,
+            rhs: ArithExpr::field("debt") * ArithExpr::constant(150),
+        }]
+    );
+    // Simple comparisons inside a conjunction keep their dedicated lowering
+    assert!(field
+        .constraints
+        .contains(&Constraint::Comparison { operator: ComparisonOp::GreaterThan, value: 10 }));
+}
+
+#[test]
+fn test_parse_expression_rejects_unsupported_operators() {
+    for (constraint, expected) in [
+        ("value / 2 > debt", "division is not supported"),
+        ("value > 1 || value < 0", "unsupported operator '||'"),
+        ("value + 1", "unsupported operator '+'"),
+        ("value % 2 == debt", "unsupported operator '%'"),
+        ("f(value) == debt", "unsupported term"),
+    ] {
+        let input = format!(
+            "struct Bad {{ #[zk_private(constraint = \"{}\")] x: u64, #[zk_private] debt: u64 }}",
+            constraint
+        );
+        let err = parse_contract(&input).unwrap_err().to_string();
+        assert!(err.contains(expected), "'{}' gave: {}", constraint, err);
+    }
+}
+
+#[test]
+fn test_parse_merkle_member_without_array_siblings_fails() {
+    let input = r#"
+        struct Bad {
+            #[zk_private(constraint = "merkle_member(value, root, siblings, indices)")]
+            leaf: Field,
+            #[zk_private]
+            siblings: Field,
+        }
+    "#;
+
+    let err = parse_contract(input).unwrap_err().to_string();
+    assert!(err.contains("fixed-size array"));
+}
+
 #[test]
 fn test_parse_invalid_syntax() {
     let input = "not valid rust code";
@@ -125,6 +548,56 @@ fn test_parse_invalid_syntax() {
     assert!(parse_contract(input).is_err());
 }
 
+#[test]
+fn test_syntax_error_has_location() {
+    let input = "struct Broken {\n    amount: u64\n    other: u64,\n}\n";
+
+    let err = parse_contract(input).unwrap_err();
+    let diagnostic = err.diagnostic().expect("syntax errors carry a location");
+    assert_eq!(diagnostic.span.line, 3);
+    assert_eq!(diagnostic.span.snippet, "    other: u64,");
+}
+
+#[test]
+fn test_annotation_error_points_at_value() {
+    let input = r#"
+struct Bounded {
+    #[zk_private(range = "10..5")]
+    amount: u64,
+}
+"#;
+
+    let err = parse_contract(input).unwrap_err().with_file("bounded.rs");
+    let diagnostic = err.diagnostic().unwrap();
+    assert_eq!((diagnostic.span.line, diagnostic.span.column), (3, 26));
+    assert!(err.to_string().starts_with("bounded.rs:3:26: Invalid annotation: field 'amount'"));
+    assert_eq!(
+        diagnostic.render(),
+        "\
+error: Invalid annotation: field 'amount': range '10..5' is empty
+ --> bounded.rs:3:26
+  |
+3 |     #[zk_private(range = \"10..5\")]
+  |                          ^^^^^^^
+"
+    );
+}
+
+#[test]
+fn test_randomness_without_commit_has_help() {
+    let input = r#"
+        struct Note {
+            #[zk_private(randomness = "blinding")]
+            amount: u64,
+        }
+    "#;
+
+    let err = parse_contract(input).unwrap_err();
+    let diagnostic = err.diagnostic().unwrap();
+    assert_eq!(diagnostic.span.line, 3);
+    assert_eq!(diagnostic.help.as_deref(), Some("add `commit = \"poseidon\"` to this attribute"));
+}
+
 // ============================================================================
 // EQUALITY TESTS
 // ============================================================================
@@ -136,6 +609,9 @@ fn test_parsed_contract_equality() {
         private_fields: vec![PrivateField {
             name: "field1".to_string(),
             field_type: "u64".to_string(),
+            constraints: vec![],
+            inter_field_constraints: vec![],
+            span: None,
         }],
     };
 
@@ -144,6 +620,9 @@ fn test_parsed_contract_equality() {
         private_fields: vec![PrivateField {
             name: "field1".to_string(),
             field_type: "u64".to_string(),
+            constraints: vec![],
+            inter_field_constraints: vec![],
+            span: None,
         }],
     };
 
@@ -2,6 +2,7 @@ use proc_macro2::TokenStream;
 use quote::{format_ident, quote};
 
 use crate::error::{ExporterError, Result};
+use crate::parser::ExprSpec;
 use crate::resolver::{ComparisonOp, GadgetBinding, ResolvedAttr, MERKLE_DEPTH};
 
 pub fn emit_circuit(circuit_name: &str, attrs: &[ResolvedAttr]) -> Result<String> {
@@ -76,6 +77,9 @@ struct ChipUsage {
     range: bool,
     comparison: bool,
     merkle: bool,
+    arithmetic: bool,
+    /// Some relation loads a constant, which needs a constants column.
+    constants: bool,
 }
 
 fn collect_chip_usage(attrs: &[ResolvedAttr]) -> ChipUsage {
@@ -87,6 +91,11 @@ fn collect_chip_usage(attrs: &[ResolvedAttr]) -> ChipUsage {
                 GadgetBinding::Range { .. } => u.range = true,
                 GadgetBinding::Comparison { .. } => u.comparison = true,
                 GadgetBinding::MerkleMember { .. } => u.merkle = true,
+                GadgetBinding::Relation { lhs, rhs, .. } => {
+                    u.arithmetic = true;
+                    u.comparison = true;
+                    u.constants |= has_const(lhs) || has_const(rhs);
+                }
             }
         }
     }
@@ -141,6 +150,10 @@ fn emit_imports(chips: &ChipUsage) -> TokenStream {
         gadget_items.push(quote! { MerkleTreeChip });
         gadget_items.push(quote! { MerkleTreeConfig });
     }
+    if chips.arithmetic {
+        gadget_items.push(quote! { ArithmeticChip });
+        gadget_items.push(quote! { ArithmeticConfig });
+    }
     quote! {
         use halo2_proofs::{
             circuit::{Layouter, SimpleFloorPlanner, Value},
@@ -191,6 +204,11 @@ fn emit_witness_fields(attrs: &[ResolvedAttr]) -> Vec<TokenStream> {
                     add(siblings_var, FieldKind::VecScalar, &mut seen, &mut ordered);
                     add(indices_var, FieldKind::VecScalar, &mut seen, &mut ordered);
                 }
+                GadgetBinding::Relation { lhs, rhs, .. } => {
+                    for var in lhs.vars().into_iter().chain(rhs.vars()) {
+                        add(var, FieldKind::Scalar, &mut seen, &mut ordered);
+                    }
+                }
                 GadgetBinding::Range { .. } => {}
             }
         }
@@ -253,6 +271,11 @@ fn emit_struct_derive(
                     push_vec(siblings_var, &mut inits, &mut seen);
                     push_vec(indices_var, &mut inits, &mut seen);
                 }
+                GadgetBinding::Relation { lhs, rhs, .. } => {
+                    for var in lhs.vars().into_iter().chain(rhs.vars()) {
+                        push_scalar(var, &mut inits, &mut seen);
+                    }
+                }
                 GadgetBinding::Range { .. } => {}
             }
         }
@@ -282,6 +305,9 @@ fn emit_config_fields(chips: &ChipUsage) -> Vec<TokenStream> {
     if chips.merkle {
         fields.push(quote! { merkle_config: MerkleTreeConfig });
     }
+    if chips.arithmetic {
+        fields.push(quote! { arithmetic_config: ArithmeticConfig });
+    }
     fields
 }
 
@@ -304,6 +330,16 @@ fn emit_configure_body(chips: &ChipUsage) -> TokenStream {
         stmts.push(quote! { let merkle_config = MerkleTreeChip::configure(meta); });
         struct_fields.push(quote! { merkle_config });
     }
+    if chips.arithmetic {
+        stmts.push(quote! { let arithmetic_config = ArithmeticChip::configure(meta); });
+        struct_fields.push(quote! { arithmetic_config });
+    }
+    if chips.constants {
+        stmts.push(quote! {
+            let constants = meta.fixed_column();
+            meta.enable_constant(constants);
+        });
+    }
     stmts.push(quote! {
         let instance = meta.instance_column();
         meta.enable_equality(instance);
@@ -321,6 +357,7 @@ fn binding_priority(b: &GadgetBinding) -> u8 {
         GadgetBinding::Range { .. } => 1,
         GadgetBinding::Comparison { .. } => 2,
         GadgetBinding::MerkleMember { .. } => 3,
+        GadgetBinding::Relation { .. } => 4,
     }
 }
 
@@ -347,6 +384,11 @@ fn emit_synthesize_body(chips: &ChipUsage, attrs: &[ResolvedAttr]) -> Result<Tok
             let merkle_chip = MerkleTreeChip::construct(config.merkle_config.clone());
         });
     }
+    if chips.arithmetic {
+        stmts.push(quote! {
+            let arithmetic_chip = ArithmeticChip::construct(config.arithmetic_config);
+        });
+    }
 
     let mut instance_idx: usize = 0;
 
@@ -387,6 +429,9 @@ fn emit_synthesize_body(chips: &ChipUsage, attrs: &[ResolvedAttr]) -> Result<Tok
                 GadgetBinding::MerkleMember { siblings_var, indices_var, .. } => {
                     stmts.extend(emit_merkle(&attr.param_name, siblings_var, indices_var)?);
                 }
+                GadgetBinding::Relation { lhs, op, rhs, num_bits } => {
+                    stmts.push(emit_relation(lhs, *op, rhs, *num_bits)?);
+                }
             }
         }
     }
@@ -479,7 +524,7 @@ fn emit_comparison(
     let value_cell = format_ident!("{}_cmp_value", param_name);
     let load_value_label = format!("load {param_name} for comparison");
     let cmp_label = format!("{} {} {}", param_name, op_symbol(op), other);
-    let method = op_method(op)?;
+    let method = op_method(op);
     let method_ident = format_ident!("{}", method);
 
     let (load_other_stmts, other_cell_token) = if is_simple_ident(other) {
@@ -508,17 +553,116 @@ fn emit_comparison(
         )?;
     }];
     stmts.extend(load_other_stmts);
+    // Equality checks need no range check, so they take no bit width.
+    let num_bits_arg = match op {
+        ComparisonOp::Eq | ComparisonOp::Neq => quote! {},
+        _ => quote! { #num_bits, },
+    };
     stmts.push(quote! {
         comparison_chip.#method_ident(
             layouter.namespace(|| #cmp_label),
             #value_cell,
             #other_cell_token,
-            #num_bits,
+            #num_bits_arg
         )?;
     });
     Ok(stmts)
 }
 
+/// Loads each variable once, evaluates both sides with `ArithmeticChip`, then compares them.
+fn emit_relation(
+    lhs: &ExprSpec,
+    op: ComparisonOp,
+    rhs: &ExprSpec,
+    num_bits: usize,
+) -> Result<TokenStream> {
+    let mut vars = lhs.vars();
+    for var in rhs.vars() {
+        if !vars.contains(&var) {
+            vars.push(var);
+        }
+    }
+    let loads = vars.iter().map(|var| {
+        let field = format_ident!("{}", var);
+        let cell = relation_cell(var);
+        let label = format!("load {var} for relation");
+        quote! {
+            let #cell = arithmetic_chip.load_value(layouter.namespace(|| #label), self.#field)?;
+        }
+    });
+    let lhs_tokens = emit_expr(lhs)?;
+    let rhs_tokens = emit_expr(rhs)?;
+    let cmp_label = format!("{} {} {}", lhs, op_symbol(op), rhs);
+    let method_ident = format_ident!("{}", op_method(op));
+    let num_bits_arg = match op {
+        ComparisonOp::Eq | ComparisonOp::Neq => quote! {},
+        _ => quote! { #num_bits, },
+    };
+    Ok(quote! {
+        {
+            #( #loads )*
+            let relation_lhs = #lhs_tokens;
+            let relation_rhs = #rhs_tokens;
+            comparison_chip.#method_ident(
+                layouter.namespace(|| #cmp_label),
+                relation_lhs,
+                relation_rhs,
+                #num_bits_arg
+            )?;
+        }
+    })
+}
+
+fn relation_cell(var: &str) -> syn::Ident {
+    format_ident!("{}_relation_value", var)
+}
+
+/// An expression evaluating to the `AssignedCell` holding `expr`.
+fn emit_expr(expr: &ExprSpec) -> Result<TokenStream> {
+    let (op, l, r) = match expr {
+        ExprSpec::Var(var) => {
+            let cell = relation_cell(var);
+            return Ok(quote! { #cell.clone() });
+        }
+        ExprSpec::Const(c) => {
+            let c = u64::try_from(*c).map_err(|_| {
+                ExporterError::Parse(format!("relation constant {c} does not fit in u64"))
+            })?;
+            let label = format!("relation constant {c}");
+            return Ok(quote! {
+                arithmetic_chip.load_constant(layouter.namespace(|| #label), Fp::from(#c))?
+            });
+        }
+        ExprSpec::Add(l, r) => (quote! { Add }, l, r),
+        ExprSpec::Sub(l, r) => (quote! { Sub }, l, r),
+        ExprSpec::Mul(l, r) => (quote! { Mul }, l, r),
+    };
+    let (l, r) = (emit_expr(l)?, emit_expr(r)?);
+    let label = expr.to_string();
+    Ok(quote! {
+        {
+            let lhs = #l;
+            let rhs = #r;
+            arithmetic_chip.apply(
+                layouter.namespace(|| #label),
+                zerostyl_compiler::ArithOp::#op,
+                &lhs,
+                &rhs,
+            )?
+        }
+    })
+}
+
+fn has_const(expr: &ExprSpec) -> bool {
+    match expr {
+        ExprSpec::Var(_) => false,
+        ExprSpec::Const(_) => true,
+        ExprSpec::Add(l, r) | ExprSpec::Sub(l, r) | ExprSpec::Mul(l, r) => {
+            has_const(l) || has_const(r)
+        }
+    }
+}
+
 fn emit_merkle(
     param_name: &str,
     siblings_var: &str,
@@ -570,15 +714,14 @@ fn emit_merkle(
     }])
 }
 
-fn op_method(op: ComparisonOp) -> Result<&'static str> {
+fn op_method(op: ComparisonOp) -> &'static str {
     match op {
-        ComparisonOp::Gt => Ok("assert_gt"),
-        ComparisonOp::Gte => Ok("assert_gte"),
-        ComparisonOp::Lt => Ok("assert_lt"),
-        ComparisonOp::Lte => Ok("assert_lte"),
-        ComparisonOp::Eq => Err(ExporterError::Parse(
-            "equality comparison not supported by ComparisonChip; use a different gadget".into(),
-        )),
+        ComparisonOp::Gt => "assert_gt",
+        ComparisonOp::Gte => "assert_gte",
+        ComparisonOp::Lt => "assert_lt",
+        ComparisonOp::Lte => "assert_lte",
+        ComparisonOp::Eq => "assert_eq",
+        ComparisonOp::Neq => "assert_neq",
     }
 }
 
@@ -589,6 +732,7 @@ fn op_symbol(op: ComparisonOp) -> &'static str {
         ComparisonOp::Lt => "<",
         ComparisonOp::Lte => "<=",
         ComparisonOp::Eq => "==",
+        ComparisonOp::Neq => "!=",
     }
 }
 
@@ -1003,6 +1147,11 @@ fn emit_witness_json_fields(attrs: &[ResolvedAttr]) -> Vec<TokenStream> {
                     add(siblings_var, FieldKind::VecScalar);
                     add(indices_var, FieldKind::VecScalar);
                 }
+                GadgetBinding::Relation { lhs, rhs, .. } => {
+                    for var in lhs.vars().into_iter().chain(rhs.vars()) {
+                        add(var, FieldKind::Scalar);
+                    }
+                }
                 GadgetBinding::Range { .. } => {}
             }
         }
@@ -1089,6 +1238,11 @@ fn emit_build_inputs_body(attrs: &[ResolvedAttr], circuit_ident: &syn::Ident) ->
                     push_vec(siblings_var, &mut seen, &mut vec_parses, &mut circuit_inits);
                     push_vec(indices_var, &mut seen, &mut vec_parses, &mut circuit_inits);
                 }
+                GadgetBinding::Relation { lhs, rhs, .. } => {
+                    for var in lhs.vars().into_iter().chain(rhs.vars()) {
+                        push_scalar(var, &mut seen, &mut scalar_parses, &mut circuit_inits);
+                    }
+                }
                 GadgetBinding::Range { .. } => {}
             }
         }
@@ -1131,6 +1285,9 @@ fn count_witness_fields(attrs: &[ResolvedAttr]) -> usize {
                     seen.insert(siblings_var.clone());
                     seen.insert(indices_var.clone());
                 }
+                GadgetBinding::Relation { lhs, rhs, .. } => {
+                    seen.extend(lhs.vars().into_iter().chain(rhs.vars()).map(String::from));
+                }
                 GadgetBinding::Range { .. } => {}
             }
         }
@@ -1241,6 +1398,21 @@ fn emit_witness_schema_fields(attrs: &[ResolvedAttr]) -> Result<Vec<TokenStream>
                         });
                     }
                 }
+                GadgetBinding::Relation { lhs, rhs, .. } => {
+                    for var in lhs.vars().into_iter().chain(rhs.vars()) {
+                        if seen.insert(var.to_string()) {
+                            let kind = field_type_token(&attr.param_type)?;
+                            out.push(quote! {
+                                WitnessField {
+                                    name: #var.into(),
+                                    kind: #kind,
+                                    visibility: FieldVisibility::Private,
+                                    description: None,
+                                }
+                            });
+                        }
+                    }
+                }
                 GadgetBinding::Range { .. } => {}
             }
         }
@@ -1380,11 +1552,15 @@ mod tests {
     }
 
     #[test]
-    fn comparison_eq_rejected() {
-        let attrs =
-            vec![resolved("x", "u64", vec![AttrSpec::Constraint(Constraint::Eq("y".into()))])];
-        let err = emit_circuit("foo", &attrs).unwrap_err();
-        assert!(format!("{err}").contains("equality"));
+    fn comparison_eq_and_neq_use_equality_methods() {
+        for (op, method) in
+            [(Constraint::Eq("y".into()), "assert_eq"), (Constraint::Neq("y".into()), "assert_neq")]
+        {
+            let attrs = vec![resolved("x", "u64", vec![AttrSpec::Constraint(op)])];
+            let src = emit_circuit("foo", &attrs).unwrap();
+            assert!(src.contains(method), "missing {method} in:\n{src}");
+            assert!(!src.contains("64usize"), "equality must not pass a bit width:\n{src}");
+        }
     }
 
     #[test]
@@ -1399,6 +1575,58 @@ mod tests {
         assert!(src.contains("threshold : Value < Fp >") || src.contains("threshold: Value<Fp>"));
     }
 
+    fn relation(lhs: ExprSpec, op: crate::resolver::ComparisonOp, rhs: ExprSpec) -> AttrSpec {
+        AttrSpec::Relation(crate::parser::RelationSpec { lhs, op, rhs })
+    }
+
+    #[test]
+    fn relation_emits_arithmetic_and_comparison() {
+        let lhs =
+            ExprSpec::Mul(Box::new(ExprSpec::Var("value".into())), Box::new(ExprSpec::Const(100)));
+        let rhs =
+            ExprSpec::Mul(Box::new(ExprSpec::Var("debt".into())), Box::new(ExprSpec::Const(150)));
+        let attrs =
+            vec![resolved("collateral", "u64", vec![relation(lhs, ComparisonOp::Gte, rhs)])];
+        let src = emit_circuit("loan", &attrs).unwrap();
+        parse_as_file(&src);
+        assert!(src.contains("ArithmeticChip :: configure"));
+        assert!(src.contains("meta . enable_constant (constants)"));
+        assert!(src.contains("load_constant"));
+        assert!(src.contains("zerostyl_compiler :: ArithOp :: Mul"));
+        assert!(src.contains("assert_gte"));
+        assert!(src.contains("73usize"));
+        assert!(src.contains("debt : Value < Fp >"));
+    }
+
+    #[test]
+    fn relation_without_constants_needs_no_constants_column() {
+        let lhs =
+            ExprSpec::Add(Box::new(ExprSpec::Var("a".into())), Box::new(ExprSpec::Var("b".into())));
+        let attrs = vec![resolved(
+            "x",
+            "u32",
+            vec![relation(lhs, ComparisonOp::Eq, ExprSpec::Var("value".into()))],
+        )];
+        let src = emit_circuit("sum", &attrs).unwrap();
+        parse_as_file(&src);
+        assert!(!src.contains("enable_constant"));
+        assert!(src.contains("assert_eq"));
+        let descriptor = emit_descriptor("sum", &attrs).unwrap();
+        assert!(descriptor.contains("const NUM_PRIVATE_WITNESSES : usize = 3usize"));
+    }
+
+    #[test]
+    fn relation_rejects_constant_wider_than_u64() {
+        let lhs = ExprSpec::Var("value".into());
+        let attrs = vec![resolved(
+            "x",
+            "u64",
+            vec![relation(lhs, ComparisonOp::Eq, ExprSpec::Const(u128::from(u64::MAX) + 1))],
+        )];
+        let err = emit_circuit("big", &attrs).unwrap_err();
+        assert!(format!("{err}").contains("does not fit in u64"));
+    }
+
     #[test]
     fn multi_gadget_composition_parses() {
         let attrs = vec![resolved(
@@ -147,6 +147,18 @@ fn build_witness_schema(attrs: &[ResolvedAttr]) -> Result<WitnessSchema> {
                         });
                     }
                 }
+                GadgetBinding::Relation { lhs, rhs, .. } => {
+                    for var in lhs.vars().into_iter().chain(rhs.vars()) {
+                        if seen.insert(var.to_string()) {
+                            fields.push(WitnessField {
+                                name: var.to_string(),
+                                kind: field_type_from(&attr.param_type)?,
+                                visibility: FieldVisibility::Private,
+                                description: None,
+                            });
+                        }
+                    }
+                }
                 GadgetBinding::Range { .. } => {}
             }
         }
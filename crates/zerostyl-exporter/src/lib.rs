@@ -15,7 +15,8 @@ pub use codegen::{emit_circuit, emit_descriptor, emit_transformed_contract};
 pub use error::{ExporterError, Result};
 pub use extractor::{emit_abi_json, from_attrs, from_descriptor};
 pub use parser::{
-    parse_fn, AttrSpec, CommitScheme, Constraint, MerkleMemberSpec, RangeSpec, ZkPrivateAttr,
+    parse_fn, AttrSpec, CommitScheme, Constraint, ExprSpec, MerkleMemberSpec, RangeSpec,
+    RelationSpec, ZkPrivateAttr,
 };
 pub use resolver::{resolve, resolve_all, ComparisonOp, GadgetBinding, ResolvedAttr, MERKLE_DEPTH};
 pub use schema::{
@@ -1,7 +1,12 @@
+use std::fmt;
+
 use quote::ToTokens;
-use syn::{Attribute, Expr, ExprBinary, ExprCall, ExprRange, FnArg, ItemFn, Lit, RangeLimits};
+use syn::{
+    Attribute, BinOp, Expr, ExprBinary, ExprCall, ExprRange, FnArg, ItemFn, Lit, RangeLimits,
+};
 
 use crate::error::{ExporterError, Result};
+use crate::resolver::ComparisonOp;
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct ZkPrivateAttr {
@@ -16,6 +21,7 @@ pub enum AttrSpec {
     Range(RangeSpec),
     Constraint(Constraint),
     MerkleMember(MerkleMemberSpec),
+    Relation(RelationSpec),
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -37,6 +43,61 @@ pub enum Constraint {
     Lte(String),
     Lt(String),
     Eq(String),
+    Neq(String),
+}
+
+/// `lhs op rhs` over fn params, e.g. `value * 100 >= debt * 150`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RelationSpec {
+    pub lhs: ExprSpec,
+    pub op: ComparisonOp,
+    pub rhs: ExprSpec,
+}
+
+/// Integer expression over fn params; `Var("value")` is the annotated param.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ExprSpec {
+    Var(String),
+    Const(u128),
+    Add(Box<ExprSpec>, Box<ExprSpec>),
+    Sub(Box<ExprSpec>, Box<ExprSpec>),
+    Mul(Box<ExprSpec>, Box<ExprSpec>),
+}
+
+impl ExprSpec {
+    /// Variables in order of first appearance.
+    pub fn vars(&self) -> Vec<&str> {
+        let mut out = Vec::new();
+        self.collect_vars(&mut out);
+        out
+    }
+
+    fn collect_vars<'a>(&'a self, out: &mut Vec<&'a str>) {
+        match self {
+            ExprSpec::Var(name) => {
+                if !out.contains(&name.as_str()) {
+                    out.push(name);
+                }
+            }
+            ExprSpec::Const(_) => {}
+            ExprSpec::Add(l, r) | ExprSpec::Sub(l, r) | ExprSpec::Mul(l, r) => {
+                l.collect_vars(out);
+                r.collect_vars(out);
+            }
+        }
+    }
+}
+
+impl fmt::Display for ExprSpec {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ExprSpec::Var(name) => f.write_str(name),
+            ExprSpec::Const(c) => write!(f, "{c}"),
+            ExprSpec::Add(l, r) => write!(f, "({l} + {r})"),
+            ExprSpec::Sub(l, r) => write!(f, "({l} - {r})"),
+            ExprSpec::Mul(l, r) => write!(f, "{l} * {r}"),
+        }
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -112,7 +173,7 @@ fn parse_zk_private_attrs(attrs: &[&Attribute]) -> Result<Vec<AttrSpec>> {
                     } else {
                         let parsed =
                             parse_constraint(&raw).map_err(|e| meta.error(e.to_string()))?;
-                        specs.push(AttrSpec::Constraint(parsed));
+                        specs.extend(parsed);
                     }
                 }
                 other => {
@@ -147,30 +208,109 @@ fn parse_range(raw: &str) -> Result<RangeSpec> {
     Ok(RangeSpec { low, high, inclusive })
 }
 
-fn parse_constraint(raw: &str) -> Result<Constraint> {
+/// Parses comparisons joined by `&&`.
+///
+/// `value <op> rhs` with a plain `rhs` (a param name or any non-arithmetic expression) stays a
+/// [`Constraint`]; every other comparison is a [`RelationSpec`].
+fn parse_constraint(raw: &str) -> Result<Vec<AttrSpec>> {
     let expr: Expr = syn::parse_str(raw)
         .map_err(|e| ExporterError::Parse(format!("invalid constraint '{raw}': {e}")))?;
+    let mut comparisons = Vec::new();
+    split_conjunction(&expr, &mut comparisons);
+    comparisons.into_iter().map(|c| parse_comparison(raw, c)).collect()
+}
+
+fn split_conjunction<'e>(expr: &'e Expr, out: &mut Vec<&'e Expr>) {
+    match unparen(expr) {
+        Expr::Binary(ExprBinary { left, op: BinOp::And(_), right, .. }) => {
+            split_conjunction(left, out);
+            split_conjunction(right, out);
+        }
+        other => out.push(other),
+    }
+}
+
+fn unparen(mut expr: &Expr) -> &Expr {
+    while let Expr::Paren(p) = expr {
+        expr = &p.expr;
+    }
+    expr
+}
+
+fn parse_comparison(raw: &str, expr: &Expr) -> Result<AttrSpec> {
     let Expr::Binary(ExprBinary { left, op, right, .. }) = expr else {
         return Err(ExporterError::Parse(format!(
-            "constraint '{raw}' must be a binary expression (LHS op RHS)"
+            "constraint '{raw}' must be a comparison (LHS op RHS)"
         )));
     };
-    let lhs = left.to_token_stream().to_string();
-    if lhs.trim() != "value" {
-        return Err(ExporterError::Parse(format!(
-            "constraint LHS must be 'value' (the annotated param); got '{lhs}'"
-        )));
+    let op = match op {
+        BinOp::Ge(_) => ComparisonOp::Gte,
+        BinOp::Gt(_) => ComparisonOp::Gt,
+        BinOp::Le(_) => ComparisonOp::Lte,
+        BinOp::Lt(_) => ComparisonOp::Lt,
+        BinOp::Eq(_) => ComparisonOp::Eq,
+        BinOp::Ne(_) => ComparisonOp::Neq,
+        other => {
+            return Err(ExporterError::Parse(format!(
+                "constraint '{raw}': unsupported operator '{}' (use >=, >, <=, <, ==, !=, \
+                 joined by &&)",
+                other.to_token_stream()
+            )))
+        }
+    };
+    let is_arithmetic = matches!(
+        unparen(right),
+        Expr::Binary(ExprBinary { op: BinOp::Add(_) | BinOp::Sub(_) | BinOp::Mul(_), .. })
+    );
+    if matches!(left.as_ref(), Expr::Path(p) if p.path.is_ident("value")) && !is_arithmetic {
+        let rhs = right.to_token_stream().to_string();
+        return Ok(AttrSpec::Constraint(match op {
+            ComparisonOp::Gte => Constraint::Gte(rhs),
+            ComparisonOp::Gt => Constraint::Gt(rhs),
+            ComparisonOp::Lte => Constraint::Lte(rhs),
+            ComparisonOp::Lt => Constraint::Lt(rhs),
+            ComparisonOp::Eq => Constraint::Eq(rhs),
+            ComparisonOp::Neq => Constraint::Neq(rhs),
+        }));
     }
-    let rhs = right.to_token_stream().to_string();
-    match op {
-        syn::BinOp::Ge(_) => Ok(Constraint::Gte(rhs)),
-        syn::BinOp::Gt(_) => Ok(Constraint::Gt(rhs)),
-        syn::BinOp::Le(_) => Ok(Constraint::Lte(rhs)),
-        syn::BinOp::Lt(_) => Ok(Constraint::Lt(rhs)),
-        syn::BinOp::Eq(_) => Ok(Constraint::Eq(rhs)),
-        _ => Err(ExporterError::Parse(format!(
-            "constraint '{raw}': unsupported operator (use >=, >, <=, <, ==)"
-        ))),
+    let expr_spec = |e: &Expr| {
+        parse_expr_spec(e).map_err(|e| ExporterError::Parse(format!("constraint '{raw}': {e}")))
+    };
+    Ok(AttrSpec::Relation(RelationSpec { lhs: expr_spec(left)?, op, rhs: expr_spec(right)? }))
+}
+
+/// Parses one side of a relation: param names, unsigned integer literals, `+`, `-`, `*` and
+/// parentheses.
+fn parse_expr_spec(expr: &Expr) -> std::result::Result<ExprSpec, String> {
+    match expr {
+        Expr::Paren(p) => parse_expr_spec(&p.expr),
+        Expr::Path(p) if p.path.get_ident().is_some() => {
+            Ok(ExprSpec::Var(p.path.to_token_stream().to_string()))
+        }
+        Expr::Lit(lit) => match &lit.lit {
+            Lit::Int(int) => int
+                .base10_parse::<u128>()
+                .map(ExprSpec::Const)
+                .map_err(|e| format!("invalid integer literal '{int}': {e}")),
+            other => Err(format!("unsupported literal '{}'", other.to_token_stream())),
+        },
+        Expr::Binary(ExprBinary { left, op, right, .. }) => {
+            let (l, r) = (Box::new(parse_expr_spec(left)?), Box::new(parse_expr_spec(right)?));
+            match op {
+                BinOp::Add(_) => Ok(ExprSpec::Add(l, r)),
+                BinOp::Sub(_) => Ok(ExprSpec::Sub(l, r)),
+                BinOp::Mul(_) => Ok(ExprSpec::Mul(l, r)),
+                BinOp::Div(_) => Err("division is not supported".to_string()),
+                other => Err(format!(
+                    "unsupported operator '{}' in an arithmetic expression (use +, -, *)",
+                    other.to_token_stream()
+                )),
+            }
+        }
+        other => Err(format!(
+            "unsupported term '{}' (expected a param name or an unsigned integer literal)",
+            other.to_token_stream()
+        )),
     }
 }
 
@@ -305,6 +445,17 @@ mod tests {
         assert_eq!(attrs[0].specs[0], AttrSpec::Constraint(Constraint::Lt("max".into())));
     }
 
+    #[test]
+    fn constraint_neq_typed() {
+        let item = parse_item(
+            r#"
+                fn foo(#[zk_private(constraint = "value != receiver")] x: u64) {}
+            "#,
+        );
+        let attrs = parse_fn(&item).unwrap();
+        assert_eq!(attrs[0].specs[0], AttrSpec::Constraint(Constraint::Neq("receiver".into())));
+    }
+
     #[test]
     fn constraint_eq_with_function_call_rhs() {
         let item = parse_item(
@@ -324,14 +475,21 @@ mod tests {
     }
 
     #[test]
-    fn constraint_with_non_value_lhs_fails() {
+    fn constraint_with_non_value_lhs_is_relation() {
         let item = parse_item(
             r#"
                 fn foo(#[zk_private(constraint = "threshold >= value")] x: u64) {}
             "#,
         );
-        let err = parse_fn(&item).unwrap_err();
-        assert!(format!("{err}").contains("LHS"));
+        let attrs = parse_fn(&item).unwrap();
+        assert_eq!(
+            attrs[0].specs[0],
+            AttrSpec::Relation(RelationSpec {
+                lhs: ExprSpec::Var("threshold".into()),
+                op: ComparisonOp::Gte,
+                rhs: ExprSpec::Var("value".into()),
+            })
+        );
     }
 
     #[test]
@@ -343,7 +501,46 @@ mod tests {
         );
         let err = parse_fn(&item).unwrap_err();
         let msg = format!("{err}");
-        assert!(msg.contains("operator") || msg.contains("LHS"));
+        assert!(msg.contains("operator"));
+    }
+
+    #[test]
+    fn relation_expression_and_conjunction() {
+        let item = parse_item(
+            r#"
+                fn foo(
+                    #[zk_private(constraint = "value * 100 >= (debt + fee) * 150 && value < cap")]
+                    x: u64,
+                ) {}
+            "#,
+        );
+        let attrs = parse_fn(&item).unwrap();
+        let var = |n: &str| Box::new(ExprSpec::Var(n.into()));
+        assert_eq!(
+            attrs[0].specs,
+            vec![
+                AttrSpec::Relation(RelationSpec {
+                    lhs: ExprSpec::Mul(var("value"), Box::new(ExprSpec::Const(100))),
+                    op: ComparisonOp::Gte,
+                    rhs: ExprSpec::Mul(
+                        Box::new(ExprSpec::Add(var("debt"), var("fee"))),
+                        Box::new(ExprSpec::Const(150)),
+                    ),
+                }),
+                AttrSpec::Constraint(Constraint::Lt("cap".into())),
+            ]
+        );
+    }
+
+    #[test]
+    fn relation_rejects_division() {
+        let item = parse_item(
+            r#"
+                fn foo(#[zk_private(constraint = "value / 2 > debt")] x: u64) {}
+            "#,
+        );
+        let err = parse_fn(&item).unwrap_err();
+        assert!(format!("{err}").contains("division is not supported"));
     }
 
     #[test]
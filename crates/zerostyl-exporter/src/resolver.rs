@@ -1,5 +1,5 @@
 use crate::error::{ExporterError, Result};
-use crate::parser::{AttrSpec, CommitScheme, Constraint, ZkPrivateAttr};
+use crate::parser::{AttrSpec, CommitScheme, Constraint, ExprSpec, ZkPrivateAttr};
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct ResolvedAttr {
@@ -18,6 +18,9 @@ pub enum GadgetBinding {
     Comparison { op: ComparisonOp, other: String, num_bits: usize },
     /// `MerkleTreeChip::verify_membership(value, root_var, siblings_var, indices_var, depth)`
     MerkleMember { root_var: String, siblings_var: String, indices_var: String, depth: usize },
+    /// `ArithmeticChip` evaluates both sides, then
+    /// `ComparisonChip::assert_<op>(lhs, rhs, num_bits)`. `value` is replaced by the param name.
+    Relation { lhs: ExprSpec, op: ComparisonOp, rhs: ExprSpec, num_bits: usize },
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -27,16 +30,20 @@ pub enum ComparisonOp {
     Lte,
     Lt,
     Eq,
+    Neq,
 }
 
 /// Convention shared across the four M1 circuits.
 pub const MERKLE_DEPTH: usize = 32;
 
+/// Widest difference `ComparisonChip` can range-check soundly (`RangeProofChip::MAX_BOUNDED_BITS`).
+const MAX_COMPARISON_BITS: usize = 253;
+
 pub fn resolve(attr: &ZkPrivateAttr) -> Result<ResolvedAttr> {
     let num_bits = num_bits_of(&attr.param_type)?;
     let mut bindings = Vec::with_capacity(attr.specs.len());
     for spec in &attr.specs {
-        bindings.push(resolve_spec(spec, &attr.param_name, num_bits));
+        bindings.push(resolve_spec(spec, &attr.param_name, num_bits)?);
     }
     Ok(ResolvedAttr {
         param_name: attr.param_name.clone(),
@@ -49,8 +56,8 @@ pub fn resolve_all(attrs: &[ZkPrivateAttr]) -> Result<Vec<ResolvedAttr>> {
     attrs.iter().map(resolve).collect()
 }
 
-fn resolve_spec(spec: &AttrSpec, param_name: &str, num_bits: usize) -> GadgetBinding {
-    match spec {
+fn resolve_spec(spec: &AttrSpec, param_name: &str, num_bits: usize) -> Result<GadgetBinding> {
+    Ok(match spec {
         AttrSpec::Commit(CommitScheme::Poseidon) => {
             GadgetBinding::PoseidonCommit { nonce_var: format!("{param_name}_nonce") }
         }
@@ -70,6 +77,44 @@ fn resolve_spec(spec: &AttrSpec, param_name: &str, num_bits: usize) -> GadgetBin
             indices_var: m.indices_var.clone(),
             depth: MERKLE_DEPTH,
         },
+        AttrSpec::Relation(r) => {
+            let lhs = substitute_value(&r.lhs, param_name);
+            let rhs = substitute_value(&r.rhs, param_name);
+            // Like `Comparison`, every operand is assumed to be as wide as the annotated param.
+            // `|lhs - rhs|` then stays below 2^num_bits.
+            let num_bits = magnitude_bits(&lhs, num_bits).max(magnitude_bits(&rhs, num_bits)) + 1;
+            let ordering = !matches!(r.op, ComparisonOp::Eq | ComparisonOp::Neq);
+            if ordering && num_bits > MAX_COMPARISON_BITS {
+                return Err(ExporterError::Parse(format!(
+                    "relation on '{param_name}' needs {num_bits}-bit comparison, more than the \
+                     {MAX_COMPARISON_BITS} bits ComparisonChip supports"
+                )));
+            }
+            GadgetBinding::Relation { lhs, op: r.op, rhs, num_bits }
+        }
+    })
+}
+
+fn substitute_value(expr: &ExprSpec, param_name: &str) -> ExprSpec {
+    let sub = |e: &ExprSpec| Box::new(substitute_value(e, param_name));
+    match expr {
+        ExprSpec::Var(name) if name == "value" => ExprSpec::Var(param_name.to_string()),
+        ExprSpec::Var(_) | ExprSpec::Const(_) => expr.clone(),
+        ExprSpec::Add(l, r) => ExprSpec::Add(sub(l), sub(r)),
+        ExprSpec::Sub(l, r) => ExprSpec::Sub(sub(l), sub(r)),
+        ExprSpec::Mul(l, r) => ExprSpec::Mul(sub(l), sub(r)),
+    }
+}
+
+/// Smallest `b` with `|expr| < 2^b` when every variable is below `2^var_bits`.
+fn magnitude_bits(expr: &ExprSpec, var_bits: usize) -> usize {
+    match expr {
+        ExprSpec::Var(_) => var_bits,
+        ExprSpec::Const(c) => (128 - c.leading_zeros()) as usize,
+        ExprSpec::Add(l, r) | ExprSpec::Sub(l, r) => {
+            magnitude_bits(l, var_bits).max(magnitude_bits(r, var_bits)) + 1
+        }
+        ExprSpec::Mul(l, r) => magnitude_bits(l, var_bits) + magnitude_bits(r, var_bits),
     }
 }
 
@@ -80,6 +125,7 @@ fn comparison_parts(c: &Constraint) -> (ComparisonOp, String) {
         Constraint::Lte(o) => (ComparisonOp::Lte, o.clone()),
         Constraint::Lt(o) => (ComparisonOp::Lt, o.clone()),
         Constraint::Eq(o) => (ComparisonOp::Eq, o.clone()),
+        Constraint::Neq(o) => (ComparisonOp::Neq, o.clone()),
     }
 }
 
@@ -91,7 +137,9 @@ fn num_bits_of(ty: &str) -> Result<usize> {
         "u32" => Ok(32),
         "u64" => Ok(64),
         "u128" => Ok(128),
-        "U256" => Ok(256),
+        // A U256 witness is a Pasta field element (< 2^255), and bounded range checks and
+        // ordering comparisons are only sound up to RangeProofChip::MAX_BOUNDED_BITS.
+        "U256" => Ok(253),
         "bool" => Ok(1),
         other => Err(ExporterError::Parse(format!(
             "cannot infer bit width for type '{other}' (supported: u8/u16/u32/u64/u128/U256/bool)"
@@ -108,7 +156,9 @@ fn strip_path(ty: &str) -> &str {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::parser::{AttrSpec, CommitScheme, Constraint, MerkleMemberSpec, RangeSpec};
+    use crate::parser::{
+        AttrSpec, CommitScheme, Constraint, MerkleMemberSpec, RangeSpec, RelationSpec,
+    };
 
     fn attr(param_name: &str, param_type: &str, specs: Vec<AttrSpec>) -> ZkPrivateAttr {
         ZkPrivateAttr { param_name: param_name.into(), param_type: param_type.into(), specs }
@@ -162,7 +212,7 @@ mod tests {
         let r = resolve(&a).unwrap();
         match &r.bindings[0] {
             GadgetBinding::Range { num_bits, inclusive, high, .. } => {
-                assert_eq!(*num_bits, 256);
+                assert_eq!(*num_bits, 253);
                 assert!(*inclusive);
                 assert!(high.contains("u128"));
             }
@@ -235,13 +285,53 @@ mod tests {
         let r = resolve(&a).unwrap();
         assert_eq!(r.bindings.len(), 3);
         assert!(matches!(r.bindings[0], GadgetBinding::PoseidonCommit { .. }));
-        assert!(matches!(r.bindings[1], GadgetBinding::Range { num_bits: 256, .. }));
+        assert!(matches!(r.bindings[1], GadgetBinding::Range { num_bits: 253, .. }));
         assert!(matches!(
             r.bindings[2],
-            GadgetBinding::Comparison { op: ComparisonOp::Gte, num_bits: 256, .. }
+            GadgetBinding::Comparison { op: ComparisonOp::Gte, num_bits: 253, .. }
         ));
     }
 
+    #[test]
+    fn relation_substitutes_value_and_sizes_comparison() {
+        let relation = RelationSpec {
+            lhs: ExprSpec::Mul(
+                Box::new(ExprSpec::Var("value".into())),
+                Box::new(ExprSpec::Const(100)),
+            ),
+            op: ComparisonOp::Gte,
+            rhs: ExprSpec::Var("debt".into()),
+        };
+        let r = resolve(&attr("collateral", "u64", vec![AttrSpec::Relation(relation)])).unwrap();
+        assert_eq!(
+            r.bindings[0],
+            GadgetBinding::Relation {
+                lhs: ExprSpec::Mul(
+                    Box::new(ExprSpec::Var("collateral".into())),
+                    Box::new(ExprSpec::Const(100)),
+                ),
+                op: ComparisonOp::Gte,
+                rhs: ExprSpec::Var("debt".into()),
+                // 64 + 7 bits for the product, plus one for the difference
+                num_bits: 72,
+            }
+        );
+    }
+
+    #[test]
+    fn relation_too_wide_for_comparison_fails() {
+        let relation = RelationSpec {
+            lhs: ExprSpec::Var("value".into()),
+            op: ComparisonOp::Lt,
+            rhs: ExprSpec::Var("cap".into()),
+        };
+        let err = resolve(&attr("x", "U256", vec![AttrSpec::Relation(relation.clone())]));
+        assert!(format!("{}", err.unwrap_err()).contains("254-bit"));
+
+        let equality = RelationSpec { op: ComparisonOp::Eq, ..relation };
+        assert!(resolve(&attr("x", "U256", vec![AttrSpec::Relation(equality)])).is_ok());
+    }
+
     #[test]
     fn unknown_type_fails() {
         let a = attr("x", "MyCustomType", vec![AttrSpec::Commit(CommitScheme::Poseidon)]);
@@ -257,7 +347,7 @@ mod tests {
             vec![AttrSpec::Constraint(Constraint::Gte("y".into()))],
         );
         let r = resolve(&a).unwrap();
-        assert!(matches!(r.bindings[0], GadgetBinding::Comparison { num_bits: 256, .. }));
+        assert!(matches!(r.bindings[0], GadgetBinding::Comparison { num_bits: 253, .. }));
     }
 
     #[test]
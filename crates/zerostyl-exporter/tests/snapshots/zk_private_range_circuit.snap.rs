@@ -62,7 +62,7 @@ impl Circuit<Fp> for DepositCircuit {
             amount_range_value,
             Fp::from((1000) as u64),
             Fp::from((100000) as u64),
-            256usize,
+            253usize,
         )?;
         Ok(())
     }
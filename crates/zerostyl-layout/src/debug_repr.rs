@@ -0,0 +1,302 @@
+//! Reader for `{:?}` output
+//!
+//! halo2_proofs 0.3 keeps the contents of a `ConstraintSystem` crate-private; its
+//! derived `Debug` output is the only public view of the gates, queries and lookups.
+//! [`Repr::parse`] reads that output back into a tree. Only the shapes derived `Debug`
+//! produces are supported: structs, tuple structs, lists, tuples, strings and bare
+//! tokens (identifiers, numbers, `0x…` field elements).
+
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+
+/// A parsed `Debug` value.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum Repr {
+    /// Identifier, number or hex literal, e.g. `Advice`, `-1`, `0x00…01`.
+    Token(String),
+    /// String literal, with escapes left as printed.
+    Str(String),
+    /// `Name { field: value, .. }`
+    Struct(String, Vec<(String, Repr)>),
+    /// `Name(a, b)`, or an unnamed tuple `(a, b)`.
+    Tuple(Option<String>, Vec<Repr>),
+    /// `[a, b]`
+    List(Vec<Repr>),
+}
+
+impl Repr {
+    /// Parses a complete `{:?}` string.
+    pub(crate) fn parse(input: &str) -> Result<Repr, String> {
+        let mut parser = Parser { bytes: input.as_bytes(), pos: 0 };
+        let value = parser.value()?;
+        parser.skip_whitespace();
+        if parser.pos != parser.bytes.len() {
+            return Err(parser.error("trailing input"));
+        }
+        Ok(value)
+    }
+
+    /// Field `name` of a struct.
+    pub(crate) fn field(&self, name: &str) -> Result<&Repr, String> {
+        match self {
+            Repr::Struct(_, fields) => fields
+                .iter()
+                .find(|(field, _)| field == name)
+                .map(|(_, value)| value)
+                .ok_or_else(|| alloc::format!("missing field `{}`", name)),
+            _ => Err(alloc::format!("expected a struct with field `{}`", name)),
+        }
+    }
+
+    /// Elements of a list or tuple.
+    pub(crate) fn items(&self) -> Result<&[Repr], String> {
+        match self {
+            Repr::List(items) | Repr::Tuple(_, items) => Ok(items),
+            _ => Err("expected a list or tuple".to_string()),
+        }
+    }
+
+    /// Name of a struct or named tuple.
+    pub(crate) fn name(&self) -> Option<&str> {
+        match self {
+            Repr::Struct(name, _) | Repr::Tuple(Some(name), _) => Some(name),
+            _ => None,
+        }
+    }
+
+    /// Elements of the named tuple `name(..)`.
+    pub(crate) fn tuple(&self, name: &str) -> Result<&[Repr], String> {
+        match self {
+            Repr::Tuple(Some(found), items) if found == name => Ok(items),
+            _ => Err(alloc::format!("expected `{}(..)`", name)),
+        }
+    }
+
+    /// The bare token.
+    pub(crate) fn token(&self) -> Result<&str, String> {
+        match self {
+            Repr::Token(token) => Ok(token),
+            _ => Err("expected a token".to_string()),
+        }
+    }
+
+    /// The token as an unsigned integer.
+    pub(crate) fn as_u32(&self) -> Result<u32, String> {
+        let token = self.token()?;
+        token.parse().map_err(|_| alloc::format!("expected an unsigned integer, found `{}`", token))
+    }
+
+    /// The token as a signed integer.
+    pub(crate) fn as_i32(&self) -> Result<i32, String> {
+        let token = self.token()?;
+        token.parse().map_err(|_| alloc::format!("expected an integer, found `{}`", token))
+    }
+
+    /// The token as a boolean.
+    pub(crate) fn as_bool(&self) -> Result<bool, String> {
+        match self.token()? {
+            "true" => Ok(true),
+            "false" => Ok(false),
+            token => Err(alloc::format!("expected a boolean, found `{}`", token)),
+        }
+    }
+
+    /// A field element printed as `0x` followed by 64 big-endian hex digits, returned as
+    /// its little-endian repr.
+    pub(crate) fn as_field_repr(&self) -> Result<[u8; 32], String> {
+        let token = self.token()?;
+        let hex = token
+            .strip_prefix("0x")
+            .filter(|hex| hex.len() == 64)
+            .ok_or_else(|| alloc::format!("expected a field element, found `{}`", token))?;
+        let mut repr = [0u8; 32];
+        for (i, byte) in repr.iter_mut().rev().enumerate() {
+            *byte = u8::from_str_radix(&hex[2 * i..2 * i + 2], 16)
+                .map_err(|_| alloc::format!("invalid hex in `{}`", token))?;
+        }
+        Ok(repr)
+    }
+
+    /// `Some(value)` or `None`.
+    pub(crate) fn as_option(&self) -> Result<Option<&Repr>, String> {
+        match self {
+            Repr::Token(token) if token == "None" => Ok(None),
+            Repr::Tuple(Some(name), items) if name == "Some" && items.len() == 1 => {
+                Ok(Some(&items[0]))
+            }
+            _ => Err("expected an Option".to_string()),
+        }
+    }
+}
+
+struct Parser<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl Parser<'_> {
+    fn error(&self, message: &str) -> String {
+        alloc::format!("{} at byte {}", message, self.pos)
+    }
+
+    fn skip_whitespace(&mut self) {
+        while self.bytes.get(self.pos).is_some_and(u8::is_ascii_whitespace) {
+            self.pos += 1;
+        }
+    }
+
+    fn peek(&mut self) -> Option<u8> {
+        self.skip_whitespace();
+        self.bytes.get(self.pos).copied()
+    }
+
+    fn expect(&mut self, byte: u8) -> Result<(), String> {
+        if self.peek() == Some(byte) {
+            self.pos += 1;
+            Ok(())
+        } else {
+            Err(self.error(&alloc::format!("expected `{}`", byte as char)))
+        }
+    }
+
+    fn value(&mut self) -> Result<Repr, String> {
+        match self.peek() {
+            Some(b'[') => {
+                self.pos += 1;
+                Ok(Repr::List(self.sequence(b']')?))
+            }
+            Some(b'(') => {
+                self.pos += 1;
+                Ok(Repr::Tuple(None, self.sequence(b')')?))
+            }
+            Some(b'"') => self.string(),
+            Some(_) => {
+                let token = self.token()?;
+                match self.peek() {
+                    Some(b'(') => {
+                        self.pos += 1;
+                        Ok(Repr::Tuple(Some(token), self.sequence(b')')?))
+                    }
+                    Some(b'{') => {
+                        self.pos += 1;
+                        self.fields(token)
+                    }
+                    _ => Ok(Repr::Token(token)),
+                }
+            }
+            None => Err(self.error("unexpected end of input")),
+        }
+    }
+
+    /// Comma-separated values up to `close`, which is consumed.
+    fn sequence(&mut self, close: u8) -> Result<Vec<Repr>, String> {
+        let mut items = Vec::new();
+        loop {
+            if self.peek() == Some(close) {
+                self.pos += 1;
+                return Ok(items);
+            }
+            items.push(self.value()?);
+            if self.peek() == Some(b',') {
+                self.pos += 1;
+            } else {
+                self.expect(close)?;
+                return Ok(items);
+            }
+        }
+    }
+
+    fn fields(&mut self, name: String) -> Result<Repr, String> {
+        let mut fields = Vec::new();
+        loop {
+            if self.peek() == Some(b'}') {
+                self.pos += 1;
+                return Ok(Repr::Struct(name, fields));
+            }
+            let field = self.token()?;
+            self.expect(b':')?;
+            fields.push((field, self.value()?));
+            if self.peek() == Some(b',') {
+                self.pos += 1;
+            } else {
+                self.expect(b'}')?;
+                return Ok(Repr::Struct(name, fields));
+            }
+        }
+    }
+
+    fn token(&mut self) -> Result<String, String> {
+        self.skip_whitespace();
+        let start = self.pos;
+        while self
+            .bytes
+            .get(self.pos)
+            .is_some_and(|&byte| !byte.is_ascii_whitespace() && !b"()[]{},:\"".contains(&byte))
+        {
+            self.pos += 1;
+        }
+        if start == self.pos {
+            return Err(self.error("expected a token"));
+        }
+        Ok(String::from_utf8_lossy(&self.bytes[start..self.pos]).into_owned())
+    }
+
+    fn string(&mut self) -> Result<Repr, String> {
+        self.expect(b'"')?;
+        let start = self.pos;
+        loop {
+            match self.bytes.get(self.pos) {
+                Some(b'\\') => self.pos += 2,
+                Some(b'"') => break,
+                Some(_) => self.pos += 1,
+                None => return Err(self.error("unterminated string")),
+            }
+        }
+        let text = String::from_utf8_lossy(&self.bytes[start..self.pos]).into_owned();
+        self.pos += 1;
+        Ok(Repr::Str(text))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parses_derived_debug_shapes() {
+        let repr = Repr::parse(
+            r#"Gate { name: "a \"b\"", polys: [Product(Selector(Selector(0, true)), Advice { query_index: 1, column_index: 2, rotation: Rotation(-1) })], pair: (Column { index: 0, column_type: Fixed }, Rotation(0)), degree: None }"#,
+        )
+        .unwrap();
+
+        assert_eq!(repr.name(), Some("Gate"));
+        assert_eq!(repr.field("name").unwrap(), &Repr::Str(r#"a \"b\""#.to_string()));
+        let product = &repr.field("polys").unwrap().items().unwrap()[0];
+        let factors = product.tuple("Product").unwrap();
+        let selector = factors[0].tuple("Selector").unwrap()[0].tuple("Selector").unwrap();
+        assert_eq!(selector[0].as_u32(), Ok(0));
+        assert_eq!(selector[1].as_bool(), Ok(true));
+        let rotation = factors[1].field("rotation").unwrap().tuple("Rotation").unwrap();
+        assert_eq!(rotation[0].as_i32(), Ok(-1));
+        let pair = repr.field("pair").unwrap().items().unwrap();
+        assert_eq!(pair[0].field("column_type").unwrap().token(), Ok("Fixed"));
+        assert_eq!(repr.field("degree").unwrap().as_option(), Ok(None));
+    }
+
+    #[test]
+    fn test_field_element_is_read_little_endian() {
+        let mut hex = "0x".to_string();
+        hex.push_str(&"0".repeat(60));
+        hex.push_str("0102");
+        let repr = Repr::parse(&alloc::format!("Constant({})", hex)).unwrap();
+        let bytes = repr.tuple("Constant").unwrap()[0].as_field_repr().unwrap();
+        assert_eq!(bytes[..3], [0x02, 0x01, 0x00]);
+    }
+
+    #[test]
+    fn test_rejects_malformed_input() {
+        assert!(Repr::parse("Gate { name: ").is_err());
+        assert!(Repr::parse("[1, 2").is_err());
+        assert!(Repr::parse("A B").is_err());
+    }
+}
@@ -0,0 +1,660 @@
+//! The serializable circuit layout and its replay
+//!
+//! A [`CircuitLayout`] holds everything `keygen_vk` reads from a circuit: the constraint
+//! system (columns, selectors, queries, gate polynomials, lookups, the permutation and
+//! constant columns) and the assignments keygen keeps (fixed cells, selector activations
+//! and copy constraints, in the order they were made). Field elements are stored as their
+//! 32-byte little-endian reprs.
+//!
+//! [`CircuitLayout::configure`] rebuilds the constraint system so that its columns and
+//! query indices line up with the recorded one, and [`CircuitLayout::assign`] replays the
+//! assignments into a single region starting at row 0. Lookup tables are replayed through
+//! `assign_table`, which fills the unused rows of table columns exactly as the recorded
+//! circuit's layouter did.
+
+use alloc::boxed::Box;
+use alloc::collections::{BTreeMap, BTreeSet};
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::marker::PhantomData;
+
+use halo2_proofs::{
+    arithmetic::Field,
+    circuit::{Cell, Layouter, Region, SimpleFloorPlanner, Value},
+    pasta::{group::ff::PrimeField, Fp},
+    plonk::{
+        Advice, Circuit, Column, ConstraintSystem, Error, Expression, Fixed, Instance, Selector,
+        TableColumn, VirtualCells,
+    },
+    poly::Rotation,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::LayoutError;
+
+/// Format version written in front of [`CircuitLayout::to_bytes`] output.
+pub const LAYOUT_FORMAT_VERSION: u8 = 1;
+
+/// A column of the recorded constraint system.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub(crate) enum ColumnRef {
+    Advice(u32),
+    Fixed(u32),
+    Instance(u32),
+}
+
+/// A cell at an absolute row.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub(crate) struct CellRef {
+    pub(crate) column: ColumnRef,
+    pub(crate) row: u32,
+}
+
+/// A gate or lookup input polynomial, mirroring `halo2_proofs::plonk::Expression`.
+///
+/// Queries carry the column and rotation instead of halo2's query index; the index
+/// follows from the order of the layout's query lists.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) enum Expr {
+    Constant([u8; 32]),
+    Selector(u32),
+    /// Fixed columns are only ever queried at the current row.
+    Fixed(u32),
+    Advice(u32, i32),
+    Instance(u32, i32),
+    Negated(Box<Expr>),
+    Sum(Box<Expr>, Box<Expr>),
+    Product(Box<Expr>, Box<Expr>),
+    Scaled(Box<Expr>, [u8; 32]),
+}
+
+/// A lookup argument: each input expression is looked up in the table column at the
+/// same position.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) struct Lookup {
+    pub(crate) inputs: Vec<Expr>,
+    pub(crate) tables: Vec<u32>,
+}
+
+/// An assignment keygen keeps.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) enum Op {
+    Fixed { column: u32, row: u32, value: [u8; 32] },
+    Enable { selector: u32, row: u32 },
+    Copy(CellRef, CellRef),
+}
+
+/// Everything `keygen_vk` reads from a circuit, in a serializable form.
+///
+/// Produced by [`record`](fn@crate::record) and replayed by [`LayoutCircuit`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CircuitLayout {
+    pub(crate) k: u32,
+    pub(crate) num_advice_columns: u32,
+    pub(crate) num_fixed_columns: u32,
+    pub(crate) num_instance_columns: u32,
+    /// Whether each selector is simple, in allocation order.
+    pub(crate) selectors: Vec<bool>,
+    pub(crate) advice_queries: Vec<(u32, i32)>,
+    pub(crate) instance_queries: Vec<(u32, i32)>,
+    pub(crate) fixed_queries: Vec<u32>,
+    /// Gate polynomials of all gates, in order. Grouping into gates does not reach the
+    /// verifying key.
+    pub(crate) constraints: Vec<Expr>,
+    pub(crate) lookups: Vec<Lookup>,
+    pub(crate) permutation: Vec<ColumnRef>,
+    pub(crate) constants: Vec<u32>,
+    pub(crate) minimum_degree: Option<u32>,
+    pub(crate) ops: Vec<Op>,
+}
+
+impl CircuitLayout {
+    /// The circuit size parameter the layout was recorded for.
+    pub fn k(&self) -> u32 {
+        self.k
+    }
+
+    /// Number of instance columns of the circuit.
+    pub fn num_instance_columns(&self) -> usize {
+        self.num_instance_columns as usize
+    }
+
+    /// Serializes the layout, prefixed with [`LAYOUT_FORMAT_VERSION`].
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = alloc::vec![LAYOUT_FORMAT_VERSION];
+        bytes.extend(postcard::to_allocvec(self).expect("serializing into a Vec cannot fail"));
+        bytes
+    }
+
+    /// Deserializes and validates a layout written by [`CircuitLayout::to_bytes`].
+    ///
+    /// # Errors
+    ///
+    /// Returns [`LayoutError::Invalid`] on an unknown format version, malformed bytes, or a
+    /// layout that refers to columns, selectors or field elements that do not exist.
+    pub fn from_bytes(bytes: &[u8]) -> Result<CircuitLayout, LayoutError> {
+        let (&version, body) = bytes.split_first().ok_or_else(|| invalid("empty layout".into()))?;
+        if version != LAYOUT_FORMAT_VERSION {
+            return Err(invalid(format!(
+                "layout format version {} (expected {})",
+                version, LAYOUT_FORMAT_VERSION
+            )));
+        }
+        let layout: CircuitLayout =
+            postcard::from_bytes(body).map_err(|e| invalid(format!("{}", e)))?;
+        layout.validate()?;
+        Ok(layout)
+    }
+
+    /// Fixed columns used as lookup tables.
+    fn table_columns(&self) -> BTreeSet<u32> {
+        self.lookups.iter().flat_map(|lookup| lookup.tables.iter().copied()).collect()
+    }
+
+    /// Checks that replaying the layout cannot index out of bounds or hit one of the
+    /// constraint system's panics.
+    pub(crate) fn validate(&self) -> Result<(), LayoutError> {
+        let tables = self.table_columns();
+        let fixed = |column: u32| {
+            if column >= self.num_fixed_columns {
+                Err(invalid(format!("fixed column {} does not exist", column)))
+            } else if tables.contains(&column) {
+                Err(invalid(format!("table column {} is used as a fixed column", column)))
+            } else {
+                Ok(())
+            }
+        };
+        let column = |column: ColumnRef| match column {
+            ColumnRef::Advice(index) if index >= self.num_advice_columns => {
+                Err(invalid(format!("advice column {} does not exist", index)))
+            }
+            ColumnRef::Instance(index) if index >= self.num_instance_columns => {
+                Err(invalid(format!("instance column {} does not exist", index)))
+            }
+            ColumnRef::Fixed(index) => fixed(index),
+            _ => Ok(()),
+        };
+        let field = |repr: &[u8; 32]| {
+            Option::<Fp>::from(Fp::from_repr(*repr))
+                .map(|_| ())
+                .ok_or_else(|| invalid("non-canonical field element".into()))
+        };
+        let expr = |expr: &Expr, in_lookup: bool| {
+            let mut result = Ok(());
+            expr.visit(&mut |node| {
+                let checked = match node {
+                    Expr::Constant(value) | Expr::Scaled(_, value) => field(value),
+                    Expr::Selector(selector) => match self.selectors.get(*selector as usize) {
+                        None => Err(invalid(format!("selector {} does not exist", selector))),
+                        Some(true) if in_lookup => {
+                            Err(invalid("simple selector in a lookup input".into()))
+                        }
+                        Some(_) => Ok(()),
+                    },
+                    Expr::Fixed(index) => fixed(*index),
+                    Expr::Advice(index, _) => column(ColumnRef::Advice(*index)),
+                    Expr::Instance(index, _) => column(ColumnRef::Instance(*index)),
+                    _ => Ok(()),
+                };
+                if result.is_ok() {
+                    result = checked;
+                }
+            });
+            result
+        };
+
+        for &(index, _) in &self.advice_queries {
+            column(ColumnRef::Advice(index))?;
+        }
+        for &(index, _) in &self.instance_queries {
+            column(ColumnRef::Instance(index))?;
+        }
+        for &index in &self.fixed_queries {
+            if index >= self.num_fixed_columns {
+                return Err(invalid(format!("fixed column {} does not exist", index)));
+            }
+        }
+        for constraint in &self.constraints {
+            expr(constraint, false)?;
+        }
+        for lookup in &self.lookups {
+            if lookup.inputs.len() != lookup.tables.len() {
+                return Err(invalid("lookup inputs and tables differ in length".into()));
+            }
+            if let Some(&table) = lookup.tables.iter().find(|&&t| t >= self.num_fixed_columns) {
+                return Err(invalid(format!("table column {} does not exist", table)));
+            }
+            for input in &lookup.inputs {
+                expr(input, true)?;
+            }
+        }
+        for &permuted in &self.permutation {
+            column(permuted)?;
+        }
+        for &constant in &self.constants {
+            fixed(constant)?;
+        }
+        for op in &self.ops {
+            match op {
+                Op::Fixed { column, value, .. } => {
+                    if *column >= self.num_fixed_columns {
+                        return Err(invalid(format!("fixed column {} does not exist", column)));
+                    }
+                    field(value)?;
+                }
+                Op::Enable { selector, .. } => {
+                    if *selector as usize >= self.selectors.len() {
+                        return Err(invalid(format!("selector {} does not exist", selector)));
+                    }
+                }
+                Op::Copy(left, right) => {
+                    column(left.column)?;
+                    column(right.column)?;
+                    if let (ColumnRef::Instance(_), ColumnRef::Fixed(_) | ColumnRef::Instance(_))
+                    | (ColumnRef::Fixed(_), ColumnRef::Instance(_)) = (left.column, right.column)
+                    {
+                        return Err(invalid(
+                            "instance cells can only be copied to advice cells".into(),
+                        ));
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Rebuilds the recorded constraint system in `meta`.
+    ///
+    /// halo2 numbers queries in the order they are first made, and only lets lookups
+    /// query table columns (after their input closure has run). The first lookup or gate
+    /// closure therefore registers every advice and instance query up front, each lookup
+    /// closure registers the fixed queries recorded before its table queries, and the
+    /// first gate registers the rest. Gate polynomials are replayed as a single gate.
+    ///
+    /// The layout must have been validated, which [`CircuitLayout::from_bytes`] and
+    /// [`record`](fn@crate::record) do.
+    pub fn configure(&self, meta: &mut ConstraintSystem<Fp>) -> LayoutConfig {
+        let tables = self.table_columns();
+        let config = LayoutConfig {
+            advice: (0..self.num_advice_columns).map(|_| meta.advice_column()).collect(),
+            fixed: (0..self.num_fixed_columns)
+                .map(|index| {
+                    if tables.contains(&index) {
+                        FixedColumn::Table(meta.lookup_table_column())
+                    } else {
+                        FixedColumn::Fixed(meta.fixed_column())
+                    }
+                })
+                .collect(),
+            instance: (0..self.num_instance_columns).map(|_| meta.instance_column()).collect(),
+            selectors: self
+                .selectors
+                .iter()
+                .map(|&simple| if simple { meta.selector() } else { meta.complex_selector() })
+                .collect(),
+        };
+
+        let mut schedule = QuerySchedule::default();
+        for lookup in &self.lookups {
+            // Position of this lookup's first table query that no earlier lookup made
+            let next = schedule.next_fixed_query;
+            let table_query = self.fixed_queries[next..]
+                .iter()
+                .position(|column| lookup.tables.contains(column))
+                .map_or(next, |offset| next + offset);
+            meta.lookup(|cells| {
+                schedule.register(self, &config, cells, table_query);
+                lookup
+                    .inputs
+                    .iter()
+                    .zip(&lookup.tables)
+                    .map(|(input, &table)| {
+                        (config.expression(input, cells), config.table_column(table))
+                    })
+                    .collect()
+            });
+            // Skip over the table queries the lookup just made
+            while self
+                .fixed_queries
+                .get(schedule.next_fixed_query)
+                .is_some_and(|column| tables.contains(column))
+            {
+                schedule.next_fixed_query += 1;
+            }
+        }
+
+        if !self.constraints.is_empty() {
+            meta.create_gate("layout", |cells| {
+                schedule.register(self, &config, cells, self.fixed_queries.len());
+                self.constraints
+                    .iter()
+                    .map(|constraint| config.expression(constraint, cells))
+                    .collect::<Vec<_>>()
+            });
+        }
+
+        for &column in &self.permutation {
+            match column {
+                ColumnRef::Advice(index) => meta.enable_equality(config.advice[index as usize]),
+                ColumnRef::Fixed(index) => meta.enable_equality(config.fixed_column(index)),
+                ColumnRef::Instance(index) => meta.enable_equality(config.instance[index as usize]),
+            }
+        }
+        for &column in &self.constants {
+            meta.enable_constant(config.fixed_column(column));
+        }
+        if let Some(degree) = self.minimum_degree {
+            meta.set_minimum_degree(degree as usize);
+        }
+
+        config
+    }
+
+    /// Replays the recorded assignments: lookup tables through `assign_table`, everything
+    /// else in one region at row 0, with copy constraints in their recorded order.
+    pub fn assign(
+        &self,
+        config: &LayoutConfig,
+        mut layouter: impl Layouter<Fp>,
+    ) -> Result<(), Error> {
+        let tables = self.table_columns();
+        if !tables.is_empty() {
+            layouter.assign_table(
+                || "layout tables",
+                |mut table| {
+                    for op in &self.ops {
+                        if let Op::Fixed { column, row, value } = op {
+                            if tables.contains(column) {
+                                table.assign_cell(
+                                    || "",
+                                    config.table_column(*column),
+                                    *row as usize,
+                                    || Value::known(field(value)),
+                                )?;
+                            }
+                        }
+                    }
+                    Ok(())
+                },
+            )?;
+        }
+
+        layouter.assign_region(
+            || "layout",
+            |mut region| {
+                let mut cells = BTreeMap::new();
+                for op in &self.ops {
+                    match op {
+                        Op::Fixed { column, .. } if tables.contains(column) => {}
+                        Op::Fixed { column, row, value } => {
+                            let cell = region.assign_fixed(
+                                || "",
+                                config.fixed_column(*column),
+                                *row as usize,
+                                || Value::known(field(value)),
+                            )?;
+                            cells.insert(
+                                CellRef { column: ColumnRef::Fixed(*column), row: *row },
+                                cell.cell(),
+                            );
+                        }
+                        Op::Enable { selector, row } => {
+                            config.selectors[*selector as usize]
+                                .enable(&mut region, *row as usize)?;
+                        }
+                        Op::Copy(left, right) => match (left.column, right.column) {
+                            (ColumnRef::Instance(_), ColumnRef::Advice(_)) => {
+                                copy_instance(&mut region, config, &mut cells, *left, *right)?
+                            }
+                            (ColumnRef::Advice(_), ColumnRef::Instance(_)) => {
+                                copy_instance(&mut region, config, &mut cells, *right, *left)?
+                            }
+                            _ => {
+                                let left = cell(&mut region, config, &mut cells, *left)?;
+                                let right = cell(&mut region, config, &mut cells, *right)?;
+                                region.constrain_equal(left, right)?;
+                            }
+                        },
+                    }
+                }
+                Ok(())
+            },
+        )
+    }
+}
+
+/// The cell at `at`, assigning it first if the replay has not touched it yet. Advice
+/// values are unknown during keygen; unassigned fixed cells hold zero.
+fn cell(
+    region: &mut Region<'_, Fp>,
+    config: &LayoutConfig,
+    cells: &mut BTreeMap<CellRef, Cell>,
+    at: CellRef,
+) -> Result<Cell, Error> {
+    if let Some(cell) = cells.get(&at) {
+        return Ok(*cell);
+    }
+    let cell = match at.column {
+        ColumnRef::Advice(index) => region
+            .assign_advice(
+                || "",
+                config.advice[index as usize],
+                at.row as usize,
+                Value::<Fp>::unknown,
+            )?
+            .cell(),
+        ColumnRef::Fixed(index) => region
+            .assign_fixed(
+                || "",
+                config.fixed_column(index),
+                at.row as usize,
+                || Value::known(Fp::ZERO),
+            )?
+            .cell(),
+        ColumnRef::Instance(_) => unreachable!("instance cells are copied via copy_instance"),
+    };
+    cells.insert(at, cell);
+    Ok(cell)
+}
+
+/// Copies the instance cell `instance` into the advice cell `target`.
+fn copy_instance(
+    region: &mut Region<'_, Fp>,
+    config: &LayoutConfig,
+    cells: &mut BTreeMap<CellRef, Cell>,
+    instance: CellRef,
+    target: CellRef,
+) -> Result<(), Error> {
+    let (ColumnRef::Instance(column), ColumnRef::Advice(advice)) = (instance.column, target.column)
+    else {
+        unreachable!("callers pass an instance and an advice cell")
+    };
+    let assigned = region.assign_advice_from_instance(
+        || "",
+        config.instance[column as usize],
+        instance.row as usize,
+        config.advice[advice as usize],
+        target.row as usize,
+    )?;
+    cells.insert(target, assigned.cell());
+    Ok(())
+}
+
+/// How far [`CircuitLayout::configure`] has got through the recorded query lists.
+#[derive(Default)]
+struct QuerySchedule {
+    advice_and_instance_registered: bool,
+    next_fixed_query: usize,
+}
+
+impl QuerySchedule {
+    /// Registers all advice and instance queries if not done yet, and the fixed queries
+    /// up to (excluding) position `fixed_until`. Table columns are skipped: only
+    /// `lookup` can query them.
+    fn register(
+        &mut self,
+        layout: &CircuitLayout,
+        config: &LayoutConfig,
+        cells: &mut VirtualCells<'_, Fp>,
+        fixed_until: usize,
+    ) {
+        if !self.advice_and_instance_registered {
+            for &(column, rotation) in &layout.advice_queries {
+                cells.query_advice(config.advice[column as usize], Rotation(rotation));
+            }
+            for &(column, rotation) in &layout.instance_queries {
+                cells.query_instance(config.instance[column as usize], Rotation(rotation));
+            }
+            self.advice_and_instance_registered = true;
+        }
+        while self.next_fixed_query < fixed_until {
+            let column = layout.fixed_queries[self.next_fixed_query];
+            if let FixedColumn::Fixed(column) = config.fixed[column as usize] {
+                cells.query_fixed(column);
+            }
+            self.next_fixed_query += 1;
+        }
+    }
+}
+
+/// A fixed column replayed either as an ordinary fixed column or as a lookup table.
+#[derive(Debug, Clone, Copy)]
+enum FixedColumn {
+    Fixed(Column<Fixed>),
+    Table(TableColumn),
+}
+
+/// Columns and selectors allocated by [`CircuitLayout::configure`].
+#[derive(Debug, Clone)]
+pub struct LayoutConfig {
+    advice: Vec<Column<Advice>>,
+    fixed: Vec<FixedColumn>,
+    instance: Vec<Column<Instance>>,
+    selectors: Vec<Selector>,
+}
+
+impl LayoutConfig {
+    fn fixed_column(&self, index: u32) -> Column<Fixed> {
+        match self.fixed[index as usize] {
+            FixedColumn::Fixed(column) => column,
+            FixedColumn::Table(_) => unreachable!("validated layouts never use tables as fixed"),
+        }
+    }
+
+    fn table_column(&self, index: u32) -> TableColumn {
+        match self.fixed[index as usize] {
+            FixedColumn::Table(column) => column,
+            FixedColumn::Fixed(_) => unreachable!("lookup tables are allocated as tables"),
+        }
+    }
+
+    fn expression(&self, expr: &Expr, cells: &mut VirtualCells<'_, Fp>) -> Expression<Fp> {
+        match expr {
+            Expr::Constant(value) => Expression::Constant(field(value)),
+            Expr::Selector(selector) => cells.query_selector(self.selectors[*selector as usize]),
+            Expr::Fixed(column) => cells.query_fixed(self.fixed_column(*column)),
+            Expr::Advice(column, rotation) => {
+                cells.query_advice(self.advice[*column as usize], Rotation(*rotation))
+            }
+            Expr::Instance(column, rotation) => {
+                cells.query_instance(self.instance[*column as usize], Rotation(*rotation))
+            }
+            Expr::Negated(inner) => Expression::Negated(Box::new(self.expression(inner, cells))),
+            Expr::Sum(a, b) => Expression::Sum(
+                Box::new(self.expression(a, cells)),
+                Box::new(self.expression(b, cells)),
+            ),
+            Expr::Product(a, b) => Expression::Product(
+                Box::new(self.expression(a, cells)),
+                Box::new(self.expression(b, cells)),
+            ),
+            Expr::Scaled(inner, value) => {
+                Expression::Scaled(Box::new(self.expression(inner, cells)), field(value))
+            }
+        }
+    }
+}
+
+impl Expr {
+    /// Calls `f` on this node and every node below it.
+    fn visit(&self, f: &mut impl FnMut(&Expr)) {
+        f(self);
+        match self {
+            Expr::Negated(inner) | Expr::Scaled(inner, _) => inner.visit(f),
+            Expr::Sum(a, b) | Expr::Product(a, b) => {
+                a.visit(f);
+                b.visit(f);
+            }
+            _ => {}
+        }
+    }
+}
+
+/// A serialized [`CircuitLayout`] compiled into a verifier, e.g. via `include_bytes!`.
+pub trait EmbeddedLayout {
+    /// The bytes written by [`CircuitLayout::to_bytes`].
+    fn layout_bytes() -> &'static [u8];
+}
+
+/// A circuit that replays the layout embedded as `L`, for generating its verifying key.
+///
+/// It has no witnesses, so it can only be used with `keygen_vk`.
+pub struct LayoutCircuit<L> {
+    layout: CircuitLayout,
+    _embedded: PhantomData<fn() -> L>,
+}
+
+impl<L: EmbeddedLayout> LayoutCircuit<L> {
+    /// Deserializes and validates `L`'s layout.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`LayoutError::Invalid`] if the embedded bytes are not a valid layout.
+    pub fn load() -> Result<Self, LayoutError> {
+        Ok(LayoutCircuit {
+            layout: CircuitLayout::from_bytes(L::layout_bytes())?,
+            _embedded: PhantomData,
+        })
+    }
+
+    /// The embedded layout.
+    pub fn layout(&self) -> &CircuitLayout {
+        &self.layout
+    }
+}
+
+impl<L> Clone for LayoutCircuit<L> {
+    fn clone(&self) -> Self {
+        LayoutCircuit { layout: self.layout.clone(), _embedded: PhantomData }
+    }
+}
+
+impl<L: EmbeddedLayout> Circuit<Fp> for LayoutCircuit<L> {
+    type Config = LayoutConfig;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        self.clone()
+    }
+
+    fn configure(meta: &mut ConstraintSystem<Fp>) -> LayoutConfig {
+        // `load` already parsed these bytes, so a failure here means `L` changed them
+        CircuitLayout::from_bytes(L::layout_bytes())
+            .expect("embedded layout is validated by LayoutCircuit::load")
+            .configure(meta)
+    }
+
+    fn synthesize(&self, config: LayoutConfig, layouter: impl Layouter<Fp>) -> Result<(), Error> {
+        self.layout.assign(&config, layouter)
+    }
+}
+
+/// A field element from a validated repr.
+fn field(repr: &[u8; 32]) -> Fp {
+    Option::from(Fp::from_repr(*repr)).expect("validated layouts hold canonical field elements")
+}
+
+fn invalid(message: String) -> LayoutError {
+    LayoutError::Invalid(message)
+}
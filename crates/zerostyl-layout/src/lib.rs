@@ -0,0 +1,108 @@
+//! Serializable halo2 circuit layouts for ZeroStyl verifiers
+//!
+//! halo2_proofs 0.3 cannot serialize a `VerifyingKey`; the only way to obtain one is
+//! `keygen_vk` on the circuit. Verifiers generated for compiled circuits embed a
+//! [`CircuitLayout`] instead of the circuit itself:
+//!
+//! - [`record`](fn@record) captures a circuit's constraint system and keygen assignments (std
+//!   only in practice, it runs the circuit's own `configure` and `synthesize`)
+//! - [`LayoutCircuit`] replays an [`EmbeddedLayout`] in `no_std` code, so `keygen_vk`
+//!   regenerates the same verifying key without the compiler or its gadgets
+//! - [`vk_digest`] fingerprints a verifying key, so a verifier can refuse to run when the
+//!   regenerated key is not the one the layout was recorded with
+//!
+//! Public inputs cross the verifier's FFI boundary as consecutive 32-byte little-endian
+//! field reprs (see [`encode_public_inputs`]).
+
+#![cfg_attr(not(test), no_std)]
+
+extern crate alloc;
+
+mod debug_repr;
+pub mod layout;
+pub mod record;
+
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt;
+
+use halo2_proofs::{
+    pasta::{group::ff::PrimeField, EqAffine, Fp},
+    plonk::VerifyingKey,
+    transcript::{Blake2bWrite, Challenge255, EncodedChallenge, Transcript},
+};
+
+pub use layout::{CircuitLayout, EmbeddedLayout, LayoutCircuit, LayoutConfig};
+pub use record::record;
+
+/// `verify()` return code: the proof is valid.
+pub const VERIFY_OK: i32 = 0;
+/// `verify()` return code: the proof was rejected, including malformed proof or input bytes.
+pub const VERIFY_REJECTED: i32 = -1;
+/// `verify()` return code: the embedded verifying data is unusable (corrupt layout or
+/// params, or a regenerated verifying key that does not match the embedded digest).
+pub const VERIFY_UNAVAILABLE: i32 = -2;
+
+/// Size in bytes of one encoded public input.
+pub const PUBLIC_INPUT_LEN: usize = 32;
+
+/// Errors from recording or loading a [`CircuitLayout`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LayoutError {
+    /// The circuit uses something a layout cannot express.
+    Unsupported(String),
+    /// The layout bytes are malformed or inconsistent.
+    Invalid(String),
+    /// The circuit failed to synthesize.
+    Synthesis(String),
+}
+
+impl fmt::Display for LayoutError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LayoutError::Unsupported(message) => write!(f, "Unsupported circuit: {}", message),
+            LayoutError::Invalid(message) => write!(f, "Invalid layout: {}", message),
+            LayoutError::Synthesis(message) => write!(f, "Synthesis failed: {}", message),
+        }
+    }
+}
+
+/// Fingerprint of a verifying key: its transcript representation, hashed into a Blake2b
+/// transcript. Covers the domain, constraint system, fixed and permutation commitments.
+pub fn vk_digest(vk: &VerifyingKey<EqAffine>) -> [u8; 32] {
+    let mut transcript = Blake2bWrite::<_, EqAffine, Challenge255<_>>::init(Vec::new());
+    vk.hash_into(&mut transcript).expect("hashing into an in-memory transcript cannot fail");
+    transcript.squeeze_challenge().get_scalar().to_repr()
+}
+
+/// Encodes public inputs for a generated verifier's `verify()` export.
+pub fn encode_public_inputs(inputs: &[Fp]) -> Vec<u8> {
+    inputs.iter().flat_map(|fp| fp.to_repr()).collect()
+}
+
+/// Inverse of [`encode_public_inputs`]; `None` on a wrong length or a non-canonical repr.
+pub fn decode_public_inputs(bytes: &[u8], count: usize) -> Option<Vec<Fp>> {
+    if bytes.len() != count * PUBLIC_INPUT_LEN {
+        return None;
+    }
+    bytes
+        .chunks_exact(PUBLIC_INPUT_LEN)
+        .map(|chunk| Option::from(Fp::from_repr(chunk.try_into().ok()?)))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use halo2_proofs::arithmetic::Field;
+
+    #[test]
+    fn test_public_inputs_roundtrip() {
+        let inputs = [Fp::from(7), -Fp::ONE];
+        let bytes = encode_public_inputs(&inputs);
+        assert_eq!(bytes.len(), 2 * PUBLIC_INPUT_LEN);
+        assert_eq!(decode_public_inputs(&bytes, 2), Some(inputs.to_vec()));
+        assert_eq!(decode_public_inputs(&bytes, 1), None);
+        assert_eq!(decode_public_inputs(&[0xff; 32], 1), None);
+    }
+}
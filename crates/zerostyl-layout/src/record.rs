@@ -0,0 +1,695 @@
+//! Recording a circuit's layout
+//!
+//! [`record`] runs a circuit's `configure` and reads the resulting constraint system
+//! back from its `Debug` output (see the `debug_repr` module), then runs its floor planner
+//! against a `Recorder` that keeps the assignments keygen reads.
+//!
+//! Before returning, the layout is replayed and compared with the original: the pinned
+//! constraint system (what the verifying key hashes) must print identically, and the
+//! replayed assignments must leave the same fixed cells, selector activations and copy
+//! constraints. A circuit the layout cannot reproduce is reported as
+//! [`LayoutError::Unsupported`] instead of yielding a verifier for a different key.
+
+use alloc::boxed::Box;
+use alloc::collections::{BTreeMap, BTreeSet};
+use alloc::format;
+use alloc::string::String;
+use alloc::vec;
+use alloc::vec::Vec;
+
+use halo2_proofs::{
+    circuit::{Layouter, SimpleFloorPlanner, Value},
+    pasta::{group::ff::PrimeField, Fp},
+    plonk::{
+        Any, Assigned, Assignment, Circuit, Column, ColumnType, ConstraintSystem, Error, Fixed,
+        FloorPlanner, Instance, Selector,
+    },
+};
+
+use crate::debug_repr::Repr;
+use crate::layout::{CellRef, CircuitLayout, ColumnRef, Expr, LayoutConfig, Lookup, Op};
+use crate::LayoutError;
+
+/// Records the layout of `circuit` for the circuit size `k`.
+///
+/// `circuit` may be witness-free; keygen never reads advice values.
+///
+/// # Errors
+///
+/// Returns [`LayoutError::Synthesis`] if the circuit fails to synthesize, and
+/// [`LayoutError::Unsupported`] if its constraint system cannot be read or the recorded
+/// layout does not replay to the same constraint system and assignments.
+pub fn record<C: Circuit<Fp>>(k: u32, circuit: &C) -> Result<CircuitLayout, LayoutError> {
+    let mut meta = ConstraintSystem::<Fp>::default();
+    let config = C::configure(&mut meta);
+    let mut layout = read_constraint_system(k, &meta).map_err(|e| {
+        LayoutError::Unsupported(format!("cannot read the constraint system: {}", e))
+    })?;
+
+    let constants = fixed_columns(layout.num_fixed_columns, &layout.constants);
+    let mut recorder = Recorder::default();
+    C::FloorPlanner::synthesize(&mut recorder, circuit, config, constants).map_err(synthesis)?;
+    for (&selector, &simple) in &recorder.selectors {
+        layout.selectors[selector as usize] = simple;
+    }
+    layout.ops = recorder.ops();
+    layout.validate().map_err(|e| match e {
+        LayoutError::Invalid(message) => LayoutError::Unsupported(message),
+        other => other,
+    })?;
+
+    let mut replayed = ConstraintSystem::<Fp>::default();
+    let replay_config = layout.configure(&mut replayed);
+    if format!("{:?}", replayed.pinned()) != format!("{:?}", meta.pinned()) {
+        return Err(LayoutError::Unsupported(
+            "the replayed constraint system differs from the circuit's".into(),
+        ));
+    }
+
+    let mut replay = Recorder::default();
+    SimpleFloorPlanner::synthesize(&mut replay, &Replay(&layout), replay_config, vec![])
+        .map_err(synthesis)?;
+    let usable_rows = (1usize << k).saturating_sub(meta.blinding_factors() + 1);
+    if recorder.keygen_state(usable_rows) != replay.keygen_state(usable_rows) {
+        return Err(LayoutError::Unsupported(
+            "the replayed assignments differ from the circuit's".into(),
+        ));
+    }
+
+    Ok(layout)
+}
+
+fn synthesis(error: Error) -> LayoutError {
+    LayoutError::Synthesis(format!("{:?}", error))
+}
+
+/// The fixed columns at `indices`, for handing the layouter the circuit's constant columns.
+/// Columns are plain indices, so allocating them in a scratch system yields equal values.
+fn fixed_columns(count: u32, indices: &[u32]) -> Vec<Column<Fixed>> {
+    let mut scratch = ConstraintSystem::<Fp>::default();
+    let columns: Vec<Column<Fixed>> = (0..count).map(|_| scratch.fixed_column()).collect();
+    indices.iter().map(|&index| columns[index as usize]).collect()
+}
+
+/// [`CircuitLayout::assign`] as a circuit, so a floor planner can run it.
+struct Replay<'a>(&'a CircuitLayout);
+
+impl Circuit<Fp> for Replay<'_> {
+    type Config = LayoutConfig;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Replay(self.0)
+    }
+
+    fn configure(_meta: &mut ConstraintSystem<Fp>) -> LayoutConfig {
+        unreachable!("replays are configured by CircuitLayout::configure")
+    }
+
+    fn synthesize(&self, config: LayoutConfig, layouter: impl Layouter<Fp>) -> Result<(), Error> {
+        self.0.assign(&config, layouter)
+    }
+}
+
+/// An assignment as the [`Recorder`] saw it.
+#[derive(Debug)]
+enum Event {
+    Op(Op),
+    /// `fill_from_row`, which table layouters use for the unused rows of a table column.
+    Fill {
+        column: u32,
+        row: u32,
+        value: [u8; 32],
+    },
+}
+
+/// Fixed cells, selector activations and copies, as keygen would end up with them.
+#[derive(Debug, PartialEq, Eq)]
+struct KeygenState {
+    /// Non-zero fixed cells; unassigned cells are zero.
+    fixed: BTreeMap<(u32, u32), [u8; 32]>,
+    enabled: BTreeSet<(u32, u32)>,
+    /// Copies in order, each pair in a canonical order.
+    copies: Vec<(CellRef, CellRef)>,
+}
+
+/// An [`Assignment`] backend that records what keygen keeps.
+#[derive(Debug, Default)]
+struct Recorder {
+    events: Vec<Event>,
+    /// Whether each enabled selector is simple.
+    selectors: BTreeMap<u32, bool>,
+}
+
+impl Recorder {
+    /// The recorded assignments, without table fills (the replay regenerates those).
+    fn ops(&self) -> Vec<Op> {
+        self.events
+            .iter()
+            .filter_map(|event| match event {
+                Event::Op(op) => Some(op.clone()),
+                Event::Fill { .. } => None,
+            })
+            .collect()
+    }
+
+    fn keygen_state(&self, usable_rows: usize) -> KeygenState {
+        let mut state =
+            KeygenState { fixed: BTreeMap::new(), enabled: BTreeSet::new(), copies: Vec::new() };
+        let mut set = |column: u32, row: u32, value: [u8; 32]| {
+            if value == [0; 32] {
+                state.fixed.remove(&(column, row));
+            } else {
+                state.fixed.insert((column, row), value);
+            }
+        };
+        for event in &self.events {
+            match event {
+                Event::Op(Op::Fixed { column, row, value }) => set(*column, *row, *value),
+                Event::Fill { column, row, value } => {
+                    for row in *row..usable_rows as u32 {
+                        set(*column, row, *value);
+                    }
+                }
+                Event::Op(Op::Enable { selector, row }) => {
+                    state.enabled.insert((*selector, *row));
+                }
+                Event::Op(Op::Copy(left, right)) => {
+                    state.copies.push((*left.min(right), *left.max(right)));
+                }
+            }
+        }
+        state
+    }
+}
+
+/// The column's index is crate-private; `Debug` prints `Column { index, column_type }`.
+fn column_ref<C: ColumnType>(column: Column<C>) -> Result<ColumnRef, Error> {
+    Repr::parse(&format!("{:?}", column))
+        .and_then(|repr| self::column(&repr))
+        .map_err(|_| Error::Synthesis)
+}
+
+/// Index of a fixed column, see [`column_ref`].
+fn fixed_index(column: Column<Fixed>) -> Result<u32, Error> {
+    match column_ref(column)? {
+        ColumnRef::Fixed(index) => Ok(index),
+        _ => Err(Error::Synthesis),
+    }
+}
+
+/// The value of an assignment; keygen needs fixed values to be known.
+fn known(value: Value<Assigned<Fp>>) -> Result<[u8; 32], Error> {
+    let mut repr = None;
+    value.map(|value| repr = Some(value.evaluate().to_repr()));
+    repr.ok_or(Error::Synthesis)
+}
+
+impl Assignment<Fp> for Recorder {
+    fn enter_region<NR, N>(&mut self, _: N)
+    where
+        NR: Into<String>,
+        N: FnOnce() -> NR,
+    {
+    }
+
+    fn exit_region(&mut self) {}
+
+    fn enable_selector<A, AR>(&mut self, _: A, selector: &Selector, row: usize) -> Result<(), Error>
+    where
+        A: FnOnce() -> AR,
+        AR: Into<String>,
+    {
+        // The index is crate-private; `Debug` prints `Selector(index, simple)`
+        let repr = Repr::parse(&format!("{:?}", selector)).map_err(|_| Error::Synthesis)?;
+        let fields = repr.tuple("Selector").map_err(|_| Error::Synthesis)?;
+        let (Some(index), Some(simple)) = (fields.first(), fields.get(1)) else {
+            return Err(Error::Synthesis);
+        };
+        let index = index.as_u32().map_err(|_| Error::Synthesis)?;
+        self.selectors.insert(index, simple.as_bool().map_err(|_| Error::Synthesis)?);
+        self.events.push(Event::Op(Op::Enable { selector: index, row: row as u32 }));
+        Ok(())
+    }
+
+    fn query_instance(&self, _: Column<Instance>, _: usize) -> Result<Value<Fp>, Error> {
+        Ok(Value::unknown())
+    }
+
+    fn assign_advice<V, VR, A, AR>(
+        &mut self,
+        _: A,
+        _: Column<halo2_proofs::plonk::Advice>,
+        _: usize,
+        _: V,
+    ) -> Result<(), Error>
+    where
+        V: FnOnce() -> Value<VR>,
+        VR: Into<Assigned<Fp>>,
+        A: FnOnce() -> AR,
+        AR: Into<String>,
+    {
+        Ok(())
+    }
+
+    fn assign_fixed<V, VR, A, AR>(
+        &mut self,
+        _: A,
+        column: Column<Fixed>,
+        row: usize,
+        to: V,
+    ) -> Result<(), Error>
+    where
+        V: FnOnce() -> Value<VR>,
+        VR: Into<Assigned<Fp>>,
+        A: FnOnce() -> AR,
+        AR: Into<String>,
+    {
+        let value = known(to().map(Into::into))?;
+        self.events.push(Event::Op(Op::Fixed {
+            column: fixed_index(column)?,
+            row: row as u32,
+            value,
+        }));
+        Ok(())
+    }
+
+    fn copy(
+        &mut self,
+        left_column: Column<Any>,
+        left_row: usize,
+        right_column: Column<Any>,
+        right_row: usize,
+    ) -> Result<(), Error> {
+        self.events.push(Event::Op(Op::Copy(
+            CellRef { column: column_ref(left_column)?, row: left_row as u32 },
+            CellRef { column: column_ref(right_column)?, row: right_row as u32 },
+        )));
+        Ok(())
+    }
+
+    fn fill_from_row(
+        &mut self,
+        column: Column<Fixed>,
+        row: usize,
+        to: Value<Assigned<Fp>>,
+    ) -> Result<(), Error> {
+        let value = known(to)?;
+        self.events.push(Event::Fill { column: fixed_index(column)?, row: row as u32, value });
+        Ok(())
+    }
+
+    fn push_namespace<NR, N>(&mut self, _: N)
+    where
+        NR: Into<String>,
+        N: FnOnce() -> NR,
+    {
+    }
+
+    fn pop_namespace(&mut self, _: Option<String>) {}
+}
+
+/// Reads the constraint system from its `Debug` output.
+fn read_constraint_system(k: u32, meta: &ConstraintSystem<Fp>) -> Result<CircuitLayout, String> {
+    let repr = Repr::parse(&format!("{:?}", meta))?;
+    let count = |name: &str| repr.field(name)?.as_u32();
+
+    let mut selectors = vec![true; count("num_selectors")? as usize];
+    let mut constraints = Vec::new();
+    for gate in repr.field("gates")?.items()? {
+        for poly in gate.field("polys")?.items()? {
+            constraints.push(expr(poly, &mut selectors)?);
+        }
+    }
+    let mut lookups = Vec::new();
+    for lookup in repr.field("lookups")?.items()? {
+        let inputs = lookup
+            .field("input_expressions")?
+            .items()?
+            .iter()
+            .map(|input| expr(input, &mut selectors))
+            .collect::<Result<_, _>>()?;
+        let tables = lookup
+            .field("table_expressions")?
+            .items()?
+            .iter()
+            .map(|table| match expr(table, &mut selectors)? {
+                Expr::Fixed(column) => Ok(column),
+                _ => Err(String::from("lookup table is not a fixed column")),
+            })
+            .collect::<Result<_, _>>()?;
+        lookups.push(Lookup { inputs, tables });
+    }
+
+    let queries = |name: &str| -> Result<Vec<(ColumnRef, i32)>, String> {
+        repr.field(name)?
+            .items()?
+            .iter()
+            .map(|query| match query.items()? {
+                [column_repr, rotation_repr] => {
+                    Ok((column(column_repr)?, rotation(rotation_repr)?))
+                }
+                _ => Err(format!("malformed entry in `{}`", name)),
+            })
+            .collect()
+    };
+    let advice_queries = queries("advice_queries")?
+        .into_iter()
+        .map(|(column, rotation)| match column {
+            ColumnRef::Advice(index) => Ok((index, rotation)),
+            _ => Err(String::from("advice query on a non-advice column")),
+        })
+        .collect::<Result<_, String>>()?;
+    let instance_queries = queries("instance_queries")?
+        .into_iter()
+        .map(|(column, rotation)| match column {
+            ColumnRef::Instance(index) => Ok((index, rotation)),
+            _ => Err(String::from("instance query on a non-instance column")),
+        })
+        .collect::<Result<_, String>>()?;
+    let fixed_queries = queries("fixed_queries")?
+        .into_iter()
+        .map(|(column, rotation)| match column {
+            ColumnRef::Fixed(index) if rotation == 0 => Ok(index),
+            _ => Err(String::from("fixed query off the current row")),
+        })
+        .collect::<Result<_, String>>()?;
+
+    let permutation = repr
+        .field("permutation")?
+        .field("columns")?
+        .items()?
+        .iter()
+        .map(column)
+        .collect::<Result<_, _>>()?;
+    let constants = repr
+        .field("constants")?
+        .items()?
+        .iter()
+        .map(|constant| match column(constant)? {
+            ColumnRef::Fixed(index) => Ok(index),
+            _ => Err(String::from("constant column is not fixed")),
+        })
+        .collect::<Result<_, String>>()?;
+    let minimum_degree =
+        repr.field("minimum_degree")?.as_option()?.map(Repr::as_u32).transpose()?;
+
+    Ok(CircuitLayout {
+        k,
+        num_advice_columns: count("num_advice_columns")?,
+        num_fixed_columns: count("num_fixed_columns")?,
+        num_instance_columns: count("num_instance_columns")?,
+        selectors,
+        advice_queries,
+        instance_queries,
+        fixed_queries,
+        constraints,
+        lookups,
+        permutation,
+        constants,
+        minimum_degree,
+        ops: Vec::new(),
+    })
+}
+
+/// `Column { index, column_type }`
+fn column(repr: &Repr) -> Result<ColumnRef, String> {
+    let index = repr.field("index")?.as_u32()?;
+    match repr.field("column_type")?.token()? {
+        "Advice" => Ok(ColumnRef::Advice(index)),
+        "Fixed" => Ok(ColumnRef::Fixed(index)),
+        "Instance" => Ok(ColumnRef::Instance(index)),
+        other => Err(format!("unknown column type `{}`", other)),
+    }
+}
+
+/// `Rotation(n)`
+fn rotation(repr: &Repr) -> Result<i32, String> {
+    match repr.tuple("Rotation")? {
+        [rotation] => rotation.as_i32(),
+        _ => Err(String::from("malformed rotation")),
+    }
+}
+
+/// The arguments of `name(..)`, which must number `N`.
+fn args<'a, const N: usize>(repr: &'a Repr, name: &str) -> Result<&'a [Repr; N], String> {
+    repr.tuple(name)?.try_into().map_err(|_| format!("`{}` takes {} arguments", name, N))
+}
+
+/// An `Expression`, noting whether each selector it queries is simple.
+fn expr(repr: &Repr, selectors: &mut [bool]) -> Result<Expr, String> {
+    let mut boxed = |repr: &Repr| expr(repr, selectors).map(Box::new);
+    Ok(match repr.name() {
+        Some("Constant") => {
+            let [value] = args(repr, "Constant")?;
+            Expr::Constant(value.as_field_repr()?)
+        }
+        Some("Selector") => {
+            let [selector] = args(repr, "Selector")?;
+            let [index, simple] = args(selector, "Selector")?;
+            let index = index.as_u32()?;
+            *selectors.get_mut(index as usize).ok_or("selector out of range")? =
+                simple.as_bool()?;
+            Expr::Selector(index)
+        }
+        Some("Fixed") => {
+            if rotation(repr.field("rotation")?)? != 0 {
+                return Err(String::from("fixed query off the current row"));
+            }
+            Expr::Fixed(repr.field("column_index")?.as_u32()?)
+        }
+        Some("Advice") => {
+            Expr::Advice(repr.field("column_index")?.as_u32()?, rotation(repr.field("rotation")?)?)
+        }
+        Some("Instance") => Expr::Instance(
+            repr.field("column_index")?.as_u32()?,
+            rotation(repr.field("rotation")?)?,
+        ),
+        Some("Negated") => {
+            let [inner] = args(repr, "Negated")?;
+            Expr::Negated(boxed(inner)?)
+        }
+        Some("Sum") => {
+            let [a, b] = args(repr, "Sum")?;
+            Expr::Sum(boxed(a)?, boxed(b)?)
+        }
+        Some("Product") => {
+            let [a, b] = args(repr, "Product")?;
+            Expr::Product(boxed(a)?, boxed(b)?)
+        }
+        Some("Scaled") => {
+            let [inner, scalar] = args(repr, "Scaled")?;
+            Expr::Scaled(boxed(inner)?, scalar.as_field_repr()?)
+        }
+        other => return Err(format!("unknown expression {:?}", other)),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{vk_digest, EmbeddedLayout, LayoutCircuit};
+    use halo2_proofs::{
+        arithmetic::Field,
+        circuit::{AssignedCell, Layouter, SimpleFloorPlanner, Value},
+        pasta::EqAffine,
+        plonk::{keygen_vk, Advice, TableColumn},
+        poly::{commitment::Params, Rotation},
+    };
+    use std::sync::OnceLock;
+
+    /// Exercises everything a layout carries: simple and complex selectors, a rotated
+    /// query, constants, a lookup table, copies to the instance column and a fixed column.
+    #[derive(Clone, Default)]
+    struct SampleCircuit;
+
+    #[derive(Clone)]
+    struct SampleConfig {
+        a: Column<Advice>,
+        b: Column<Advice>,
+        coeff: Column<Fixed>,
+        table: TableColumn,
+        instance: Column<Instance>,
+        mul: Selector,
+        lookup: Selector,
+    }
+
+    impl Circuit<Fp> for SampleCircuit {
+        type Config = SampleConfig;
+        type FloorPlanner = SimpleFloorPlanner;
+
+        fn without_witnesses(&self) -> Self {
+            SampleCircuit
+        }
+
+        fn configure(meta: &mut ConstraintSystem<Fp>) -> SampleConfig {
+            let a = meta.advice_column();
+            let b = meta.advice_column();
+            let coeff = meta.fixed_column();
+            let constants = meta.fixed_column();
+            let table = meta.lookup_table_column();
+            let instance = meta.instance_column();
+            let mul = meta.selector();
+            let lookup = meta.complex_selector();
+            meta.enable_equality(a);
+            meta.enable_equality(instance);
+            meta.enable_constant(constants);
+
+            meta.lookup(|cells| {
+                let s = cells.query_selector(lookup);
+                let b = cells.query_advice(b, Rotation::cur());
+                vec![(s * b, table)]
+            });
+            meta.create_gate("scaled product", |cells| {
+                let s = cells.query_selector(mul);
+                let a_cur = cells.query_advice(a, Rotation::cur());
+                let a_next = cells.query_advice(a, Rotation::next());
+                let b = cells.query_advice(b, Rotation::cur());
+                let coeff = cells.query_fixed(coeff);
+                vec![s * (a_cur * b * coeff * Fp::from(3) - a_next)]
+            });
+
+            SampleConfig { a, b, coeff, table, instance, mul, lookup }
+        }
+
+        fn synthesize(
+            &self,
+            config: SampleConfig,
+            mut layouter: impl Layouter<Fp>,
+        ) -> Result<(), Error> {
+            layouter.assign_table(
+                || "small values",
+                |mut table| {
+                    for value in 0..8 {
+                        table.assign_cell(
+                            || "value",
+                            config.table,
+                            value,
+                            || Value::known(Fp::from(value as u64)),
+                        )?;
+                    }
+                    Ok(())
+                },
+            )?;
+            let out: AssignedCell<Fp, Fp> = layouter.assign_region(
+                || "product",
+                |mut region| {
+                    config.mul.enable(&mut region, 0)?;
+                    config.lookup.enable(&mut region, 0)?;
+                    region.assign_advice_from_constant(|| "a", config.a, 0, Fp::from(2))?;
+                    region.assign_advice(|| "b", config.b, 0, || Value::known(Fp::from(5)))?;
+                    region.assign_fixed(|| "coeff", config.coeff, 0, || Value::known(Fp::ONE))?;
+                    region.assign_advice(|| "out", config.a, 1, || Value::known(Fp::from(30)))
+                },
+            )?;
+            layouter.constrain_instance(out.cell(), config.instance, 0)
+        }
+    }
+
+    fn sample_layout() -> &'static [u8] {
+        static LAYOUT: OnceLock<Vec<u8>> = OnceLock::new();
+        LAYOUT.get_or_init(|| record(6, &SampleCircuit).unwrap().to_bytes())
+    }
+
+    struct Sample;
+
+    impl EmbeddedLayout for Sample {
+        fn layout_bytes() -> &'static [u8] {
+            sample_layout()
+        }
+    }
+
+    #[test]
+    fn test_replayed_layout_regenerates_the_verifying_key() {
+        let params = Params::<EqAffine>::new(6);
+        let original = keygen_vk(&params, &SampleCircuit).unwrap();
+        let replayed = keygen_vk(&params, &LayoutCircuit::<Sample>::load().unwrap()).unwrap();
+        assert_eq!(vk_digest(&replayed), vk_digest(&original));
+    }
+
+    /// Everything `record` parses: the constraint system, a column and a selector.
+    #[test]
+    fn test_debug_format() {
+        let mut meta = ConstraintSystem::<Fp>::default();
+        let config = SampleCircuit::configure(&mut meta);
+        let actual = format!("{:?}\n{:?}\n{:?}\n", meta, config.coeff, config.mul);
+
+        let path = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/snapshots/sample_debug.txt");
+        if std::env::var_os("REGEN_LAYOUT_SNAPSHOTS").is_some() {
+            std::fs::write(path, &actual).expect("snapshot writable");
+            return;
+        }
+        let expected = std::fs::read_to_string(path).expect("snapshot readable");
+        assert!(
+            expected.replace("\r\n", "\n") == actual,
+            "halo2_proofs changed its Debug output: adapt debug_repr and record before moving \
+             the pin, then run with REGEN_LAYOUT_SNAPSHOTS=1\n{}",
+            actual
+        );
+    }
+
+    #[test]
+    fn test_layout_bytes_roundtrip() {
+        let layout = CircuitLayout::from_bytes(sample_layout()).unwrap();
+        assert_eq!(layout.k(), 6);
+        assert_eq!(layout.num_instance_columns(), 1);
+        assert_eq!(layout.selectors, vec![true, false]);
+        assert_eq!(layout.lookups.len(), 1);
+        assert_eq!(layout.to_bytes(), sample_layout());
+    }
+
+    #[test]
+    fn test_rejects_corrupt_layouts() {
+        let mut bytes = sample_layout().to_vec();
+        bytes[0] = 0xff;
+        assert!(matches!(CircuitLayout::from_bytes(&bytes), Err(LayoutError::Invalid(_))));
+        assert!(CircuitLayout::from_bytes(&sample_layout()[..10]).is_err());
+
+        let mut layout = CircuitLayout::from_bytes(sample_layout()).unwrap();
+        layout.ops.push(Op::Enable { selector: 9, row: 0 });
+        assert!(matches!(
+            CircuitLayout::from_bytes(&layout.to_bytes()),
+            Err(LayoutError::Invalid(message)) if message.contains("selector 9")
+        ));
+    }
+
+    #[test]
+    fn test_different_assignments_change_the_layout() {
+        #[derive(Clone, Default)]
+        struct Shifted;
+
+        impl Circuit<Fp> for Shifted {
+            type Config = SampleConfig;
+            type FloorPlanner = SimpleFloorPlanner;
+
+            fn without_witnesses(&self) -> Self {
+                Shifted
+            }
+
+            fn configure(meta: &mut ConstraintSystem<Fp>) -> SampleConfig {
+                SampleCircuit::configure(meta)
+            }
+
+            fn synthesize(
+                &self,
+                config: SampleConfig,
+                mut layouter: impl Layouter<Fp>,
+            ) -> Result<(), Error> {
+                let coeff = config.coeff;
+                SampleCircuit.synthesize(config, layouter.namespace(|| "sample"))?;
+                layouter.assign_region(
+                    || "extra coefficient",
+                    |mut region| {
+                        region.assign_fixed(|| "coeff", coeff, 0, || Value::known(Fp::from(4)))
+                    },
+                )?;
+                Ok(())
+            }
+        }
+
+        let shifted = record(6, &Shifted).unwrap();
+        let sample = CircuitLayout::from_bytes(sample_layout()).unwrap();
+        assert_eq!(shifted.constraints, sample.constraints);
+        assert_ne!(shifted.ops, sample.ops);
+    }
+}
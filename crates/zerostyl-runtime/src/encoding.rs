@@ -0,0 +1,139 @@
+//! Canonical field-limb encodings for EVM words and addresses
+//!
+//! A 256-bit EVM word does not fit in a Pasta `Fp` element (`p < 2^255`), so ZeroStyl
+//! circuits carry `bytes32` values as two 128-bit limbs, high limb first:
+//!
+//! ```text
+//! word = hi * 2^128 + lo      hi, lo ∈ [0, 2^128)
+//! ```
+//!
+//! An `address` is 160 bits and fits in a single limb. Limbs are exchanged as
+//! [`FieldRepr`] values — the 32-byte little-endian representation produced by
+//! `Fp::to_repr` — so this crate stays independent of any curve library.
+
+use crate::{Result, ZeroStylError};
+
+/// Little-endian 32-byte representation of a field element (as `Fp::to_repr`).
+pub type FieldRepr = [u8; 32];
+
+/// Bit width of each of the two limbs of a `bytes32` value.
+pub const BYTES32_LIMB_BITS: usize = 128;
+
+/// Bit width of the single limb of an `address`.
+pub const ADDRESS_BITS: usize = 160;
+
+/// Splits a big-endian EVM word into its `[hi, lo]` 128-bit limbs.
+///
+/// # Examples
+///
+/// ```
+/// use zerostyl_runtime::encoding::{decode_bytes32, encode_bytes32};
+///
+/// let word = [0xab_u8; 32];
+/// let limbs = encode_bytes32(&word);
+/// assert_eq!(decode_bytes32(&limbs).unwrap(), word);
+/// ```
+#[must_use]
+pub fn encode_bytes32(word: &[u8; 32]) -> [FieldRepr; 2] {
+    let mut hi = [0u8; 32];
+    let mut lo = [0u8; 32];
+    for i in 0..16 {
+        hi[i] = word[15 - i];
+        lo[i] = word[31 - i];
+    }
+    [hi, lo]
+}
+
+/// Reassembles a big-endian EVM word from its `[hi, lo]` limbs.
+///
+/// # Errors
+///
+/// Returns [`ZeroStylError::SerializationError`] if a limb does not fit in 128 bits.
+pub fn decode_bytes32(limbs: &[FieldRepr; 2]) -> Result<[u8; 32]> {
+    for (name, limb) in ["high", "low"].iter().zip(limbs) {
+        check_limb_width(limb, BYTES32_LIMB_BITS / 8, name)?;
+    }
+    let mut word = [0u8; 32];
+    for i in 0..16 {
+        word[15 - i] = limbs[0][i];
+        word[31 - i] = limbs[1][i];
+    }
+    Ok(word)
+}
+
+/// Encodes a 20-byte EVM address as a single 160-bit limb.
+#[must_use]
+pub fn encode_address(address: &[u8; 20]) -> FieldRepr {
+    let mut limb = [0u8; 32];
+    for (i, byte) in address.iter().rev().enumerate() {
+        limb[i] = *byte;
+    }
+    limb
+}
+
+/// Decodes a 160-bit limb back into a 20-byte EVM address.
+///
+/// # Errors
+///
+/// Returns [`ZeroStylError::SerializationError`] if the limb does not fit in 160 bits.
+pub fn decode_address(limb: &FieldRepr) -> Result<[u8; 20]> {
+    check_limb_width(limb, ADDRESS_BITS / 8, "address")?;
+    let mut address = [0u8; 20];
+    for (i, byte) in address.iter_mut().rev().enumerate() {
+        *byte = limb[i];
+    }
+    Ok(address)
+}
+
+/// Parses a `0x`-prefixed hex string of exactly `N` bytes (e.g. a `bytes32` or `address`).
+///
+/// # Errors
+///
+/// Returns [`ZeroStylError::SerializationError`] if the prefix, length, or digits are invalid.
+pub fn parse_hex<const N: usize>(s: &str) -> Result<[u8; N]> {
+    let digits = s.strip_prefix("0x").ok_or_else(|| {
+        ZeroStylError::serialization_error(format!("hex value '{}' must start with 0x", s))
+    })?;
+    if digits.len() != 2 * N {
+        return Err(ZeroStylError::serialization_error(format!(
+            "hex value '{}' must have exactly {} hex digits, got {}",
+            s,
+            2 * N,
+            digits.len()
+        )));
+    }
+    if let Some(bad) = digits.chars().find(|c| !c.is_ascii_hexdigit()) {
+        return Err(ZeroStylError::serialization_error(format!(
+            "invalid hex digit '{}' in '{}'",
+            bad, s
+        )));
+    }
+    let mut bytes = [0u8; N];
+    for (i, byte) in bytes.iter_mut().enumerate() {
+        // Cannot fail: the digits were checked above
+        *byte = u8::from_str_radix(&digits[2 * i..2 * i + 2], 16).unwrap_or_default();
+    }
+    Ok(bytes)
+}
+
+/// Formats bytes as a lowercase `0x`-prefixed hex string.
+#[must_use]
+pub fn to_hex(bytes: &[u8]) -> String {
+    let mut s = String::with_capacity(2 + 2 * bytes.len());
+    s.push_str("0x");
+    for byte in bytes {
+        s.push_str(&format!("{:02x}", byte));
+    }
+    s
+}
+
+fn check_limb_width(limb: &FieldRepr, num_bytes: usize, name: &str) -> Result<()> {
+    if limb[num_bytes..].iter().any(|b| *b != 0) {
+        return Err(ZeroStylError::serialization_error(format!(
+            "{} limb exceeds {} bits",
+            name,
+            num_bytes * 8
+        )));
+    }
+    Ok(())
+}
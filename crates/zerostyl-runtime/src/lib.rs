@@ -9,9 +9,13 @@
 //! - [`MerklePath`] - Merkle proof path (siblings + indices)
 //! - [`RangeProofConfig`] - Bit-decomposition range proof configuration
 //! - [`CircuitConfig`] - halo2 circuit parameters (k, columns)
+//! - [`encoding`] - Field-limb encodings for EVM `bytes32` words and addresses
 
+pub mod encoding;
 pub mod error;
 pub mod types;
 
 pub use error::{Result, ZeroStylError};
-pub use types::{CircuitConfig, CommitmentHash, MerklePath, MerkleRoot, RangeProofConfig, ZkProof};
+pub use types::{
+    CircuitConfig, CommitmentHash, MerklePath, MerkleRoot, RangeChip, RangeProofConfig, ZkProof,
+};
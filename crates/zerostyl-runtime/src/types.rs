@@ -266,6 +266,18 @@ impl RangeProofConfig {
     }
 }
 
+/// Gadget a compiled circuit proves range constraints with.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RangeChip {
+    /// Bit decomposition: one row per bit, no fixed table.
+    #[default]
+    Bits,
+    /// 8-bit limbs looked up in a 256-row table: one row per byte, but the circuit is at
+    /// least as tall as the table.
+    Lookup,
+}
+
 /// Configuration for a halo2 zk-SNARK circuit.
 ///
 /// Contains parameters needed to configure and compile a halo2 circuit.
@@ -286,6 +298,8 @@ pub struct CircuitConfig {
     num_advice_columns: usize,
     num_instance_columns: usize,
     num_fixed_columns: usize,
+    #[serde(default)]
+    range_chip: RangeChip,
 }
 
 impl CircuitConfig {
@@ -301,7 +315,13 @@ impl CircuitConfig {
     /// Returns error if `k` is outside valid range [4, 28].
     pub fn minimal(k: u32) -> crate::Result<Self> {
         Self::validate_k(k)?;
-        Ok(Self { k, num_advice_columns: 1, num_instance_columns: 1, num_fixed_columns: 0 })
+        Ok(Self {
+            k,
+            num_advice_columns: 1,
+            num_instance_columns: 1,
+            num_fixed_columns: 0,
+            range_chip: RangeChip::Bits,
+        })
     }
 
     /// Creates a configuration with specified column counts.
@@ -316,7 +336,20 @@ impl CircuitConfig {
         num_fixed_columns: usize,
     ) -> crate::Result<Self> {
         Self::validate_k(k)?;
-        Ok(Self { k, num_advice_columns, num_instance_columns, num_fixed_columns })
+        Ok(Self {
+            k,
+            num_advice_columns,
+            num_instance_columns,
+            num_fixed_columns,
+            range_chip: RangeChip::Bits,
+        })
+    }
+
+    /// Returns the configuration with range constraints proven by `range_chip`.
+    #[must_use]
+    pub fn with_range_chip(mut self, range_chip: RangeChip) -> Self {
+        self.range_chip = range_chip;
+        self
     }
 
     fn validate_k(k: u32) -> crate::Result<()> {
@@ -366,4 +399,10 @@ impl CircuitConfig {
     pub fn num_fixed_columns(&self) -> usize {
         self.num_fixed_columns
     }
+
+    /// Returns the gadget range constraints are proven with.
+    #[must_use]
+    pub fn range_chip(&self) -> RangeChip {
+        self.range_chip
+    }
 }
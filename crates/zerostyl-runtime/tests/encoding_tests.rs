@@ -0,0 +1,80 @@
+//! Integration tests for the field-limb encodings in zerostyl-runtime
+
+use zerostyl_runtime::encoding::{
+    decode_address, decode_bytes32, encode_address, encode_bytes32, parse_hex, to_hex,
+};
+
+// --- bytes32 ---
+
+#[test]
+fn test_bytes32_limbs_are_high_first_little_endian() {
+    let mut word = [0u8; 32];
+    word[15] = 0x01; // lowest byte of the high half
+    word[31] = 0x02; // lowest byte of the low half
+    let [hi, lo] = encode_bytes32(&word);
+
+    assert_eq!(hi[0], 0x01);
+    assert_eq!(lo[0], 0x02);
+    assert!(hi[1..].iter().all(|b| *b == 0));
+    assert!(lo[1..].iter().all(|b| *b == 0));
+}
+
+#[test]
+fn test_bytes32_roundtrip() {
+    let words = [[0u8; 32], [0xff; 32], std::array::from_fn(|i| i as u8)];
+    for word in words {
+        assert_eq!(decode_bytes32(&encode_bytes32(&word)).unwrap(), word);
+    }
+}
+
+#[test]
+fn test_bytes32_rejects_wide_limb() {
+    let mut limbs = encode_bytes32(&[0u8; 32]);
+    limbs[1][16] = 1; // 2^128 does not fit in a 128-bit limb
+    assert!(decode_bytes32(&limbs).is_err());
+}
+
+// --- address ---
+
+#[test]
+fn test_address_roundtrip() {
+    let address: [u8; 20] = std::array::from_fn(|i| 0xa0 + i as u8);
+    let limb = encode_address(&address);
+
+    assert_eq!(limb[0], address[19]);
+    assert!(limb[20..].iter().all(|b| *b == 0));
+    assert_eq!(decode_address(&limb).unwrap(), address);
+}
+
+#[test]
+fn test_address_rejects_wide_limb() {
+    let mut limb = encode_address(&[0xff; 20]);
+    limb[20] = 1;
+    assert!(decode_address(&limb).is_err());
+}
+
+// --- hex ---
+
+#[test]
+fn test_hex_roundtrip() {
+    let s = "0x00112233445566778899aabbccddeeff00112233445566778899aabbccddeeff";
+    let word: [u8; 32] = parse_hex(s).unwrap();
+    assert_eq!(word[0], 0x00);
+    assert_eq!(word[31], 0xff);
+    assert_eq!(to_hex(&word), s);
+}
+
+#[test]
+fn test_hex_accepts_uppercase_digits() {
+    let address: [u8; 20] = parse_hex("0xABCDEF0000000000000000000000000000000001").unwrap();
+    assert_eq!(address[0], 0xab);
+    assert_eq!(address[19], 0x01);
+}
+
+#[test]
+fn test_hex_rejects_malformed_input() {
+    assert!(parse_hex::<20>("abcdef0000000000000000000000000000000001").is_err());
+    assert!(parse_hex::<20>("0x00").is_err());
+    assert!(parse_hex::<2>("0xzz00").is_err());
+    assert!(parse_hex::<2>("0x+1ff").is_err());
+}
@@ -1,7 +1,7 @@
 //! Integration tests for core types in zerostyl-runtime
 
 use zerostyl_runtime::{
-    CircuitConfig, CommitmentHash, MerklePath, MerkleRoot, RangeProofConfig, ZkProof,
+    CircuitConfig, CommitmentHash, MerklePath, MerkleRoot, RangeChip, RangeProofConfig, ZkProof,
 };
 
 // --- ZkProof ---
@@ -247,6 +247,25 @@ fn test_circuit_config_new_with_columns() {
     assert_eq!(config.num_fixed_columns(), 3);
 }
 
+#[test]
+fn test_circuit_config_range_chip() {
+    let config = CircuitConfig::minimal(9).unwrap();
+    assert_eq!(config.range_chip(), RangeChip::Bits);
+
+    let config = config.with_range_chip(RangeChip::Lookup);
+    assert_eq!(config.range_chip(), RangeChip::Lookup);
+    let json = serde_json::to_string(&config).unwrap();
+    assert!(json.contains(r#""range_chip":"lookup""#));
+    assert_eq!(serde_json::from_str::<CircuitConfig>(&json).unwrap(), config);
+
+    // Configs serialized before the option existed use bit decomposition
+    let legacy = r#"{"k":9,"num_advice_columns":1,"num_instance_columns":1,"num_fixed_columns":0}"#;
+    assert_eq!(
+        serde_json::from_str::<CircuitConfig>(legacy).unwrap().range_chip(),
+        RangeChip::Bits
+    );
+}
+
 #[test]
 fn test_circuit_config_k_too_small() {
     let result = CircuitConfig::minimal(3);
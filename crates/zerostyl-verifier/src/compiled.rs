@@ -0,0 +1,137 @@
+//! Verifier for circuits compiled by `zerostyl-compiler`
+//!
+//! Crates generated by the compiler's `WasmCodegen` embed three files: the circuit's
+//! [`CircuitLayout`](zerostyl_layout::CircuitLayout), the IPA params for its `k`, and
+//! the digest of the verifying key the compiler generated. [`CompiledVerifier::load`]
+//! regenerates the key from the layout with `keygen_vk` and refuses to verify anything
+//! if its digest differs from the embedded one.
+//!
+//! Public inputs are encoded as consecutive 32-byte little-endian field reprs (see
+//! [`encode_public_inputs`](zerostyl_layout::encode_public_inputs)).
+
+#[cfg(not(feature = "std"))]
+use alloc::{format, vec::Vec};
+
+use halo2_proofs::{
+    plonk::{keygen_vk, VerifyingKey},
+    poly::commitment::Params,
+};
+use halo2curves::pasta::EqAffine;
+use zerostyl_layout::{
+    decode_public_inputs, vk_digest, EmbeddedLayout, LayoutCircuit, VERIFY_OK, VERIFY_REJECTED,
+};
+
+use crate::verifier_nostd::{verify_with_vk_and_params, Result};
+
+/// Verifier for one compiled circuit, rebuilt from its embedded layout.
+#[derive(Debug)]
+pub struct CompiledVerifier {
+    params: Params<EqAffine>,
+    vk: VerifyingKey<EqAffine>,
+    num_public_inputs: usize,
+}
+
+impl CompiledVerifier {
+    /// Regenerates the verifying key of layout `L` with `params` and checks it against
+    /// `digest`. The circuit takes `num_public_inputs` values in its instance column.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the layout or params do not load, or if the regenerated key
+    /// does not match `digest`.
+    pub fn load<L: EmbeddedLayout>(
+        params: &[u8],
+        digest: &[u8; 32],
+        num_public_inputs: usize,
+    ) -> Result<CompiledVerifier> {
+        let circuit = LayoutCircuit::<L>::load().map_err(|e| format!("{}", e).into_bytes())?;
+        if circuit.layout().num_instance_columns() != 1 {
+            return Err(Vec::from(b"Compiled circuits have exactly one instance column"));
+        }
+        let params = Params::<EqAffine>::read(&mut &params[..])
+            .map_err(|_| Vec::from(b"Failed to deserialize params"))?;
+        if params.k() != circuit.layout().k() {
+            return Err(format!(
+                "Params are for k={}, the circuit needs k={}",
+                params.k(),
+                circuit.layout().k()
+            )
+            .into_bytes());
+        }
+
+        let vk = keygen_vk(&params, &circuit)
+            .map_err(|e| format!("Failed to generate VK: {:?}", e).into_bytes())?;
+        if &vk_digest(&vk) != digest {
+            return Err(Vec::from(b"Regenerated VK does not match the embedded digest"));
+        }
+
+        Ok(CompiledVerifier { params, vk, num_public_inputs })
+    }
+
+    /// Verifies `proof` against encoded public inputs.
+    ///
+    /// Returns [`VERIFY_OK`] or [`VERIFY_REJECTED`]; malformed proofs or inputs are
+    /// rejected.
+    pub fn verify(&self, proof: &[u8], public_inputs: &[u8]) -> i32 {
+        let Some(inputs) = decode_public_inputs(public_inputs, self.num_public_inputs) else {
+            return VERIFY_REJECTED;
+        };
+        match verify_with_vk_and_params(proof, &[inputs], &self.vk, &self.params) {
+            Ok(true) => VERIFY_OK,
+            _ => VERIFY_REJECTED,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::reference_circuit::{ReferenceCircuit, REFERENCE_K};
+    use halo2_proofs::{
+        circuit::Value,
+        plonk::{create_proof, keygen_pk},
+        transcript::{Blake2bWrite, Challenge255},
+    };
+    use halo2curves::pasta::Fp;
+    use rand::rngs::OsRng;
+    use std::sync::OnceLock;
+    use zerostyl_layout::{encode_public_inputs, record};
+
+    struct Reference;
+
+    impl EmbeddedLayout for Reference {
+        fn layout_bytes() -> &'static [u8] {
+            static LAYOUT: OnceLock<Vec<u8>> = OnceLock::new();
+            LAYOUT.get_or_init(|| {
+                record(REFERENCE_K, &ReferenceCircuit::default()).unwrap().to_bytes()
+            })
+        }
+    }
+
+    #[test]
+    fn test_compiled_verifier_checks_reference_proofs() {
+        let params = Params::<EqAffine>::new(REFERENCE_K);
+        let mut params_bytes = Vec::new();
+        params.write(&mut params_bytes).unwrap();
+        let vk = keygen_vk(&params, &ReferenceCircuit::default()).unwrap();
+        let digest = vk_digest(&vk);
+        let pk = keygen_pk(&params, vk, &ReferenceCircuit::default()).unwrap();
+
+        let circuit =
+            ReferenceCircuit { a: Value::known(Fp::from(2)), b: Value::known(Fp::from(3)) };
+        let sum = [Fp::from(5)];
+        let mut transcript = Blake2bWrite::<_, EqAffine, Challenge255<_>>::init(vec![]);
+        create_proof(&params, &pk, &[circuit], &[&[&sum[..]]], OsRng, &mut transcript).unwrap();
+        let proof = transcript.finalize();
+
+        let verifier = CompiledVerifier::load::<Reference>(&params_bytes, &digest, 1).unwrap();
+        assert_eq!(verifier.verify(&proof, &encode_public_inputs(&sum)), VERIFY_OK);
+        assert_eq!(verifier.verify(&proof, &encode_public_inputs(&[Fp::from(6)])), VERIFY_REJECTED);
+        assert_eq!(verifier.verify(&proof, &[]), VERIFY_REJECTED);
+
+        assert!(CompiledVerifier::load::<Reference>(&params_bytes, &[0; 32], 1).is_err());
+        let mut other_params = Vec::new();
+        Params::<EqAffine>::new(REFERENCE_K + 1).write(&mut other_params).unwrap();
+        assert!(CompiledVerifier::load::<Reference>(&other_params, &digest, 1).is_err());
+    }
+}
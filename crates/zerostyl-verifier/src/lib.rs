@@ -8,9 +8,10 @@
 //!
 //! The crate currently only includes the `ReferenceCircuit` (a + b = sum) as a
 //! built-in verifiable circuit. Verifying arbitrary user circuits (tx_privacy,
-//! state_mask, etc.) requires generating a verifier with the circuit's verification
-//! key embedded. Use `zerostyl-prove` for off-chain proof generation and
-//! verification of user-defined circuits.
+//! state_mask, etc.) requires generating a verifier with the circuit's layout
+//! embedded; `zerostyl_compiler::WasmCodegen` generates one for compiled circuits,
+//! built on [`CompiledVerifier`]. Use `zerostyl-prove` for off-chain proof
+//! generation and verification of user-defined circuits.
 
 #![cfg_attr(all(not(feature = "std"), not(test)), no_std)]
 
@@ -31,6 +32,7 @@ mod getrandom_custom {
 #[cfg(not(feature = "std"))]
 use alloc::vec::Vec;
 
+pub mod compiled;
 pub mod reference_circuit;
 
 #[cfg(feature = "std")]
@@ -60,6 +62,13 @@ pub fn verify(_proof: &[u8], _public_inputs: &[u8]) -> Result<bool, Vec<u8>> {
 /// Re-export verify_with_vk_and_params from verifier_nostd for direct access
 pub use verifier_nostd::verify_with_vk_and_params;
 
+pub use compiled::CompiledVerifier;
+/// Re-exports for generated verifier crates, which depend on this crate only
+pub use zerostyl_layout::{
+    encode_public_inputs, EmbeddedLayout, PUBLIC_INPUT_LEN, VERIFY_OK, VERIFY_REJECTED,
+    VERIFY_UNAVAILABLE,
+};
+
 #[cfg(feature = "std")]
 pub use verifier::verify_halo2_proof;
 